@@ -0,0 +1,289 @@
+//! Derive macros for tokio-postgres's `ToSql`, `FromSql` and `FromRow` traits.
+//!
+//! These are re-exported by `tokio-postgres` and `postgres` when their `derive` Cargo features are enabled, and should
+//! not typically be used directly.
+//!
+//! The generated code refers to the traits through the `tokio_postgres` crate. Crates which depend on `postgres`
+//! instead can specify the path to use with `#[postgres(crate = "postgres")]`.
+#![doc(html_root_url = "https://docs.rs/postgres-derive/0.1")]
+#![recursion_limit = "256"]
+#![warn(clippy::all, rust_2018_idioms, missing_docs)]
+
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use syn::{Data, DeriveInput, Error, Field, Fields, Lit, Meta, NestedMeta, Path, Type};
+
+/// Derives `ToSql` for a `#[postgres(transparent)]` newtype.
+#[proc_macro_derive(ToSql, attributes(postgres))]
+pub fn derive_to_sql(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as DeriveInput);
+    expand_to_sql(&input)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
+
+/// Derives `FromSql` for a `#[postgres(transparent)]` newtype.
+#[proc_macro_derive(FromSql, attributes(postgres))]
+pub fn derive_from_sql(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as DeriveInput);
+    expand_from_sql(&input)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
+
+/// Derives `FromRow` for a struct with named fields.
+#[proc_macro_derive(FromRow, attributes(postgres))]
+pub fn derive_from_row(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as DeriveInput);
+    expand_from_row(&input)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
+
+fn expand_to_sql(input: &DeriveInput) -> Result<proc_macro2::TokenStream, Error> {
+    let attrs = ContainerAttrs::parse(input)?;
+    let inner = transparent_field(input, &attrs)?;
+    let krate = &attrs.krate;
+    let ident = &input.ident;
+
+    Ok(quote! {
+        impl #krate::types::ToSql for #ident {
+            fn to_sql(
+                &self,
+                ty: &#krate::types::Type,
+                out: &mut ::std::vec::Vec<u8>,
+            ) -> ::std::result::Result<
+                #krate::types::IsNull,
+                ::std::boxed::Box<dyn ::std::error::Error + ::std::marker::Sync + ::std::marker::Send>,
+            > {
+                <#inner as #krate::types::ToSql>::to_sql(&self.0, ty, out)
+            }
+
+            fn accepts(ty: &#krate::types::Type) -> bool {
+                <#inner as #krate::types::ToSql>::accepts(ty)
+            }
+
+            fn to_sql_checked(
+                &self,
+                ty: &#krate::types::Type,
+                out: &mut ::std::vec::Vec<u8>,
+            ) -> ::std::result::Result<
+                #krate::types::IsNull,
+                ::std::boxed::Box<dyn ::std::error::Error + ::std::marker::Sync + ::std::marker::Send>,
+            > {
+                <#inner as #krate::types::ToSql>::to_sql_checked(&self.0, ty, out)
+            }
+
+            fn encode_format(&self) -> #krate::types::Format {
+                <#inner as #krate::types::ToSql>::encode_format(&self.0)
+            }
+        }
+    })
+}
+
+fn expand_from_sql(input: &DeriveInput) -> Result<proc_macro2::TokenStream, Error> {
+    let attrs = ContainerAttrs::parse(input)?;
+    let inner = transparent_field(input, &attrs)?;
+    let krate = &attrs.krate;
+    let ident = &input.ident;
+
+    Ok(quote! {
+        impl<'a> #krate::types::FromSql<'a> for #ident {
+            fn from_sql(
+                ty: &#krate::types::Type,
+                raw: &'a [u8],
+            ) -> ::std::result::Result<
+                #ident,
+                ::std::boxed::Box<dyn ::std::error::Error + ::std::marker::Sync + ::std::marker::Send>,
+            > {
+                <#inner as #krate::types::FromSql<'a>>::from_sql(ty, raw).map(#ident)
+            }
+
+            fn from_sql_null(
+                ty: &#krate::types::Type,
+            ) -> ::std::result::Result<
+                #ident,
+                ::std::boxed::Box<dyn ::std::error::Error + ::std::marker::Sync + ::std::marker::Send>,
+            > {
+                <#inner as #krate::types::FromSql<'a>>::from_sql_null(ty).map(#ident)
+            }
+
+            fn from_sql_text(
+                ty: &#krate::types::Type,
+                raw: &'a [u8],
+            ) -> ::std::result::Result<
+                #ident,
+                ::std::boxed::Box<dyn ::std::error::Error + ::std::marker::Sync + ::std::marker::Send>,
+            > {
+                <#inner as #krate::types::FromSql<'a>>::from_sql_text(ty, raw).map(#ident)
+            }
+
+            fn accepts(ty: &#krate::types::Type) -> bool {
+                <#inner as #krate::types::FromSql<'a>>::accepts(ty)
+            }
+        }
+    })
+}
+
+fn expand_from_row(input: &DeriveInput) -> Result<proc_macro2::TokenStream, Error> {
+    let attrs = ContainerAttrs::parse(input)?;
+    let krate = &attrs.krate;
+
+    if !input.generics.params.is_empty() {
+        return Err(Error::new(
+            Span::call_site(),
+            "`FromRow` does not support generic types",
+        ));
+    }
+
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(Error::new(
+                    Span::call_site(),
+                    "`FromRow` may only be derived for structs with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(Error::new(
+                Span::call_site(),
+                "`FromRow` may only be derived for structs with named fields",
+            ))
+        }
+    };
+
+    let ident = &input.ident;
+    let mut values = vec![];
+    for field in fields {
+        let field_ident = field.ident.as_ref().unwrap();
+        let field_name = field_ident.to_string().trim_start_matches("r#").to_string();
+        let column = column_name(field)?.unwrap_or_else(|| field_name.clone());
+        values.push(quote! {
+            #field_ident: row.__get_field(#column, #field_name)?
+        });
+    }
+
+    Ok(quote! {
+        impl<'a> #krate::row::FromRow<'a> for #ident {
+            fn from_row(
+                row: &'a #krate::Row,
+            ) -> ::std::result::Result<#ident, #krate::Error> {
+                ::std::result::Result::Ok(#ident {
+                    #(#values,)*
+                })
+            }
+        }
+    })
+}
+
+// Returns the column name from a field's `#[postgres(column = "...")]` attribute, if present.
+fn column_name(field: &Field) -> Result<Option<String>, Error> {
+    let mut column = None;
+
+    for attr in &field.attrs {
+        if !attr.path.is_ident("postgres") {
+            continue;
+        }
+
+        let list = match attr.parse_meta()? {
+            Meta::List(list) => list,
+            meta => return Err(Error::new_spanned(meta, "expected `#[postgres(...)]`")),
+        };
+
+        for nested in &list.nested {
+            match nested {
+                NestedMeta::Meta(Meta::NameValue(meta)) if meta.ident == "column" => {
+                    match &meta.lit {
+                        Lit::Str(lit) => column = Some(lit.value()),
+                        lit => return Err(Error::new_spanned(lit, "expected a string")),
+                    }
+                }
+                nested => return Err(Error::new_spanned(nested, "unknown attribute")),
+            }
+        }
+    }
+
+    Ok(column)
+}
+
+// Returns the type of the single field of a tuple struct marked `#[postgres(transparent)]`.
+fn transparent_field<'a>(
+    input: &'a DeriveInput,
+    attrs: &ContainerAttrs,
+) -> Result<&'a Type, Error> {
+    if !attrs.transparent {
+        return Err(Error::new(
+            Span::call_site(),
+            "only `#[postgres(transparent)]` types are supported",
+        ));
+    }
+
+    if !input.generics.params.is_empty() {
+        return Err(Error::new(
+            Span::call_site(),
+            "`#[postgres(transparent)]` does not support generic types",
+        ));
+    }
+
+    let field = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Unnamed(fields) if fields.unnamed.len() == 1 => Some(&fields.unnamed[0]),
+            _ => None,
+        },
+        _ => None,
+    };
+
+    field.map(|f| &f.ty).ok_or_else(|| {
+        Error::new(
+            Span::call_site(),
+            "`#[postgres(transparent)]` may only be applied to tuple structs with a single field",
+        )
+    })
+}
+
+// The attributes applied to the type being derived for.
+struct ContainerAttrs {
+    transparent: bool,
+    // the path to the crate the traits are referenced through, `::tokio_postgres` by default
+    krate: Path,
+}
+
+impl ContainerAttrs {
+    fn parse(input: &DeriveInput) -> Result<ContainerAttrs, Error> {
+        let mut attrs = ContainerAttrs {
+            transparent: false,
+            krate: syn::parse_quote!(::tokio_postgres),
+        };
+
+        for attr in &input.attrs {
+            if !attr.path.is_ident("postgres") {
+                continue;
+            }
+
+            let list = match attr.parse_meta()? {
+                Meta::List(list) => list,
+                meta => return Err(Error::new_spanned(meta, "expected `#[postgres(...)]`")),
+            };
+
+            for nested in &list.nested {
+                match nested {
+                    NestedMeta::Meta(Meta::Word(word)) if word == "transparent" => {
+                        attrs.transparent = true
+                    }
+                    NestedMeta::Meta(Meta::NameValue(meta)) if meta.ident == "crate" => {
+                        attrs.krate = match &meta.lit {
+                            Lit::Str(lit) => lit.parse()?,
+                            lit => return Err(Error::new_spanned(lit, "expected a string")),
+                        }
+                    }
+                    nested => return Err(Error::new_spanned(nested, "unknown attribute")),
+                }
+            }
+        }
+
+        Ok(attrs)
+    }
+}
@@ -49,7 +49,9 @@
 #![warn(rust_2018_idioms, clippy::all, missing_docs)]
 
 use futures::{try_ready, Async, Future, Poll};
+use std::error::Error;
 use tokio_io::{AsyncRead, AsyncWrite};
+use tokio_postgres::config::SslMode;
 #[cfg(feature = "runtime")]
 use tokio_postgres::tls::MakeTlsConnect;
 use tokio_postgres::tls::{ChannelBinding, TlsConnect};
@@ -88,6 +90,11 @@ where
 }
 
 /// A `TlsConnect` implementation using the `native-tls` crate.
+///
+/// `native-tls` verifies the server's certificate chain and hostname unless the connector was built with
+/// `danger_accept_invalid_certs` or `danger_accept_invalid_hostnames`, so connections using `SslMode::VerifyCa` or
+/// `SslMode::VerifyFull` rely on the connector's own verification. Note that this means the hostname is also checked
+/// for `SslMode::VerifyCa`, and that verification which has been disabled on the connector can't be detected.
 pub struct TlsConnector {
     connector: tokio_tls::TlsConnector,
     domain: String,
@@ -114,6 +121,10 @@ where
     fn connect(self, stream: S) -> TlsConnectFuture<S> {
         TlsConnectFuture(self.connector.connect(&self.domain, stream))
     }
+
+    fn require_verification(&mut self, _: SslMode) -> Result<(), Box<dyn Error + Sync + Send>> {
+        Ok(())
+    }
 }
 
 /// The future returned by `TlsConnector`.
@@ -2,6 +2,7 @@ use futures::{Future, Stream};
 use native_tls::{self, Certificate};
 use tokio::net::TcpStream;
 use tokio::runtime::current_thread::Runtime;
+use tokio_postgres::config::SslMode;
 use tokio_postgres::tls::TlsConnect;
 
 #[cfg(feature = "runtime")]
@@ -51,6 +52,29 @@ fn require() {
     );
 }
 
+#[test]
+fn verify_full() {
+    let connector = native_tls::TlsConnector::builder()
+        .add_root_certificate(
+            Certificate::from_pem(include_bytes!("../../test/server.crt")).unwrap(),
+        )
+        .build()
+        .unwrap();
+    smoke_test(
+        "user=ssl_user dbname=postgres sslmode=verify-full",
+        TlsConnector::new(connector, "localhost"),
+    );
+}
+
+#[test]
+fn verification_modes_supported() {
+    let connector = native_tls::TlsConnector::new().unwrap();
+    let mut connector = TlsConnector::new(connector, "localhost");
+    for &mode in &[SslMode::Require, SslMode::VerifyCa, SslMode::VerifyFull] {
+        assert!(TlsConnect::<TcpStream>::require_verification(&mut connector, mode).is_ok());
+    }
+}
+
 #[test]
 fn prefer() {
     let connector = native_tls::TlsConnector::builder()
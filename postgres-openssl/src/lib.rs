@@ -49,12 +49,14 @@ use openssl::hash::MessageDigest;
 use openssl::nid::Nid;
 #[cfg(feature = "runtime")]
 use openssl::ssl::SslConnector;
-use openssl::ssl::{ConnectConfiguration, HandshakeError, SslRef};
+use openssl::ssl::{ConnectConfiguration, HandshakeError, SslRef, SslVerifyMode};
+use std::error::Error;
 use std::fmt::Debug;
 #[cfg(feature = "runtime")]
 use std::sync::Arc;
 use tokio_io::{AsyncRead, AsyncWrite};
 use tokio_openssl::{ConnectAsync, ConnectConfigurationExt, SslStream};
+use tokio_postgres::config::SslMode;
 #[cfg(feature = "runtime")]
 use tokio_postgres::tls::MakeTlsConnect;
 use tokio_postgres::tls::{ChannelBinding, TlsConnect};
@@ -136,6 +138,19 @@ where
     fn connect(self, stream: S) -> TlsConnectFuture<S> {
         TlsConnectFuture(self.ssl.connect_async(&self.domain, stream))
     }
+
+    /// Enables verification of the server's certificate chain, and of its hostname for `SslMode::VerifyFull`,
+    /// overriding any configuration which disabled it.
+    fn require_verification(&mut self, mode: SslMode) -> Result<(), Box<dyn Error + Sync + Send>> {
+        match mode {
+            SslMode::VerifyCa | SslMode::VerifyFull => {
+                self.ssl.set_verify(SslVerifyMode::PEER);
+                self.ssl.set_verify_hostname(mode == SslMode::VerifyFull);
+            }
+            _ => {}
+        }
+        Ok(())
+    }
 }
 
 /// The future returned by `TlsConnector`.
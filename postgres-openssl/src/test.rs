@@ -46,6 +46,36 @@ fn require() {
     );
 }
 
+#[test]
+fn verify_full() {
+    let mut builder = SslConnector::builder(SslMethod::tls()).unwrap();
+    builder.set_ca_file("../test/server.crt").unwrap();
+    let ctx = builder.build();
+    smoke_test(
+        "user=ssl_user dbname=postgres sslmode=verify-full",
+        TlsConnector::new(ctx.configure().unwrap(), "localhost"),
+    );
+}
+
+#[test]
+fn verify_full_overrides_disabled_verification() {
+    let mut runtime = Runtime::new().unwrap();
+
+    let builder = SslConnector::builder(SslMethod::tls()).unwrap();
+    let ctx = builder.build();
+    let mut ssl = ctx.configure().unwrap();
+    ssl.set_verify(SslVerifyMode::NONE);
+    ssl.set_verify_hostname(false);
+
+    let config = "user=ssl_user dbname=postgres sslmode=verify-full"
+        .parse::<tokio_postgres::Config>()
+        .unwrap();
+    let handshake = TcpStream::connect(&"127.0.0.1:5433".parse().unwrap())
+        .map_err(|e| panic!("{}", e))
+        .and_then(|s| config.connect_raw(s, TlsConnector::new(ssl, "localhost")));
+    runtime.block_on(handshake).err().unwrap();
+}
+
 #[test]
 fn prefer() {
     let mut builder = SslConnector::builder(SslMethod::tls()).unwrap();
@@ -368,6 +368,15 @@ pub fn sasl_initial_response(mechanism: &str, data: &[u8], buf: &mut Vec<u8>) ->
     })
 }
 
+#[inline]
+pub fn gss_response(data: &[u8], buf: &mut Vec<u8>) -> io::Result<()> {
+    buf.push(b'p');
+    write_body(buf, |buf| {
+        buf.extend_from_slice(data);
+        Ok(())
+    })
+}
+
 #[inline]
 pub fn sasl_response(data: &[u8], buf: &mut Vec<u8>) -> io::Result<()> {
     buf.push(b'p');
@@ -398,6 +407,27 @@ where
     })
 }
 
+/// Serializes a standby status update of the streaming replication protocol.
+///
+/// Unlike the other functions in this module, this produces the body of a `CopyData` message rather than a complete
+/// message.
+#[inline]
+pub fn standby_status_update(
+    write_lsn: u64,
+    flush_lsn: u64,
+    apply_lsn: u64,
+    timestamp: i64,
+    reply: u8,
+    buf: &mut Vec<u8>,
+) {
+    buf.push(b'r');
+    buf.write_u64::<BigEndian>(write_lsn).unwrap();
+    buf.write_u64::<BigEndian>(flush_lsn).unwrap();
+    buf.write_u64::<BigEndian>(apply_lsn).unwrap();
+    buf.write_i64::<BigEndian>(timestamp).unwrap();
+    buf.push(reply);
+}
+
 #[inline]
 pub fn sync(buf: &mut Vec<u8>) {
     buf.push(b'S');
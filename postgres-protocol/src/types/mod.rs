@@ -49,6 +49,56 @@ pub fn bytea_from_sql(buf: &[u8]) -> &[u8] {
     buf
 }
 
+/// Deserializes a `BYTEA` value from its text representation.
+///
+/// Both the hex format (`\x0a0b`) and the legacy escape format (`a\\b\012`) are supported.
+pub fn bytea_from_text(buf: &[u8]) -> Result<Vec<u8>, StdBox<dyn Error + Sync + Send>> {
+    if buf.starts_with(b"\\x") {
+        let hex = &buf[2..];
+        let mut v = Vec::with_capacity(hex.len() / 2);
+        for pair in hex.chunks(2) {
+            if pair.len() != 2 {
+                return Err("invalid bytea hex encoding".into());
+            }
+            let hi = hex_digit(pair[0]).ok_or("invalid bytea hex digit")?;
+            let lo = hex_digit(pair[1]).ok_or("invalid bytea hex digit")?;
+            v.push(hi << 4 | lo);
+        }
+        return Ok(v);
+    }
+
+    let mut v = Vec::with_capacity(buf.len());
+    let mut i = 0;
+    while i < buf.len() {
+        if buf[i] != b'\\' {
+            v.push(buf[i]);
+            i += 1;
+        } else if buf.get(i + 1) == Some(&b'\\') {
+            v.push(b'\\');
+            i += 2;
+        } else {
+            let digits = buf
+                .get(i + 1..i + 4)
+                .ok_or("invalid bytea escape sequence")?;
+            if digits[0] > b'3' || digits.iter().any(|d| !(b'0'..=b'7').contains(d)) {
+                return Err("invalid bytea escape sequence".into());
+            }
+            v.push((digits[0] - b'0') << 6 | (digits[1] - b'0') << 3 | (digits[2] - b'0'));
+            i += 4;
+        }
+    }
+    Ok(v)
+}
+
+fn hex_digit(b: u8) -> Option<u8> {
+    match b {
+        b'0'..=b'9' => Some(b - b'0'),
+        b'a'..=b'f' => Some(b - b'a' + 10),
+        b'A'..=b'F' => Some(b - b'A' + 10),
+        _ => None,
+    }
+}
+
 /// Serializes a `TEXT`, `VARCHAR`, `CHAR(n)`, `NAME`, or `CITEXT` value.
 #[inline]
 pub fn text_to_sql(v: &str, buf: &mut Vec<u8>) {
@@ -141,6 +191,22 @@ pub fn int8_from_sql(mut buf: &[u8]) -> Result<i64, StdBox<dyn Error + Sync + Se
     Ok(v)
 }
 
+/// Serializes a `PG_LSN` value.
+#[inline]
+pub fn lsn_to_sql(v: u64, buf: &mut Vec<u8>) {
+    buf.write_u64::<BigEndian>(v).unwrap();
+}
+
+/// Deserializes a `PG_LSN` value.
+#[inline]
+pub fn lsn_from_sql(mut buf: &[u8]) -> Result<u64, StdBox<dyn Error + Sync + Send>> {
+    let v = buf.read_u64::<BigEndian>()?;
+    if !buf.is_empty() {
+        return Err("invalid buffer size".into());
+    }
+    Ok(v)
+}
+
 /// Serializes a `FLOAT4` value.
 #[inline]
 pub fn float4_to_sql(v: f32, buf: &mut Vec<u8>) {
@@ -346,6 +412,110 @@ impl<'a> Varbit<'a> {
     }
 }
 
+/// Serializes a `TXID_SNAPSHOT` value.
+#[inline]
+pub fn txid_snapshot_to_sql<I>(
+    xmin: u64,
+    xmax: u64,
+    xip: I,
+    buf: &mut Vec<u8>,
+) -> Result<(), StdBox<dyn Error + Sync + Send>>
+where
+    I: IntoIterator<Item = u64>,
+{
+    let base = buf.len();
+    buf.extend_from_slice(&[0; 4]);
+    buf.write_u64::<BigEndian>(xmin).unwrap();
+    buf.write_u64::<BigEndian>(xmax).unwrap();
+
+    let mut count = 0;
+    for xid in xip {
+        count += 1;
+        buf.write_u64::<BigEndian>(xid).unwrap();
+    }
+
+    let count = i32::from_usize(count)?;
+    BigEndian::write_i32(&mut buf[base..], count);
+
+    Ok(())
+}
+
+/// Deserializes a `TXID_SNAPSHOT` value.
+#[inline]
+pub fn txid_snapshot_from_sql<'a>(
+    mut buf: &'a [u8],
+) -> Result<TxidSnapshot<'a>, StdBox<dyn Error + Sync + Send>> {
+    let count = buf.read_i32::<BigEndian>()?;
+    if count < 0 {
+        return Err("invalid xip count".into());
+    }
+    let xmin = buf.read_u64::<BigEndian>()?;
+    let xmax = buf.read_u64::<BigEndian>()?;
+    if buf.len() != count as usize * 8 {
+        return Err("invalid message length".into());
+    }
+
+    Ok(TxidSnapshot {
+        xmin,
+        xmax,
+        xip: TxidSnapshotXip { buf },
+    })
+}
+
+/// A `TXID_SNAPSHOT` value.
+pub struct TxidSnapshot<'a> {
+    xmin: u64,
+    xmax: u64,
+    xip: TxidSnapshotXip<'a>,
+}
+
+impl<'a> TxidSnapshot<'a> {
+    /// Returns the earliest transaction ID that is still active.
+    #[inline]
+    pub fn xmin(&self) -> u64 {
+        self.xmin
+    }
+
+    /// Returns the first as-yet-unassigned transaction ID.
+    #[inline]
+    pub fn xmax(&self) -> u64 {
+        self.xmax
+    }
+
+    /// Returns an iterator over the IDs of transactions in progress at the time of the snapshot.
+    #[inline]
+    pub fn xip(&self) -> TxidSnapshotXip<'a> {
+        self.xip
+    }
+}
+
+/// An iterator over the in-progress transaction IDs of a `TXID_SNAPSHOT` value.
+#[derive(Copy, Clone)]
+pub struct TxidSnapshotXip<'a> {
+    buf: &'a [u8],
+}
+
+impl<'a> FallibleIterator for TxidSnapshotXip<'a> {
+    type Item = u64;
+    type Error = StdBox<dyn Error + Sync + Send>;
+
+    #[inline]
+    fn next(&mut self) -> Result<Option<u64>, StdBox<dyn Error + Sync + Send>> {
+        if self.buf.is_empty() {
+            return Ok(None);
+        }
+
+        let xid = self.buf.read_u64::<BigEndian>()?;
+        Ok(Some(xid))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.buf.len() / 8;
+        (len, Some(len))
+    }
+}
+
 /// Serializes a `TIMESTAMP` or `TIMESTAMPTZ` value.
 ///
 /// The value should represent the number of microseconds since midnight, January 1st, 2000.
@@ -366,6 +536,33 @@ pub fn timestamp_from_sql(mut buf: &[u8]) -> Result<i64, StdBox<dyn Error + Sync
     Ok(v)
 }
 
+/// Serializes an `INTERVAL` value.
+///
+/// The value is made up of a number of microseconds, days, and months, which are stored separately since the length
+/// of a day or month is not fixed.
+#[inline]
+pub fn interval_to_sql(microseconds: i64, days: i32, months: i32, buf: &mut Vec<u8>) {
+    buf.write_i64::<BigEndian>(microseconds).unwrap();
+    buf.write_i32::<BigEndian>(days).unwrap();
+    buf.write_i32::<BigEndian>(months).unwrap();
+}
+
+/// Deserializes an `INTERVAL` value.
+///
+/// The value is returned as a tuple of microseconds, days, and months.
+#[inline]
+pub fn interval_from_sql(
+    mut buf: &[u8],
+) -> Result<(i64, i32, i32), StdBox<dyn Error + Sync + Send>> {
+    let microseconds = buf.read_i64::<BigEndian>()?;
+    let days = buf.read_i32::<BigEndian>()?;
+    let months = buf.read_i32::<BigEndian>()?;
+    if !buf.is_empty() {
+        return Err("invalid message length".into());
+    }
+    Ok((microseconds, days, months))
+}
+
 /// Serializes a `DATE` value.
 ///
 /// The value should represent the number of days since January 1st, 2000.
@@ -636,6 +833,96 @@ impl<'a> FallibleIterator for ArrayValues<'a> {
         Ok(Some(val))
     }
 
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.remaining as usize;
+        (len, Some(len))
+    }
+}
+
+/// Deserializes a `RECORD` value.
+///
+/// Each field of an anonymous record carries its own type OID, so the fields can be decoded without looking up the
+/// record's type.
+#[inline]
+pub fn record_from_sql<'a>(
+    mut buf: &'a [u8],
+) -> Result<Record<'a>, StdBox<dyn Error + Sync + Send>> {
+    let len = buf.read_i32::<BigEndian>()?;
+    if len < 0 {
+        return Err("invalid field count".into());
+    }
+
+    Ok(Record { len, buf })
+}
+
+/// A Postgres record.
+pub struct Record<'a> {
+    len: i32,
+    buf: &'a [u8],
+}
+
+impl<'a> Record<'a> {
+    /// Returns the number of fields in the record.
+    #[inline]
+    pub fn len(&self) -> i32 {
+        self.len
+    }
+
+    /// Determines if the record has no fields.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns an iterator over the fields of the record.
+    #[inline]
+    pub fn fields(&self) -> RecordFields<'a> {
+        RecordFields {
+            remaining: self.len,
+            buf: self.buf,
+        }
+    }
+}
+
+/// An iterator over the fields of a record, yielding the type OID and value of each.
+pub struct RecordFields<'a> {
+    remaining: i32,
+    buf: &'a [u8],
+}
+
+impl<'a> FallibleIterator for RecordFields<'a> {
+    type Item = (Oid, Option<&'a [u8]>);
+    type Error = StdBox<dyn Error + Sync + Send>;
+
+    #[inline]
+    fn next(&mut self) -> Result<Option<(Oid, Option<&'a [u8]>)>, StdBox<dyn Error + Sync + Send>> {
+        if self.remaining == 0 {
+            if !self.buf.is_empty() {
+                return Err("invalid message length".into());
+            }
+            return Ok(None);
+        }
+        self.remaining -= 1;
+
+        let oid = self.buf.read_u32::<BigEndian>()?;
+        let len = self.buf.read_i32::<BigEndian>()?;
+        let val = if len < 0 {
+            None
+        } else {
+            if self.buf.len() < len as usize {
+                return Err("invalid value length".into());
+            }
+
+            let (val, buf) = self.buf.split_at(len as usize);
+            self.buf = buf;
+            Some(val)
+        };
+
+        Ok(Some((oid, val)))
+    }
+
+    #[inline]
     fn size_hint(&self) -> (usize, Option<usize>) {
         let len = self.remaining as usize;
         (len, Some(len))
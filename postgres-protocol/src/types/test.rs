@@ -29,6 +29,36 @@ fn int4() {
     assert_eq!(int4_from_sql(&buf).unwrap(), 0x0102_0304);
 }
 
+#[test]
+fn bytea_text() {
+    assert_eq!(bytea_from_text(b"\\x").unwrap(), b"");
+    assert_eq!(bytea_from_text(b"\\x00ff7F").unwrap(), b"\x00\xff\x7f");
+    assert!(bytea_from_text(b"\\x0").is_err());
+    assert!(bytea_from_text(b"\\xzz").is_err());
+
+    assert_eq!(bytea_from_text(b"").unwrap(), b"");
+    assert_eq!(
+        bytea_from_text(b"a\\\\b\\000\\377").unwrap(),
+        b"a\\b\x00\xff"
+    );
+    assert!(bytea_from_text(b"a\\").is_err());
+    assert!(bytea_from_text(b"\\400").is_err());
+    assert!(bytea_from_text(b"\\12").is_err());
+}
+
+#[test]
+fn txid_snapshot() {
+    let mut buf = vec![];
+    txid_snapshot_to_sql(10, 20, vec![12, 15], &mut buf).unwrap();
+
+    let snapshot = txid_snapshot_from_sql(&buf).unwrap();
+    assert_eq!(snapshot.xmin(), 10);
+    assert_eq!(snapshot.xmax(), 20);
+    assert_eq!(snapshot.xip().collect::<Vec<_>>().unwrap(), vec![12, 15]);
+
+    assert!(txid_snapshot_from_sql(&buf[..buf.len() - 1]).is_err());
+}
+
 #[test]
 fn int8() {
     let mut buf = vec![];
@@ -36,6 +66,26 @@ fn int8() {
     assert_eq!(int8_from_sql(&buf).unwrap(), 0x0102_0304_0506_0708);
 }
 
+#[test]
+fn lsn() {
+    let mut buf = vec![];
+    lsn_to_sql(0x0000_0001_6B37_4D48, &mut buf);
+    assert_eq!(buf, [0, 0, 0, 1, 0x6B, 0x37, 0x4D, 0x48]);
+    assert_eq!(lsn_from_sql(&buf).unwrap(), 0x0000_0001_6B37_4D48);
+}
+
+#[test]
+fn interval() {
+    let mut buf = vec![];
+    interval_to_sql(1_500_000, -2, 14, &mut buf);
+    assert_eq!(
+        buf,
+        [0, 0, 0, 0, 0, 0x16, 0xE3, 0x60, 0xFF, 0xFF, 0xFF, 0xFE, 0, 0, 0, 14]
+    );
+    assert_eq!(interval_from_sql(&buf).unwrap(), (1_500_000, -2, 14));
+    assert!(interval_from_sql(&buf[..12]).is_err());
+}
+
 #[test]
 #[allow(clippy::float_cmp)]
 fn float4() {
@@ -118,6 +168,26 @@ fn array() {
     assert_eq!(array.values().collect::<Vec<_>>().unwrap(), values);
 }
 
+#[test]
+fn record() {
+    let mut buf = vec![];
+    buf.extend_from_slice(&2i32.to_be_bytes());
+    buf.extend_from_slice(&23u32.to_be_bytes());
+    buf.extend_from_slice(&4i32.to_be_bytes());
+    buf.extend_from_slice(&1i32.to_be_bytes());
+    buf.extend_from_slice(&25u32.to_be_bytes());
+    buf.extend_from_slice(&(-1i32).to_be_bytes());
+
+    let record = record_from_sql(&buf).unwrap();
+    assert_eq!(record.len(), 2);
+    let fields = record.fields().collect::<Vec<_>>().unwrap();
+    assert_eq!(fields, vec![(23, Some(&[0, 0, 0, 1][..])), (25, None)]);
+
+    buf.push(0);
+    let record = record_from_sql(&buf).unwrap();
+    assert!(record.fields().collect::<Vec<_>>().is_err());
+}
+
 #[test]
 fn non_null_array() {
     let dimensions = [
@@ -0,0 +1,84 @@
+use fallible_iterator::FallibleIterator;
+use futures::stream::{self, Stream};
+use futures::sync::oneshot;
+use futures::Future;
+use tokio_postgres::impls;
+use tokio_postgres::{Error, Row};
+
+use crate::Client;
+
+/// The iterator returned by the `query_iter_cancellable` method.
+///
+/// If the iterator is dropped after the query has started but before all of its rows have been read, a cancellation
+/// request is sent to the server, and the drop blocks until the request has been delivered and the query's remaining
+/// responses have been read.
+pub struct CancellableQueryIter<'a> {
+    it: stream::Wait<impls::Query>,
+    cancel: Option<Box<dyn Future<Item = (), Error = Error> + Send>>,
+    started: bool,
+    client: &'a Client,
+}
+
+impl<'a> Drop for CancellableQueryIter<'a> {
+    fn drop(&mut self) {
+        if !self.started {
+            return;
+        }
+
+        // the cancel is cleared once the stream has finished, in which case there's nothing left to cancel
+        let cancel = match self.cancel.take() {
+            Some(cancel) => cancel,
+            None => return,
+        };
+
+        // the cancellation is best-effort, so failures to send it are ignored
+        let (tx, rx) = oneshot::channel();
+        let future = cancel.then(|r| tx.send(r).map_err(|_| ()));
+        if self
+            .client
+            .with_executor(|e| e.execute(Box::new(future)))
+            .is_ok()
+        {
+            let _ = rx.wait();
+        }
+
+        // read the rest of the responses, which normally end with a `QUERY_CANCELED` error, so that the connection is
+        // idle again before the client is used for anything else
+        while let Some(Ok(_)) = self.it.next() {}
+    }
+}
+
+impl<'a> CancellableQueryIter<'a> {
+    pub(crate) fn new(
+        stream: impls::Query,
+        cancel: Box<dyn Future<Item = (), Error = Error> + Send>,
+        client: &'a Client,
+    ) -> CancellableQueryIter<'a> {
+        CancellableQueryIter {
+            it: stream.wait(),
+            cancel: Some(cancel),
+            started: false,
+            client,
+        }
+    }
+}
+
+impl<'a> FallibleIterator for CancellableQueryIter<'a> {
+    type Item = Row;
+    type Error = Error;
+
+    fn next(&mut self) -> Result<Option<Row>, Error> {
+        self.started = true;
+        match self.it.next() {
+            Some(Ok(row)) => Ok(Some(row)),
+            Some(Err(e)) => {
+                self.cancel = None;
+                Err(e)
+            }
+            None => {
+                self.cancel = None;
+                Ok(None)
+            }
+        }
+    }
+}
@@ -1,20 +1,47 @@
 use fallible_iterator::FallibleIterator;
-use futures::{Async, Future, Poll, Stream};
+#[cfg(feature = "runtime")]
+use futures::future::Either;
+#[cfg(feature = "runtime")]
+use futures::future::Executor;
+#[cfg(feature = "runtime")]
+use futures::sync::oneshot;
+#[cfg(feature = "runtime")]
+use futures::IntoFuture;
+use futures::{future, Async, Future, Poll, Stream};
+use std::collections::HashMap;
 use std::io::{self, Read};
+#[cfg(feature = "runtime")]
+use std::sync::Arc;
+#[cfg(feature = "runtime")]
+use std::time::{Duration, Instant};
+#[cfg(feature = "runtime")]
+use tokio::timer::Delay;
+use tokio_postgres::binary_copy::BinaryCopyWriter;
+use tokio_postgres::error::DbError;
+use tokio_postgres::row::FromRow;
 use tokio_postgres::tls::{MakeTlsConnect, TlsConnect};
-use tokio_postgres::types::{ToSql, Type};
+use tokio_postgres::types::{Format, FromSqlOwned, ToSql, Type};
 #[cfg(feature = "runtime")]
 use tokio_postgres::Socket;
-use tokio_postgres::{Error, Row, SimpleQueryMessage};
+use tokio_postgres::{ConnectionState, Error, Row, SimpleQueryMessage};
 
 #[cfg(feature = "runtime")]
-use crate::Config;
-use crate::{CopyOutReader, QueryIter, SimpleQueryIter, Statement, ToStatement, Transaction};
+use crate::config::DynExecutor;
+use crate::{
+    quote_identifier, CopyOutReader, QueryIter, SimpleQueryIter, Statement, ToStatement,
+    Transaction,
+};
+#[cfg(feature = "runtime")]
+use crate::{CancellableQueryIter, Config, RUNTIME};
 
 /// A synchronous PostgreSQL client.
 ///
 /// This is a lightweight wrapper over the asynchronous tokio_postgres `Client`.
-pub struct Client(tokio_postgres::Client);
+pub struct Client {
+    client: tokio_postgres::Client,
+    #[cfg(feature = "runtime")]
+    executor: Option<Arc<DynExecutor>>,
+}
 
 impl Client {
     /// A convenience function which parses a configuration string into a `Config` and then connects to the database.
@@ -50,14 +77,14 @@ impl Client {
     ///
     /// If the statement does not modify any rows (e.g. `SELECT`), 0 is returned.
     ///
+    /// For an `INSERT ... ON CONFLICT DO NOTHING` of a single row, 1 is returned if the row was inserted and 0 if it
+    /// was skipped. `ON CONFLICT DO UPDATE` reports 1 for both an insert and an update; to tell them apart, use
+    /// `RETURNING (xmax = 0) AS inserted` with `query` instead.
+    ///
     /// The `query` argument can either be a `Statement`, or a raw query string. If the same statement will be
     /// repeatedly executed (perhaps with different query parameters), consider preparing the statement up front
     /// with the `prepare` method.
     ///
-    /// # Panics
-    ///
-    /// Panics if the number of parameters provided does not match the number expected.
-    ///
     /// # Example
     ///
     /// ```no_run
@@ -82,7 +109,52 @@ impl Client {
         T: ?Sized + ToStatement,
     {
         let statement = query.__statement(self)?;
-        self.0.execute(&statement, params).wait()
+        self.client.execute(&statement, params).wait()
+    }
+
+    /// Like `execute`, but cancels the statement if it has not completed before the specified timeout elapses.
+    ///
+    /// If the timeout elapses, a cancellation request is sent to the server over a new connection, which is opened
+    /// using the provided TLS configuration. The statement will then fail with a `QUERY_CANCELED` error, and the client
+    /// remains usable. An error is also returned if the cancellation request could not be sent.
+    ///
+    /// Note that the server cancels whatever query is running when the request arrives. If the statement completes
+    /// just as the timeout elapses, the cancellation may affect a later query instead.
+    ///
+    /// Requires the `runtime` Cargo feature (enabled by default).
+    #[cfg(feature = "runtime")]
+    pub fn execute_with_deadline<T, U>(
+        &mut self,
+        query: &T,
+        params: &[&dyn ToSql],
+        timeout: Duration,
+        make_tls_mode: U,
+    ) -> Result<u64, Error>
+    where
+        T: ?Sized + ToStatement,
+        U: MakeTlsConnect<Socket> + 'static + Send,
+        U::TlsConnect: Send,
+        U::Stream: Send,
+        <U::TlsConnect as TlsConnect<Socket>>::Future: Send,
+    {
+        let statement = query.__statement(self)?;
+        let execute = self.client.execute(&statement, params);
+        let cancel = self.client.cancel_query(make_tls_mode);
+
+        let (tx, rx) = oneshot::channel();
+        let future = execute
+            .select2(Delay::new(Instant::now() + timeout))
+            .then(|r| match r {
+                Ok(Either::A((rows, _))) => Either::A(Ok(rows).into_future()),
+                Err(Either::A((e, _))) => Either::A(Err(e).into_future()),
+                Ok(Either::B((_, execute))) | Err(Either::B((_, execute))) => {
+                    Either::B(cancel.and_then(|()| execute))
+                }
+            })
+            .then(|r| tx.send(r).map_err(|_| ()));
+        self.with_executor(|e| e.execute(Box::new(future))).unwrap();
+
+        rx.wait().unwrap()
     }
 
     /// Executes a statement, returning the resulting rows.
@@ -96,10 +168,6 @@ impl Client {
     ///
     /// The `query_iter` method can be used to avoid buffering all rows in memory at once.
     ///
-    /// # Panics
-    ///
-    /// Panics if the number of parameters provided does not match the number expected.
-    ///
     /// # Examples
     ///
     /// ```no_run
@@ -123,12 +191,188 @@ impl Client {
         self.query_iter(query, params)?.collect()
     }
 
-    /// Like `query`, except that it returns a fallible iterator over the resulting rows rather than buffering the
-    /// response in memory.
+    /// Executes a statement which returns a single row, returning it.
+    ///
+    /// An error is returned if the statement returns no rows or more than one row.
+    pub fn query_one<T>(&mut self, query: &T, params: &[&dyn ToSql]) -> Result<Row, Error>
+    where
+        T: ?Sized + ToStatement,
+    {
+        let statement = query.__statement(self)?;
+        self.client.query_one(&statement, params).wait()
+    }
+
+    /// Like `query_one`, but converts the row into a value with its `FromRow` implementation.
+    ///
+    /// With the `derive` Cargo feature enabled, `FromRow` can be derived for structs whose fields are retrieved from
+    /// the columns with the same names. The struct must be marked `#[postgres(crate = "postgres")]`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use postgres::{Client, NoTls};
+    ///
+    /// # fn main() -> Result<(), postgres::Error> {
+    /// let mut client = Client::connect("host=localhost user=postgres", NoTls)?;
+    ///
+    /// let (id, name) = client.query_one_as::<_, (i32, String)>("SELECT id, name FROM people LIMIT 1", &[])?;
+    /// println!("{}: {}", id, name);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn query_one_as<T, R>(&mut self, query: &T, params: &[&dyn ToSql]) -> Result<R, Error>
+    where
+        T: ?Sized + ToStatement,
+        R: for<'a> FromRow<'a>,
+    {
+        let row = self.query_one(query, params)?;
+        R::from_row(&row)
+    }
+
+    /// Like `query`, but binds parameters by name rather than by position.
+    ///
+    /// Postgres only supports positional parameters, so each `:name` placeholder in the query is rewritten to a `$N`
+    /// parameter before it is prepared, with every occurrence of a name sharing the same position. Placeholders inside
+    /// string literals, quoted identifiers and comments are left alone, as are `::` casts and the `:` separating the
+    /// bounds of an array slice such as `arr[lo:hi]`. Values in `params` which are not referenced by the query are
+    /// ignored.
+    ///
+    /// An error is returned if the query references a name which is not present in `params`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use postgres::types::ToSql;
+    /// use postgres::{Client, NoTls};
+    /// use std::collections::HashMap;
+    ///
+    /// # fn main() -> Result<(), postgres::Error> {
+    /// let mut client = Client::connect("host=localhost user=postgres", NoTls)?;
+    ///
+    /// let mut params = HashMap::<&str, &dyn ToSql>::new();
+    /// params.insert("min_age", &18i32);
+    /// params.insert("name", &"alice");
+    /// let rows = client.query_named(
+    ///     "SELECT id FROM people WHERE age >= :min_age AND (name = :name OR nickname = :name)",
+    ///     &params,
+    /// )?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn query_named(
+        &mut self,
+        query: &str,
+        params: &HashMap<&str, &dyn ToSql>,
+    ) -> Result<Vec<Row>, Error> {
+        let statement = self.client.prepare_named(query).wait()?;
+        self.client.query_named(&statement, params).collect().wait()
+    }
+
+    /// Fetches the rows of a table whose key column matches any of the provided keys.
+    ///
+    /// This runs `SELECT * FROM table WHERE column = ANY($1)`, binding the keys as a single array parameter, so any
+    /// number of rows are loaded in one round trip. The table and column names are quoted as identifiers, so they are
+    /// matched case-sensitively and cannot be schema-qualified.
+    ///
+    /// The rows are returned in no particular order, and keys which do not match a row are skipped, so callers which
+    /// care about order should reorder the rows by key themselves.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use postgres::{Client, NoTls};
     ///
-    /// # Panics
+    /// # fn main() -> Result<(), postgres::Error> {
+    /// let mut client = Client::connect("host=localhost user=postgres", NoTls)?;
+    ///
+    /// for row in client.query_by_keys("people", "id", &[1i32, 5, 7])? {
+    ///     let name: &str = row.get("name");
+    ///     println!("name: {}", name);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn query_by_keys<K>(
+        &mut self,
+        table: &str,
+        column: &str,
+        keys: &[K],
+    ) -> Result<Vec<Row>, Error>
+    where
+        K: ToSql,
+    {
+        let query = format!(
+            "SELECT * FROM {} WHERE {} = ANY($1)",
+            quote_identifier(table),
+            quote_identifier(column)
+        );
+        self.query(&*query, &[&keys])
+    }
+
+    /// Returns the lines of the plan the server chooses for a query, as reported by `EXPLAIN`.
+    ///
+    /// The query is prepared with `EXPLAIN` prepended, and the parameters are bound to it as they would be when
+    /// executing the query, so the plan reflects the actual parameter values without them being interpolated into
+    /// the query text. The query is not executed.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use postgres::{Client, NoTls};
+    ///
+    /// # fn main() -> Result<(), postgres::Error> {
+    /// let mut client = Client::connect("host=localhost user=postgres", NoTls)?;
     ///
-    /// Panics if the number of parameters provided does not match the number expected.
+    /// for line in client.explain("SELECT * FROM people WHERE id = $1", &[&1i32])? {
+    ///     println!("{}", line);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn explain(&mut self, query: &str, params: &[&dyn ToSql]) -> Result<Vec<String>, Error> {
+        let query = format!("EXPLAIN (FORMAT TEXT) {}", query);
+        let rows = self.query(&*query, params)?;
+        rows.iter().map(|row| row.try_get(0)).collect()
+    }
+
+    /// Like `explain`, but returns the plan in `EXPLAIN`'s JSON format.
+    ///
+    /// The plan can be retrieved as any type which can be decoded from a `JSON` value, such as `serde_json::Value`
+    /// with the `with-serde_json-1` Cargo feature.
+    pub fn explain_json<T>(&mut self, query: &str, params: &[&dyn ToSql]) -> Result<T, Error>
+    where
+        T: FromSqlOwned,
+    {
+        let query = format!("EXPLAIN (FORMAT JSON) {}", query);
+        let rows = self.query(&*query, params)?;
+        rows[0].try_get(0)
+    }
+
+    /// Fetches rows from an existing cursor.
+    ///
+    /// Outside of a transaction, only cursors declared `WITH HOLD` remain open, such as those declared with
+    /// `Transaction::declare_cursor_with_hold`. This can be called repeatedly to page through the cursor's results. If
+    /// the requested number of rows is negative or 0, all remaining rows will be returned.
+    pub fn query_cursor(&mut self, name: &str, max_rows: i32) -> Result<Vec<Row>, Error> {
+        let name = quote_identifier(name);
+        let query = if max_rows > 0 {
+            format!("FETCH FORWARD {} FROM {}", max_rows, name)
+        } else {
+            format!("FETCH ALL FROM {}", name)
+        };
+        self.query(&*query, &[])
+    }
+
+    /// Closes a cursor.
+    ///
+    /// Cursors declared `WITH HOLD` are otherwise kept open by the server until the session ends.
+    pub fn close_cursor(&mut self, name: &str) -> Result<(), Error> {
+        self.simple_query(&format!("CLOSE {}", quote_identifier(name)))?;
+        Ok(())
+    }
+
+    /// Like `query`, except that it returns a fallible iterator over the resulting rows rather than buffering the
+    /// response in memory.
     ///
     /// # Examples
     ///
@@ -158,7 +402,41 @@ impl Client {
         T: ?Sized + ToStatement,
     {
         let statement = query.__statement(self)?;
-        Ok(QueryIter::new(self.0.query(&statement, params)))
+        Ok(QueryIter::new(self.client.query(&statement, params)))
+    }
+
+    /// Like `query_iter`, but cancels the query if the iterator is dropped before all rows have been read.
+    ///
+    /// This is useful for queries producing many rows when the caller may stop reading early, since the server would
+    /// otherwise keep producing rows which are then discarded. When the iterator is dropped after the first call to
+    /// `next` but before the last row has been returned, a cancellation request is sent to the server over a new
+    /// connection, which is opened using the provided TLS configuration. The drop blocks until the request has been
+    /// delivered and the query's remaining responses, including the resulting `QUERY_CANCELED` error, have been read
+    /// and discarded, so the client is idle and usable again afterwards. The cancellation is best-effort: errors
+    /// sending it are ignored, in which case the drop waits for the query to run to completion. No request is sent if
+    /// the iterator is dropped after all of the rows have been read.
+    ///
+    /// The request is sent on the client's executor, or the crate's default runtime if none was configured.
+    ///
+    /// Requires the `runtime` Cargo feature (enabled by default).
+    #[cfg(feature = "runtime")]
+    pub fn query_iter_cancellable<T, U>(
+        &mut self,
+        query: &T,
+        params: &[&dyn ToSql],
+        make_tls_mode: U,
+    ) -> Result<CancellableQueryIter<'_>, Error>
+    where
+        T: ?Sized + ToStatement,
+        U: MakeTlsConnect<Socket> + 'static + Send,
+        U::TlsConnect: Send,
+        U::Stream: Send,
+        <U::TlsConnect as TlsConnect<Socket>>::Future: Send,
+    {
+        let statement = query.__statement(self)?;
+        let cancel = Box::new(self.client.cancel_query(make_tls_mode));
+        let stream = self.client.query(&statement, params);
+        Ok(CancellableQueryIter::new(stream, cancel, self))
     }
 
     /// Creates a new prepared statement.
@@ -185,7 +463,7 @@ impl Client {
     /// # }
     /// ```
     pub fn prepare(&mut self, query: &str) -> Result<Statement, Error> {
-        self.0.prepare(query).wait()
+        self.client.prepare(query).wait()
     }
 
     /// Like `prepare`, but allows the types of query parameters to be explicitly specified.
@@ -216,7 +494,113 @@ impl Client {
     /// # }
     /// ```
     pub fn prepare_typed(&mut self, query: &str, types: &[Type]) -> Result<Statement, Error> {
-        self.0.prepare_typed(query, types).wait()
+        self.client.prepare_typed(query, types).wait()
+    }
+
+    /// Like `prepare`, but allows the formats the result columns are requested in to be explicitly specified.
+    ///
+    /// The list of formats may be smaller than the number of columns - the remaining columns are requested in the
+    /// binary format, unless their type has no binary format. Values of columns requested in the text format are
+    /// decoded with `FromSql::from_sql_text`, and can always be retrieved as strings.
+    ///
+    /// Formats are not negotiated per column automatically: every column is requested in the binary format by default,
+    /// even if no `FromSql` implementation can decode its type's binary format, and only columns explicitly requested
+    /// in the text format here are retrieved as text. Columns of types without a `FromSql` implementation should be
+    /// requested in the text format to read them as strings.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use postgres::types::Format;
+    /// use postgres::{Client, NoTls};
+    ///
+    /// # fn main() -> Result<(), postgres::Error> {
+    /// let mut client = Client::connect("host=localhost user=postgres", NoTls)?;
+    ///
+    /// // retrieve a value of an extension type without a FromSql implementation in its text format
+    /// let statement = client.prepare_with_result_formats(
+    ///     "SELECT path FROM categories WHERE id = $1",
+    ///     &[Format::Text],
+    /// )?;
+    /// let rows = client.query(&statement, &[&1i32])?;
+    /// let path: &str = rows[0].get(0);
+    /// println!("path: {}", path);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn prepare_with_result_formats(
+        &mut self,
+        query: &str,
+        result_formats: &[Format],
+    ) -> Result<Statement, Error> {
+        self.client
+            .prepare_with_result_formats(query, result_formats)
+            .wait()
+    }
+
+    /// Creates a new prepared statement and executes it, returning both the statement and the resulting rows.
+    ///
+    /// This is a convenience for queries which need to be run immediately but will also be executed again later.
+    /// The statement is prepared and executed in a single round trip, and remains valid afterwards so it can be passed
+    /// to `query` and `execute` as usual.
+    ///
+    /// Since the parameters are encoded before the server has described the statement, the type of every parameter
+    /// must be specified, and every column of the result is requested in the binary format. An error is returned if
+    /// the number of parameters does not match the number of types.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use postgres::{Client, NoTls};
+    /// use postgres::types::Type;
+    ///
+    /// # fn main() -> Result<(), postgres::Error> {
+    /// let mut client = Client::connect("host=localhost user=postgres", NoTls)?;
+    ///
+    /// let (statement, rows) = client.prepare_and_query(
+    ///     "SELECT name FROM people WHERE id = $1",
+    ///     &[Type::INT4],
+    ///     &[&0i32],
+    /// )?;
+    /// for id in 1..10 {
+    ///     let rows = client.query(&statement, &[&id])?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn prepare_and_query(
+        &mut self,
+        query: &str,
+        param_types: &[Type],
+        params: &[&dyn ToSql],
+    ) -> Result<(Statement, Vec<Row>), Error> {
+        self.client
+            .prepare_and_query(query, param_types, params)
+            .wait()
+    }
+
+    /// Closes a prepared statement, releasing its resources on the server.
+    ///
+    /// Statements are closed automatically when they are dropped, but any error is ignored. This method closes the
+    /// statement immediately and reports any error returned by the server. Any clones of the statement will no longer
+    /// be usable once it has been closed.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use postgres::{Client, NoTls};
+    ///
+    /// # fn main() -> Result<(), postgres::Error> {
+    /// let mut client = Client::connect("host=localhost user=postgres", NoTls)?;
+    ///
+    /// let statement = client.prepare("SELECT name FROM people WHERE id = $1")?;
+    /// client.query(&statement, &[&1i32])?;
+    /// client.close_statement(statement)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn close_statement(&mut self, statement: Statement) -> Result<(), Error> {
+        self.client.close_statement(statement).wait()
     }
 
     /// Executes a `COPY FROM STDIN` statement, returning the number of rows created.
@@ -247,11 +631,73 @@ impl Client {
         R: Read,
     {
         let statement = query.__statement(self)?;
-        self.0
+        self.client
             .copy_in(&statement, params, CopyInStream(reader))
             .wait()
     }
 
+    /// Loads rows into a table with a binary `COPY`, returning the number of rows created.
+    ///
+    /// The types of the listed columns are looked up from the server, and each row's values are encoded with their
+    /// `ToSql` implementations into the binary `COPY` format. Rows are encoded and sent as the iterator produces
+    /// them, so the data is never buffered in memory all at once. If a row has the wrong number of values or a value
+    /// of the wrong type, the `COPY` is aborted, leaving the table untouched, and an error identifying the row is
+    /// returned. The table and column names are quoted as identifiers, so they are matched case-sensitively.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use postgres::{Client, NoTls};
+    /// use postgres::types::ToSql;
+    ///
+    /// # fn main() -> Result<(), postgres::Error> {
+    /// let mut client = Client::connect("host=localhost user=postgres", NoTls)?;
+    ///
+    /// let rows: &[&[&dyn ToSql]] = &[&[&1i32, &"john"], &[&2i32, &"jane"]];
+    /// client.copy_in_typed("people", &["id", "name"], rows)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn copy_in_typed<'a, I, R>(
+        &mut self,
+        table: &str,
+        columns: &[&str],
+        rows: I,
+    ) -> Result<u64, Error>
+    where
+        I: IntoIterator<Item = R>,
+        R: AsRef<[&'a dyn ToSql]>,
+    {
+        let table = quote_identifier(table);
+        let columns = columns
+            .iter()
+            .map(|c| quote_identifier(c))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let statement = self.prepare(&format!("SELECT {} FROM {} LIMIT 0", columns, table))?;
+        let types = statement
+            .columns()
+            .iter()
+            .map(|c| c.type_().clone())
+            .collect::<Vec<_>>();
+
+        let query = format!("COPY {} ({}) FROM STDIN (FORMAT binary)", table, columns);
+        let statement = self.prepare(&query)?;
+
+        let mut error = None;
+        let stream = TypedCopyInStream {
+            rows: rows.into_iter(),
+            writer: Some(BinaryCopyWriter::new(&types)),
+            error: &mut error,
+        };
+        let result = self.client.copy_in(&statement, &[], stream).wait();
+        match error {
+            Some(e) => Err(e),
+            None => result,
+        }
+    }
+
     /// Executes a `COPY TO STDOUT` statement, returning a reader of the resulting data.
     ///
     /// The `query` argument can either be a `Statement`, or a raw query string.
@@ -280,17 +726,56 @@ impl Client {
         T: ?Sized + ToStatement,
     {
         let statement = query.__statement(self)?;
-        let stream = self.0.copy_out(&statement, params);
+        let stream = self.client.copy_out(&statement, params);
         CopyOutReader::new(stream)
     }
 
+    /// Executes a statement which returns a single column, returning a reader over the contents of its values.
+    ///
+    /// Values are read from the connection as they arrive rather than being buffered in memory, so a single huge
+    /// value can be copied to its destination with `std::io::copy`. The reader returns the raw encoding of the values
+    /// in the column's format, which for a `BYTEA` column is its bytes. The values of consecutive rows are not
+    /// delimited, and the reader fails with an error if it encounters a `NULL` value, which can't be represented in the
+    /// data.
+    ///
+    /// Returns an error if the statement does not return exactly one column.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use postgres::{Client, NoTls};
+    /// use std::fs::File;
+    /// use std::io;
+    ///
+    /// # fn main() -> Result<(), Box<std::error::Error>> {
+    /// let mut client = Client::connect("host=localhost user=postgres", NoTls)?;
+    ///
+    /// let mut reader = client.query_field("SELECT pg_read_binary_file('base/1/1259')", &[])?;
+    /// let mut file = File::create("pg_class")?;
+    /// io::copy(&mut reader, &mut file)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn query_field<T>(
+        &mut self,
+        query: &T,
+        params: &[&dyn ToSql],
+    ) -> Result<CopyOutReader<'_>, Error>
+    where
+        T: ?Sized + ToStatement,
+    {
+        let statement = query.__statement(self)?;
+        let stream = self.client.query_field(&statement, params);
+        CopyOutReader::field(stream)
+    }
+
     /// Executes a sequence of SQL statements using the simple query protocol.
     ///
     /// Statements should be separated by semicolons. If an error occurs, execution of the sequence will stop at that
     /// point. The simple query protocol returns the values in rows as strings rather than in their binary encodings,
     /// so the associated row type doesn't work with the `FromSql` trait. Rather than simply returning the rows, this
-    /// method returns a sequence of an enum which indicates either the completion of one of the commands, or a row of
-    /// data. This preserves the framing between the separate statements in the request.
+    /// method returns a sequence of an enum which indicates either the completion of one of the commands, the start of
+    /// a new result set, or a row of data. This preserves the framing between the separate statements in the request.
     ///
     /// This is a simple convenience method over `simple_query_iter`.
     ///
@@ -312,7 +797,45 @@ impl Client {
     /// functionality to safely imbed that data in the request. Do not form statements via string concatenation and pass
     /// them to this method!
     pub fn simple_query_iter(&mut self, query: &str) -> Result<SimpleQueryIter<'_>, Error> {
-        Ok(SimpleQueryIter::new(self.0.simple_query(query)))
+        Ok(SimpleQueryIter::new(self.client.simple_query(query)))
+    }
+
+    /// Executes a sequence of SQL statements inside of a single database transaction.
+    ///
+    /// The statements are run as with `simple_query`, but are wrapped in a `BEGIN`/`COMMIT` pair so that either all of
+    /// them take effect or none of them do. If any statement fails, execution of the sequence stops, the transaction
+    /// is rolled back, and the error is returned.
+    ///
+    /// Some statements, like `CREATE DATABASE` or `CREATE INDEX CONCURRENTLY`, cannot be run inside of a transaction
+    /// block. If the sequence contains one of them, the server rejects it and the returned error's `code` will be
+    /// `SqlState::ACTIVE_SQL_TRANSACTION`. The transaction is rolled back in that case as well, so the client remains
+    /// usable.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use postgres::{Client, NoTls};
+    ///
+    /// # fn main() -> Result<(), postgres::Error> {
+    /// let mut client = Client::connect("host=localhost user=postgres", NoTls)?;
+    ///
+    /// client.execute_batch_transactional(
+    ///     "CREATE TABLE people (id SERIAL PRIMARY KEY, name TEXT NOT NULL);
+    ///      INSERT INTO people (name) VALUES ('steven');",
+    /// )?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Warning
+    ///
+    /// Prepared statements should be use for any query which contains user-specified data, as they provided the
+    /// functionality to safely imbed that data in the request. Do not form statements via string concatenation and pass
+    /// them to this method!
+    pub fn execute_batch_transactional(&mut self, query: &str) -> Result<(), Error> {
+        let mut transaction = self.transaction()?;
+        transaction.simple_query(query)?;
+        transaction.commit()
     }
 
     /// Begins a new database transaction.
@@ -340,32 +863,196 @@ impl Client {
         Ok(Transaction::new(self))
     }
 
+    /// Runs a closure with the session's role set to `role`, resetting it afterwards.
+    ///
+    /// The role is set with `SET ROLE` before the closure is called, and reset with `RESET ROLE` once it returns,
+    /// even if it returns an error or panics. This is useful with row-level security, where the role must not leak to
+    /// the next user of a pooled connection. The role name is quoted as an identifier, so it is matched
+    /// case-sensitively.
+    ///
+    /// Within a transaction, `Transaction::set_role` can be used instead, which resets the role automatically when
+    /// the transaction ends.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use postgres::{Client, NoTls};
+    ///
+    /// # fn main() -> Result<(), postgres::Error> {
+    /// let mut client = Client::connect("host=localhost user=postgres", NoTls)?;
+    ///
+    /// let rows = client.with_role("tenant_1", |client| client.query("SELECT * FROM orders", &[]))?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_role<F, T>(&mut self, role: &str, f: F) -> Result<T, Error>
+    where
+        F: FnOnce(&mut Client) -> Result<T, Error>,
+    {
+        self.simple_query(&format!("SET ROLE {}", quote_identifier(role)))?;
+
+        let mut guard = RoleGuard {
+            client: self,
+            done: false,
+        };
+        let r = f(guard.client);
+        guard.done = true;
+        let reset = guard.client.simple_query("RESET ROLE");
+
+        let value = r?;
+        reset?;
+        Ok(value)
+    }
+
+    /// Opens a new connection with the same configuration as this client's.
+    ///
+    /// The new client has its own socket and session, so it can be used to run queries in parallel with this one. The
+    /// client doesn't retain the TLS connector it was created with, so one must be provided again.
+    ///
+    /// Requires the `runtime` Cargo feature (enabled by default).
+    #[cfg(feature = "runtime")]
+    pub fn connect_another<T>(&self, tls_mode: T) -> Result<Client, Error>
+    where
+        T: MakeTlsConnect<Socket> + 'static + Send,
+        T::TlsConnect: Send,
+        T::Stream: Send,
+        <T::TlsConnect as TlsConnect<Socket>>::Future: Send,
+    {
+        let mut config = Config::from(self.client.config().clone());
+        config.executor = self.executor.clone();
+        config.connect(tls_mode)
+    }
+
     /// Determines if the client's connection has already closed.
     ///
-    /// If this returns `true`, the client is no longer usable.
+    /// If this returns `true`, the client is no longer usable. The connection is closed if an IO error occurs, or if
+    /// the client and server lose track of the state of the protocol. A closed connection cannot be recovered, and
+    /// the client should be dropped rather than being returned to a connection pool.
     pub fn is_closed(&self) -> bool {
-        self.0.is_closed()
+        self.client.is_closed()
+    }
+
+    /// Returns the error the server sent when it closed the connection, if any.
+    ///
+    /// The server reports why it is terminating a session, for example with the `ADMIN_SHUTDOWN` error code when the
+    /// server is shutting down or the backend was terminated by an administrator. This method returns that error
+    /// without communicating with the server, so connection pools can cheaply use it alongside `is_closed` to discard
+    /// and log clients the server has torn down. The error is returned at most once.
+    pub fn take_error(&mut self) -> Option<DbError> {
+        self.client.take_error()
+    }
+
+    /// Returns the state of the session.
+    ///
+    /// This waits for the server to finish processing any outstanding requests. It can be used to detect a
+    /// transaction which has failed and must be rolled back, or by a connection pool to avoid reusing a client which
+    /// has been left in a transaction.
+    pub fn state(&mut self) -> ConnectionState {
+        let _ = future::poll_fn(|| self.client.poll_idle()).wait();
+        self.client.state()
+    }
+
+    /// Returns how long the current transaction has been open, or `None` if the session is not in a transaction.
+    ///
+    /// Like `state`, this waits for the server to finish processing any outstanding requests. The duration is measured
+    /// from when the server acknowledged the `BEGIN`.
+    pub fn transaction_duration(&mut self) -> Option<Duration> {
+        let _ = future::poll_fn(|| self.client.poll_idle()).wait();
+        self.client.transaction_duration()
     }
 
     /// Returns a shared reference to the inner nonblocking client.
     pub fn get_ref(&self) -> &tokio_postgres::Client {
-        &self.0
+        &self.client
     }
 
     /// Returns a mutable reference to the inner nonblocking client.
     pub fn get_mut(&mut self) -> &mut tokio_postgres::Client {
-        &mut self.0
+        &mut self.client
     }
 
     /// Consumes the client, returning the inner nonblocking client.
     pub fn into_inner(self) -> tokio_postgres::Client {
-        self.0
+        self.client
+    }
+
+    #[cfg(feature = "runtime")]
+    pub(crate) fn set_executor(&mut self, executor: Option<Arc<DynExecutor>>) {
+        self.executor = executor;
+    }
+
+    #[cfg(feature = "runtime")]
+    pub(crate) fn with_executor<F, T>(&self, f: F) -> T
+    where
+        F: FnOnce(&dyn Executor<Box<dyn Future<Item = (), Error = ()> + Send>>) -> T,
+    {
+        match &self.executor {
+            Some(e) => f(&**e),
+            None => f(&RUNTIME.executor()),
+        }
+    }
+}
+
+struct RoleGuard<'a> {
+    client: &'a mut Client,
+    done: bool,
+}
+
+impl<'a> Drop for RoleGuard<'a> {
+    fn drop(&mut self) {
+        if !self.done {
+            let _ = self.client.simple_query("RESET ROLE");
+        }
     }
 }
 
 impl From<tokio_postgres::Client> for Client {
     fn from(c: tokio_postgres::Client) -> Client {
-        Client(c)
+        Client {
+            client: c,
+            #[cfg(feature = "runtime")]
+            executor: None,
+        }
+    }
+}
+
+// Encodes rows into the binary COPY format as the copy consumes them. An encoding error aborts the copy, and is
+// stored in `error` so it can be returned as-is rather than wrapped as a stream error.
+struct TypedCopyInStream<'b, I> {
+    rows: I,
+    writer: Option<BinaryCopyWriter>,
+    error: &'b mut Option<Error>,
+}
+
+impl<'a, 'b, I, R> Stream for TypedCopyInStream<'b, I>
+where
+    I: Iterator<Item = R>,
+    R: AsRef<[&'a dyn ToSql]>,
+{
+    type Item = Vec<u8>;
+    type Error = io::Error;
+
+    fn poll(&mut self) -> Poll<Option<Vec<u8>>, io::Error> {
+        let mut writer = match self.writer.take() {
+            Some(writer) => writer,
+            None => return Ok(Async::Ready(None)),
+        };
+
+        while writer.buffered_bytes() < 64 * 1024 {
+            match self.rows.next() {
+                Some(row) => {
+                    if let Err(e) = writer.write(row.as_ref()) {
+                        *self.error = Some(e);
+                        return Err(io::Error::new(io::ErrorKind::InvalidInput, "invalid row"));
+                    }
+                }
+                None => return Ok(Async::Ready(Some(writer.finish()))),
+            }
+        }
+
+        let data = writer.take_data();
+        self.writer = Some(writer);
+        Ok(Async::Ready(Some(data)))
     }
 }
 
@@ -11,18 +11,24 @@ use std::path::Path;
 use std::str::FromStr;
 use std::sync::Arc;
 use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio_postgres::auth::Authenticator;
 use tokio_postgres::tls::{MakeTlsConnect, TlsConnect};
-use tokio_postgres::{Error, Socket};
+use tokio_postgres::{Error, Socket, Version};
 
 #[doc(inline)]
-use tokio_postgres::config::{SslMode, TargetSessionAttrs};
+use tokio_postgres::config::{ReplicationMode, SslMode, TargetSessionAttrs};
 
 use crate::{Client, RUNTIME};
 
-type DynExecutor = dyn Executor<Box<dyn Future<Item = (), Error = ()> + Send>> + Sync + Send;
+pub(crate) type DynExecutor =
+    dyn Executor<Box<dyn Future<Item = (), Error = ()> + Send>> + Sync + Send;
 
 /// Connection configuration.
 ///
+/// In addition to the parameters configured here, the client sets the `client_encoding` session parameter to `UTF8`
+/// and `TimeZone` to `GMT` on startup unless configured otherwise with the `client_encoding` and `timezone` methods.
+///
 /// Configuration can be parsed from libpq-style connection strings. These strings come in two formats:
 ///
 /// # Key-Value
@@ -35,10 +41,15 @@ type DynExecutor = dyn Executor<Box<dyn Future<Item = (), Error = ()> + Send>> +
 /// * `user` - The username to authenticate with. Required.
 /// * `password` - The password to authenticate with.
 /// * `dbname` - The name of the database to connect to. Defaults to the username.
-/// * `options` - Command line options used to configure the server.
+/// * `options` - Command line options used to configure the server, such as
+///     `'-c search_path=myschema -c statement_timeout=5000'`. Spaces within an option's value must be escaped with a
+///     backslash, which itself must be escaped in the connection string.
 /// * `application_name` - Sets the `application_name` parameter on the server.
-/// * `sslmode` - Controls usage of TLS. If set to `disable`, TLS will not be used. If set to `prefer`, TLS will be used
-///     if available, but not used otherwise. If set to `require`, TLS will be forced to be used. Defaults to `prefer`.
+/// * `sslmode` - Controls usage of TLS. If set to `disable`, TLS will not be used. If set to `allow`, TLS will only be
+///     used if the server rejects an unencrypted session. If set to `prefer`, TLS will be used if available, but not
+///     used otherwise. If set to `require`, TLS will be forced to be used. `verify-ca` and `verify-full` also force TLS
+///     to be used, and additionally require the TLS connector to verify the server's certificate chain and, for
+///     `verify-full`, its hostname; the connection fails if the connector can't. Defaults to `prefer`.
 /// * `host` - The host to connect to. On Unix platforms, if the host starts with a `/` character it is treated as the
 ///     path to the directory containing Unix domain sockets. Otherwise, it is treated as a hostname. Multiple hosts
 ///     can be specified, separated by commas. Each host will be tried in turn when connecting. Required if connecting
@@ -52,9 +63,17 @@ type DynExecutor = dyn Executor<Box<dyn Future<Item = (), Error = ()> + Send>> +
 ///     This option is ignored when connecting with Unix sockets. Defaults to on.
 /// * `keepalives_idle` - The number of seconds of inactivity after which a keepalive message is sent to the server.
 ///     This option is ignored when connecting with Unix sockets. Defaults to 2 hours.
+/// * `proxy` - The `host:port` address of a SOCKS5 proxy through which TCP connections are made. The proxy connects to
+///     each host in turn on the client's behalf, resolving hostnames itself. The port defaults to 1080 if omitted.
+///     This option is ignored when connecting with Unix sockets.
+/// * `proxy_user` - The username to authenticate with the SOCKS5 proxy. If omitted, the proxy must allow connections
+///     without authentication.
+/// * `proxy_password` - The password to authenticate with the SOCKS5 proxy.
 /// * `target_session_attrs` - Specifies requirements of the session. If set to `read-write`, the client will check that
 ///     the `transaction_read_write` session parameter is set to `on`. This can be used to connect to the primary server
 ///     in a database cluster as opposed to the secondary read-only mirrors. Defaults to `all`.
+/// * `replication` - The replication mode: `true` for physical replication or `database` for logical replication.
+/// * `client_encoding` - The character encoding of text sent to and received from the server. Defaults to `UTF8`.
 ///
 /// ## Examples
 ///
@@ -98,7 +117,7 @@ type DynExecutor = dyn Executor<Box<dyn Future<Item = (), Error = ()> + Send>> +
 pub struct Config {
     config: tokio_postgres::Config,
     // this is an option since we don't want to boot up our default runtime unless we're actually going to use it.
-    executor: Option<Arc<DynExecutor>>,
+    pub(crate) executor: Option<Arc<DynExecutor>>,
 }
 
 impl fmt::Debug for Config {
@@ -225,6 +244,33 @@ impl Config {
         self
     }
 
+    /// Sets a SOCKS5 proxy through which TCP connections are made.
+    ///
+    /// The proxy is asked to connect to each host on the client's behalf, so hostnames are resolved by the proxy rather
+    /// than the client. TLS, if used, is negotiated with the server over the proxied connection. This is ignored for
+    /// Unix domain socket connections. Defaults to `None`.
+    pub fn proxy(&mut self, proxy: Option<(&str, u16)>) -> &mut Config {
+        self.config.proxy(proxy);
+        self
+    }
+
+    /// Sets the username to authenticate with the SOCKS5 proxy.
+    ///
+    /// If no username is set, only connections without authentication are offered to the proxy.
+    pub fn proxy_user(&mut self, proxy_user: &str) -> &mut Config {
+        self.config.proxy_user(proxy_user);
+        self
+    }
+
+    /// Sets the password to authenticate with the SOCKS5 proxy.
+    pub fn proxy_password<T>(&mut self, proxy_password: T) -> &mut Config
+    where
+        T: AsRef<[u8]>,
+    {
+        self.config.proxy_password(proxy_password);
+        self
+    }
+
     /// Sets the requirements of the session.
     ///
     /// This can be used to connect to the primary server in a clustered database rather than one of the read-only
@@ -237,9 +283,130 @@ impl Config {
         self
     }
 
+    /// Sets the `TimeZone` session parameter sent to the server on startup.
+    ///
+    /// The session time zone is used by the server whenever it converts between `TIMESTAMP WITH TIME ZONE` values and
+    /// local times, both in the text format of values such as those returned by `simple_query` and within SQL itself,
+    /// for example when casting to `TEXT` or `DATE`, or in `date_trunc` and `extract`. Binary values are unaffected.
+    /// If `None`, no time zone is sent and the server's default is used. Defaults to `GMT`.
+    pub fn timezone(&mut self, timezone: Option<&str>) -> &mut Config {
+        self.config.timezone(timezone);
+        self
+    }
+
+    /// Sets the `client_encoding` session parameter sent to the server on startup.
+    ///
+    /// `UTF8` is strongly recommended, since strings are always encoded and decoded as UTF-8 by the client. With any
+    /// other encoding, text values must be retrieved as `EncodedText` and decoded by the caller. The
+    /// connection fails if the server's encoding later changes from the one configured here, for example by a
+    /// `SET client_encoding` command. Defaults to `UTF8`.
+    pub fn client_encoding(&mut self, client_encoding: &str) -> &mut Config {
+        self.config.client_encoding(client_encoding);
+        self
+    }
+
+    /// Sets the `lc_numeric` session parameter sent to the server on startup.
+    ///
+    /// This controls the formatting of numbers by functions such as `to_char`. Values sent and received in the
+    /// binary format are not affected. If `None`, the server's default is used. Defaults to `None`.
+    pub fn lc_numeric(&mut self, lc_numeric: Option<&str>) -> &mut Config {
+        self.config.lc_numeric(lc_numeric);
+        self
+    }
+
+    /// Sets the `lc_monetary` session parameter sent to the server on startup.
+    ///
+    /// This controls the formatting of `MONEY` values in the text format, including their currency symbol and decimal
+    /// separator. Values sent and received in the binary format are not affected. If `None`, the server's default is
+    /// used. Defaults to `None`.
+    pub fn lc_monetary(&mut self, lc_monetary: Option<&str>) -> &mut Config {
+        self.config.lc_monetary(lc_monetary);
+        self
+    }
+
+    /// Sets the `extra_float_digits` session parameter sent to the server on startup.
+    ///
+    /// This controls the precision of `REAL` and `DOUBLE PRECISION` values in the text format, such as those returned
+    /// by `simple_query`. Values sent and received in the binary format are always exact. Servers before Postgres 12
+    /// round floats in the text format unless this is set to 3, while newer servers produce exact output whenever it
+    /// is positive, as it is by default. If `None`, the server's default is used. Defaults to `None`.
+    pub fn extra_float_digits(&mut self, extra_float_digits: Option<i32>) -> &mut Config {
+        self.config.extra_float_digits(extra_float_digits);
+        self
+    }
+
+    /// Sets the oldest server version the connection will accept.
+    ///
+    /// The version is checked against the `server_version` parameter reported by the server at the end of startup,
+    /// and the connection fails with an error whose source is an `UnsupportedServerVersion` if the server is older.
+    /// Defaults to `None`, which accepts any version.
+    pub fn require_server_version(&mut self, version: Option<Version>) -> &mut Config {
+        self.config.require_server_version(version);
+        self
+    }
+
+    /// Sets the prefix of the names of statements prepared by the client.
+    ///
+    /// Each statement is named with the prefix followed by a counter which is never reused, so statements prepared by
+    /// the client can't collide with each other. Libraries which also prepare statements on the connection with the
+    /// `PREPARE` command or through another driver layer can use a distinct prefix to avoid collisions with those
+    /// names. Defaults to `s`.
+    pub fn statement_name_prefix(&mut self, statement_name_prefix: &str) -> &mut Config {
+        self.config.statement_name_prefix(statement_name_prefix);
+        self
+    }
+
+    /// Sets the initial size of the buffer used to read messages from the server.
+    ///
+    /// Larger buffers reduce the number of reads needed for queries returning wide rows. Defaults to 8 KiB.
+    pub fn read_buffer_size(&mut self, read_buffer_size: usize) -> &mut Config {
+        self.config.read_buffer_size(read_buffer_size);
+        self
+    }
+
+    /// Sets the initial size of the buffer used to write messages to the server.
+    ///
+    /// Defaults to 8 KiB.
+    pub fn write_buffer_size(&mut self, write_buffer_size: usize) -> &mut Config {
+        self.config.write_buffer_size(write_buffer_size);
+        self
+    }
+
+    /// Sets the maximum size of a single message accepted from the server.
+    ///
+    /// A message whose declared length exceeds this limit causes the connection to fail rather than attempting to
+    /// buffer it, protecting against corrupt or malicious length prefixes. Queries returning very large values, such
+    /// as big `bytea` fields, may need a larger limit. Defaults to 64 MiB.
+    pub fn max_message_size(&mut self, max_message_size: usize) -> &mut Config {
+        self.config.max_message_size(max_message_size);
+        self
+    }
+
+    /// Sets the replication mode of the session.
+    ///
+    /// Replication connections accept the commands of the streaming replication protocol, such as
+    /// `IDENTIFY_SYSTEM`, through `Client::simple_query`. Defaults to `None`, which creates a normal connection.
+    pub fn replication_mode(&mut self, replication_mode: Option<ReplicationMode>) -> &mut Config {
+        self.config.replication_mode(replication_mode);
+        self
+    }
+
+    /// Sets the handler used for authentication mechanisms which are not natively supported, such as GSSAPI and
+    /// SSPI.
+    ///
+    /// Without an authenticator, connections to servers requesting those mechanisms will fail.
+    pub fn authenticator<A>(&mut self, authenticator: A) -> &mut Config
+    where
+        A: Authenticator + 'static,
+    {
+        self.config.authenticator(authenticator);
+        self
+    }
+
     /// Sets the executor used to run the connection futures.
     ///
-    /// Defaults to a postgres-specific tokio `Runtime`.
+    /// Clients connected with this configuration also use it to run the timeout of `Client::execute_with_deadline`,
+    /// so it must provide a timer. Defaults to a postgres-specific tokio `Runtime`.
     pub fn executor<E>(&mut self, executor: E) -> &mut Config
     where
         E: Executor<Box<dyn Future<Item = (), Error = ()> + Send>> + 'static + Sync + Send,
@@ -269,7 +436,38 @@ impl Config {
         self.with_executor(|e| e.execute(Box::new(connection)))
             .unwrap();
 
-        Ok(Client::from(client))
+        let mut client = Client::from(client);
+        client.set_executor(self.executor.clone());
+        Ok(client)
+    }
+
+    /// Opens a connection to a PostgreSQL database over an existing stream.
+    ///
+    /// The stream is used as-is, which allows connections to be made through tunnels, proxies, or custom transports.
+    /// The `host`, `port`, `connect_timeout`, `keepalives`, `keepalives_idle`, and `proxy` options are ignored.
+    pub fn connect_raw<S, T>(&self, stream: S, tls_mode: T) -> Result<Client, Error>
+    where
+        S: AsyncRead + AsyncWrite + 'static + Send,
+        T: TlsConnect<S> + 'static + Send,
+        T::Stream: Send,
+        T::Future: Send,
+    {
+        let (tx, rx) = oneshot::channel();
+        let connect = self
+            .config
+            .connect_raw(stream, tls_mode)
+            .then(|r| tx.send(r).map_err(|_| ()));
+        self.with_executor(|e| e.execute(Box::new(connect)))
+            .unwrap();
+        let (client, connection) = rx.wait().unwrap()?;
+
+        let connection = connection.map_err(|e| error!("postgres connection error: {}", e));
+        self.with_executor(|e| e.execute(Box::new(connection)))
+            .unwrap();
+
+        let mut client = Client::from(client);
+        client.set_executor(self.executor.clone());
+        Ok(client)
     }
 
     fn with_executor<F, T>(&self, f: F) -> T
@@ -1,13 +1,14 @@
 use bytes::{Buf, Bytes};
 use futures::stream::{self, Stream};
+use futures::Poll;
 use std::io::{self, BufRead, Cursor, Read};
 use std::marker::PhantomData;
 use tokio_postgres::impls;
 use tokio_postgres::Error;
 
-/// The reader returned by the `copy_out` method.
+/// The reader returned by the `copy_out` and `query_field` methods.
 pub struct CopyOutReader<'a> {
-    it: stream::Wait<impls::CopyOut>,
+    it: stream::Wait<Source>,
     cur: Cursor<Bytes>,
     _p: PhantomData<&'a mut ()>,
 }
@@ -20,7 +21,15 @@ impl<'a> Drop for CopyOutReader<'a> {
 impl<'a> CopyOutReader<'a> {
     #[allow(clippy::new_ret_no_self)]
     pub(crate) fn new(stream: impls::CopyOut) -> Result<CopyOutReader<'a>, Error> {
-        let mut it = stream.wait();
+        CopyOutReader::from_source(Source::CopyOut(stream))
+    }
+
+    pub(crate) fn field(stream: impls::QueryField) -> Result<CopyOutReader<'a>, Error> {
+        CopyOutReader::from_source(Source::Field(stream))
+    }
+
+    fn from_source(source: Source) -> Result<CopyOutReader<'a>, Error> {
+        let mut it = source.wait();
         let cur = match it.next() {
             Some(Ok(cur)) => cur,
             Some(Err(e)) => return Err(e),
@@ -35,6 +44,23 @@ impl<'a> CopyOutReader<'a> {
     }
 }
 
+enum Source {
+    CopyOut(impls::CopyOut),
+    Field(impls::QueryField),
+}
+
+impl Stream for Source {
+    type Item = Bytes;
+    type Error = Error;
+
+    fn poll(&mut self) -> Poll<Option<Bytes>, Error> {
+        match self {
+            Source::CopyOut(s) => s.poll(),
+            Source::Field(s) => s.poll(),
+        }
+    }
+}
+
 impl<'a> Read for CopyOutReader<'a> {
     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
         let b = self.fill_buf()?;
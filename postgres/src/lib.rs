@@ -63,15 +63,19 @@ use tokio::runtime::{self, Runtime};
 #[cfg(feature = "runtime")]
 pub use tokio_postgres::Socket;
 pub use tokio_postgres::{
-    accepts, error, row, tls, to_sql_checked, types, Column, Portal, SimpleQueryMessage, Statement,
+    accepts, auth, binary_copy, error, row, tls, to_sql_checked, types, Column, ConnectionState,
+    ParseVersionError, Portal, SimpleQueryMessage, Statement, Version,
 };
 
+#[cfg(feature = "runtime")]
+pub use crate::cancellable_query_iter::*;
 pub use crate::client::*;
 #[cfg(feature = "runtime")]
 pub use crate::config::Config;
 pub use crate::copy_out_reader::*;
 #[doc(no_inline)]
 pub use crate::error::Error;
+pub use crate::progress_reader::*;
 pub use crate::query_iter::*;
 pub use crate::query_portal_iter::*;
 #[doc(no_inline)]
@@ -82,10 +86,13 @@ pub use crate::tls::NoTls;
 pub use crate::to_statement::*;
 pub use crate::transaction::*;
 
+#[cfg(feature = "runtime")]
+mod cancellable_query_iter;
 mod client;
 #[cfg(feature = "runtime")]
 pub mod config;
 mod copy_out_reader;
+mod progress_reader;
 mod query_iter;
 mod query_portal_iter;
 mod simple_query_iter;
@@ -103,3 +110,7 @@ lazy_static! {
         .build()
         .unwrap();
 }
+
+fn quote_identifier(s: &str) -> String {
+    format!("\"{}\"", s.replace('"', "\"\""))
+}
@@ -0,0 +1,122 @@
+use std::io::{self, BufRead, Read};
+
+/// A reader which reports the number of bytes read from an inner reader as it goes.
+///
+/// This can wrap the reader passed to `copy_in`, or the reader returned by `copy_out`, to track the progress of a
+/// long-running `COPY`. The callback is invoked with the cumulative number of bytes read each time at least `interval`
+/// more bytes have been read since it was last invoked, and once more with the final total when the end of the inner
+/// reader is reached.
+///
+/// # Examples
+///
+/// ```no_run
+/// use postgres::{Client, NoTls, ProgressReader};
+/// use std::fs::File;
+///
+/// # fn main() -> Result<(), Box<std::error::Error>> {
+/// let mut client = Client::connect("host=localhost user=postgres", NoTls)?;
+///
+/// let file = File::open("people.tsv")?;
+/// let len = file.metadata()?.len();
+/// let reader = ProgressReader::new(file, 1024 * 1024, |read| {
+///     println!("{}% loaded", read * 100 / len);
+/// });
+/// client.copy_in("COPY people FROM stdin", &[], reader)?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct ProgressReader<R, F> {
+    reader: R,
+    callback: F,
+    interval: u64,
+    read: u64,
+    reported: u64,
+}
+
+impl<R, F> ProgressReader<R, F>
+where
+    F: FnMut(u64),
+{
+    /// Creates a new `ProgressReader` which invokes `callback` every `interval` bytes.
+    pub fn new(reader: R, interval: u64, callback: F) -> ProgressReader<R, F> {
+        ProgressReader {
+            reader,
+            callback,
+            interval,
+            read: 0,
+            reported: 0,
+        }
+    }
+
+    /// Returns the number of bytes read so far.
+    pub fn bytes_read(&self) -> u64 {
+        self.read
+    }
+
+    /// Returns a shared reference to the inner reader.
+    pub fn get_ref(&self) -> &R {
+        &self.reader
+    }
+
+    /// Returns a mutable reference to the inner reader.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.reader
+    }
+
+    /// Consumes the `ProgressReader`, returning the inner reader.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    fn advance(&mut self, amt: usize) {
+        self.read += amt as u64;
+        if self.read - self.reported >= self.interval {
+            self.report();
+        }
+    }
+
+    fn finish(&mut self) {
+        if self.read != self.reported {
+            self.report();
+        }
+    }
+
+    fn report(&mut self) {
+        self.reported = self.read;
+        (self.callback)(self.read);
+    }
+}
+
+impl<R, F> Read for ProgressReader<R, F>
+where
+    R: Read,
+    F: FnMut(u64),
+{
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let len = self.reader.read(buf)?;
+        if len == 0 && !buf.is_empty() {
+            self.finish();
+        } else {
+            self.advance(len);
+        }
+        Ok(len)
+    }
+}
+
+impl<R, F> BufRead for ProgressReader<R, F>
+where
+    R: BufRead,
+    F: FnMut(u64),
+{
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        if self.reader.fill_buf()?.is_empty() {
+            self.finish();
+        }
+        self.reader.fill_buf()
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.reader.consume(amt);
+        self.advance(amt);
+    }
+}
@@ -1,5 +1,10 @@
-use std::io::Read;
-use tokio_postgres::types::Type;
+use fallible_iterator::FallibleIterator;
+use std::collections::HashMap;
+use std::io::{self, Read};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+use tokio_postgres::error::SqlState;
+use tokio_postgres::types::{FromSql, ToSql, Type};
 use tokio_postgres::NoTls;
 
 use super::*;
@@ -95,6 +100,66 @@ fn transaction_drop() {
     assert_eq!(rows.len(), 0);
 }
 
+#[test]
+fn execute_batch_transactional_commit() {
+    let mut client = Client::connect("host=localhost port=5433 user=postgres", NoTls).unwrap();
+
+    client
+        .simple_query("CREATE TEMPORARY TABLE foo (id INT PRIMARY KEY)")
+        .unwrap();
+
+    client
+        .execute_batch_transactional(
+            "INSERT INTO foo (id) VALUES (1);
+             INSERT INTO foo (id) VALUES (2);",
+        )
+        .unwrap();
+
+    let rows = client.query("SELECT id FROM foo ORDER BY id", &[]).unwrap();
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0].get::<_, i32>(0), 1);
+    assert_eq!(rows[1].get::<_, i32>(0), 2);
+}
+
+#[test]
+fn execute_batch_transactional_rollback() {
+    let mut client = Client::connect("host=localhost port=5433 user=postgres", NoTls).unwrap();
+
+    client
+        .simple_query("CREATE TEMPORARY TABLE foo (id INT PRIMARY KEY)")
+        .unwrap();
+
+    client
+        .execute_batch_transactional(
+            "INSERT INTO foo (id) VALUES (1);
+             INSERT INTO foo (id) VALUES (1);",
+        )
+        .unwrap_err();
+
+    let rows = client.query("SELECT id FROM foo", &[]).unwrap();
+    assert_eq!(rows.len(), 0);
+}
+
+#[test]
+fn execute_batch_transactional_not_in_transaction_block() {
+    let mut client = Client::connect("host=localhost port=5433 user=postgres", NoTls).unwrap();
+
+    client
+        .simple_query("CREATE TEMPORARY TABLE foo (id INT)")
+        .unwrap();
+
+    let err = client
+        .execute_batch_transactional(
+            "INSERT INTO foo (id) VALUES (1);
+             CREATE INDEX CONCURRENTLY foo_id ON foo (id);",
+        )
+        .unwrap_err();
+    assert_eq!(err.code(), Some(&SqlState::ACTIVE_SQL_TRANSACTION));
+
+    let rows = client.query("SELECT id FROM foo", &[]).unwrap();
+    assert_eq!(rows.len(), 0);
+}
+
 #[test]
 fn nested_transactions() {
     let mut client = Client::connect("host=localhost port=5433 user=postgres", NoTls).unwrap();
@@ -173,6 +238,69 @@ fn copy_in() {
     assert_eq!(rows[1].get::<_, &str>(1), "timothy");
 }
 
+#[test]
+fn copy_in_typed() {
+    let mut client = Client::connect("host=localhost port=5433 user=postgres", NoTls).unwrap();
+
+    client
+        .simple_query("CREATE TEMPORARY TABLE foo (id INT, name TEXT)")
+        .unwrap();
+
+    let rows: Vec<[&dyn ToSql; 2]> = vec![[&1i32, &"steven"], [&2i32, &"timothy"]];
+    let count = client.copy_in_typed("foo", &["id", "name"], &rows).unwrap();
+    assert_eq!(count, 2);
+
+    let rows: Vec<Vec<&dyn ToSql>> = vec![vec![&3i32, &"bob"], vec![&"four", &"alice"]];
+    let err = client
+        .copy_in_typed("foo", &["id", "name"], &rows)
+        .unwrap_err();
+    assert!(err.to_string().contains("row 1"));
+
+    let rows = client
+        .query("SELECT id, name FROM foo ORDER BY id", &[])
+        .unwrap();
+
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0].get::<_, i32>(0), 1);
+    assert_eq!(rows[0].get::<_, &str>(1), "steven");
+    assert_eq!(rows[1].get::<_, i32>(0), 2);
+    assert_eq!(rows[1].get::<_, &str>(1), "timothy");
+}
+
+#[test]
+fn copy_in_typed_streaming() {
+    let mut client = Client::connect("host=localhost port=5433 user=postgres", NoTls).unwrap();
+
+    client
+        .simple_query("CREATE TEMPORARY TABLE foo (id INT)")
+        .unwrap();
+
+    // enough rows to be sent in several chunks
+    let ids = (0..20_000).collect::<Vec<i32>>();
+    let bad = "bad";
+    let rows = ids.iter().map(|id| -> [&dyn ToSql; 1] {
+        if *id == 15_000 {
+            [&bad]
+        } else {
+            [id]
+        }
+    });
+    let err = client.copy_in_typed("foo", &["id"], rows).unwrap_err();
+    assert!(err.to_string().contains("row 15000"), "{}", err);
+
+    let count = client.query_one("SELECT COUNT(*) FROM foo", &[]).unwrap();
+    assert_eq!(count.get::<_, i64>(0), 0);
+
+    let rows = ids.iter().map(|id| [id as &dyn ToSql]);
+    let count = client.copy_in_typed("foo", &["id"], rows).unwrap();
+    assert_eq!(count, 20_000);
+
+    let row = client
+        .query_one("SELECT COUNT(*), SUM(id) FROM foo", &[])
+        .unwrap();
+    assert_eq!(row.get::<_, i64>(0), 20_000);
+}
+
 #[test]
 fn copy_out() {
     let mut client = Client::connect("host=localhost port=5433 user=postgres", NoTls).unwrap();
@@ -196,6 +324,66 @@ fn copy_out() {
     client.simple_query("SELECT 1").unwrap();
 }
 
+#[test]
+fn copy_progress() {
+    let mut client = Client::connect("host=localhost port=5433 user=postgres", NoTls).unwrap();
+
+    client
+        .simple_query("CREATE TEMPORARY TABLE foo (id INT, name TEXT)")
+        .unwrap();
+
+    let data = (0..100)
+        .map(|i| format!("{}\tname {}\n", i, i))
+        .collect::<String>();
+
+    let mut progress = vec![];
+    let reader = ProgressReader::new(data.as_bytes(), 100, |read| progress.push(read));
+    client.copy_in("COPY foo FROM stdin", &[], reader).unwrap();
+    assert!(progress.len() > 1);
+    assert!(progress.windows(2).all(|w| w[0] < w[1]));
+    assert_eq!(*progress.last().unwrap(), data.len() as u64);
+
+    let mut progress = vec![];
+    let mut s = String::new();
+    let reader = client.copy_out("COPY foo TO STDOUT", &[]).unwrap();
+    let mut reader = ProgressReader::new(reader, 100, |read| progress.push(read));
+    reader.read_to_string(&mut s).unwrap();
+    assert_eq!(reader.bytes_read(), data.len() as u64);
+    drop(reader);
+
+    assert_eq!(s, data);
+    assert_eq!(*progress.last().unwrap(), data.len() as u64);
+}
+
+#[test]
+fn large_objects() {
+    let mut client = Client::connect("host=localhost port=5433 user=postgres", NoTls).unwrap();
+    let mut transaction = client.transaction().unwrap();
+
+    let oid = transaction
+        .query_one("SELECT lo_from_bytea(0, '\\x0102030405')", &[])
+        .unwrap()
+        .get::<_, u32>(0);
+    let empty = transaction
+        .query_one("SELECT lo_create(0)", &[])
+        .unwrap()
+        .get::<_, u32>(0);
+
+    assert_eq!(transaction.large_object_size(oid).unwrap(), 5);
+    assert_eq!(transaction.large_object_size(empty).unwrap(), 0);
+    let objects = transaction.large_objects().unwrap();
+    assert!(objects.contains(&oid));
+    assert!(objects.contains(&empty));
+
+    transaction.unlink_large_object(oid).unwrap();
+    assert!(!transaction.large_objects().unwrap().contains(&oid));
+
+    let mut nested = transaction.transaction().unwrap();
+    assert!(nested.large_object_size(oid).is_err());
+    nested.rollback().unwrap();
+    assert!(transaction.unlink_large_object(oid).is_err());
+}
+
 #[test]
 fn portal() {
     let mut client = Client::connect("host=localhost port=5433 user=postgres", NoTls).unwrap();
@@ -222,3 +410,722 @@ fn portal() {
     assert_eq!(rows.len(), 1);
     assert_eq!(rows[0].get::<_, i32>(0), 3);
 }
+
+#[test]
+fn cursor() {
+    let mut client = Client::connect("host=localhost port=5433 user=postgres", NoTls).unwrap();
+
+    client
+        .simple_query(
+            "CREATE TEMPORARY TABLE foo (id INT);
+             INSERT INTO foo (id) VALUES (1), (2), (3);
+             CREATE FUNCTION pg_temp.open_foo() RETURNS refcursor AS $$
+             DECLARE
+                 c refcursor := 'Foo Cursor';
+             BEGIN
+                 OPEN c FOR SELECT id FROM foo ORDER BY id;
+                 RETURN c;
+             END
+             $$ LANGUAGE plpgsql;",
+        )
+        .unwrap();
+
+    let mut transaction = client.transaction().unwrap();
+
+    let rows = transaction.query("SELECT pg_temp.open_foo()", &[]).unwrap();
+    let name = rows[0].get::<_, String>(0);
+    assert_eq!(name, "Foo Cursor");
+
+    let rows = transaction.query_cursor(&name, 2).unwrap();
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0].get::<_, i32>(0), 1);
+    assert_eq!(rows[1].get::<_, i32>(0), 2);
+
+    let rows = transaction.query_cursor(&name, 0).unwrap();
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].get::<_, i32>(0), 3);
+}
+
+#[test]
+fn cursor_with_hold() {
+    let mut client = Client::connect("host=localhost port=5433 user=postgres", NoTls).unwrap();
+
+    let mut transaction = client.transaction().unwrap();
+    transaction
+        .declare_cursor_with_hold(
+            "Held Cursor",
+            "SELECT x FROM generate_series(1, $1) x",
+            &[&5i32],
+        )
+        .unwrap();
+    let rows = transaction.query_cursor("Held Cursor", 2).unwrap();
+    assert_eq!(rows.len(), 2);
+    transaction.commit().unwrap();
+
+    let rows = client.query_cursor("Held Cursor", 2).unwrap();
+    assert_eq!(rows[0].get::<_, i32>(0), 3);
+    assert_eq!(rows[1].get::<_, i32>(0), 4);
+
+    // other work can be committed between fetches
+    let mut transaction = client.transaction().unwrap();
+    transaction.execute("SELECT 1", &[]).unwrap();
+    transaction.commit().unwrap();
+
+    let rows = client.query_cursor("Held Cursor", 0).unwrap();
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].get::<_, i32>(0), 5);
+
+    client.close_cursor("Held Cursor").unwrap();
+    assert!(client.query_cursor("Held Cursor", 0).is_err());
+
+    // cursors declared without hold are closed by the commit
+    let mut transaction = client.transaction().unwrap();
+    transaction.declare_cursor("foo", "SELECT 1", &[]).unwrap();
+    transaction.commit().unwrap();
+    assert!(client.query_cursor("foo", 0).is_err());
+}
+
+#[test]
+fn execute_with_deadline() {
+    let mut client = Client::connect("host=localhost port=5433 user=postgres", NoTls).unwrap();
+
+    let n = client
+        .execute_with_deadline("SELECT 1", &[], Duration::from_secs(10), NoTls)
+        .unwrap();
+    assert_eq!(n, 1);
+
+    let start = Instant::now();
+    let err = client
+        .execute_with_deadline(
+            "SELECT pg_sleep(10)",
+            &[],
+            Duration::from_millis(100),
+            NoTls,
+        )
+        .unwrap_err();
+    assert_eq!(err.code(), Some(&SqlState::QUERY_CANCELED));
+    assert!(start.elapsed() < Duration::from_secs(5));
+
+    let rows = client.query("SELECT 1::INT", &[]).unwrap();
+    assert_eq!(rows[0].get::<_, i32>(0), 1);
+}
+
+#[test]
+fn execute_with_deadline_custom_executor() {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let mut client = "host=localhost port=5433 user=postgres"
+        .parse::<Config>()
+        .unwrap()
+        .executor(runtime.executor())
+        .connect(NoTls)
+        .unwrap();
+
+    let err = client
+        .execute_with_deadline(
+            "SELECT pg_sleep(10)",
+            &[],
+            Duration::from_millis(100),
+            NoTls,
+        )
+        .unwrap_err();
+    assert_eq!(err.code(), Some(&SqlState::QUERY_CANCELED));
+
+    let mut client2 = client.connect_another(NoTls).unwrap();
+    let n = client2
+        .execute_with_deadline("SELECT 1", &[], Duration::from_secs(10), NoTls)
+        .unwrap();
+    assert_eq!(n, 1);
+}
+
+#[test]
+fn connect_raw() {
+    let stream = std::net::TcpStream::connect("127.0.0.1:5433").unwrap();
+    let stream =
+        tokio::net::TcpStream::from_std(stream, &tokio::reactor::Handle::default()).unwrap();
+
+    let mut client = "user=postgres"
+        .parse::<Config>()
+        .unwrap()
+        .connect_raw(stream, NoTls)
+        .unwrap();
+
+    let rows = client.query("SELECT 1::INT", &[]).unwrap();
+    assert_eq!(rows[0].get::<_, i32>(0), 1);
+}
+
+#[test]
+fn close_statement() {
+    let mut client = Client::connect("host=localhost port=5433 user=postgres", NoTls).unwrap();
+
+    let count = |client: &mut Client| {
+        client
+            .query("SELECT COUNT(*) FROM pg_prepared_statements", &[])
+            .unwrap()[0]
+            .get::<_, i64>(0)
+    };
+
+    let stmt = client.prepare("SELECT 1").unwrap();
+    let clone = stmt.clone();
+    assert_eq!(count(&mut client), 2);
+
+    client.close_statement(stmt).unwrap();
+    assert_eq!(count(&mut client), 1);
+
+    let err = client.query(&clone, &[]).err().unwrap();
+    assert_eq!(err.code(), Some(&SqlState::INVALID_SQL_STATEMENT_NAME));
+}
+
+#[test]
+fn prepare_and_query() {
+    let mut client = Client::connect("host=localhost port=5433 user=postgres", NoTls).unwrap();
+
+    let (stmt, rows) = client
+        .prepare_and_query("SELECT $1::INT + 1", &[Type::INT4], &[&1i32])
+        .unwrap();
+    assert_eq!(rows[0].get::<_, i32>(0), 2);
+
+    let rows = client.query(&stmt, &[&41i32]).unwrap();
+    assert_eq!(rows[0].get::<_, i32>(0), 42);
+
+    let mut transaction = client.transaction().unwrap();
+    let (stmt, rows) = transaction
+        .prepare_and_query("SELECT $1::TEXT", &[Type::TEXT], &[&"foo"])
+        .unwrap();
+    assert_eq!(rows[0].get::<_, &str>(0), "foo");
+    let rows = transaction.query(&stmt, &[&"bar"]).unwrap();
+    assert_eq!(rows[0].get::<_, &str>(0), "bar");
+}
+
+#[test]
+fn timezone() {
+    let source = |client: &mut Client| {
+        let rows = client
+            .query(
+                "SELECT current_setting('TimeZone'), source FROM pg_settings WHERE name = 'TimeZone'",
+                &[],
+            )
+            .unwrap();
+        (rows[0].get::<_, String>(0), rows[0].get::<_, String>(1))
+    };
+
+    let mut config = "host=localhost port=5433 user=postgres"
+        .parse::<Config>()
+        .unwrap();
+
+    let mut client = config.connect(NoTls).unwrap();
+    assert_eq!(
+        source(&mut client),
+        ("GMT".to_string(), "client".to_string())
+    );
+
+    let mut client = config
+        .timezone(Some("America/New_York"))
+        .connect(NoTls)
+        .unwrap();
+    assert_eq!(
+        source(&mut client),
+        ("America/New_York".to_string(), "client".to_string())
+    );
+
+    let mut client = config.timezone(None).connect(NoTls).unwrap();
+    assert_ne!(source(&mut client).1, "client");
+}
+
+#[test]
+fn locale() {
+    let source = |client: &mut Client, name: &str| {
+        let rows = client
+            .query(
+                "SELECT setting, source FROM pg_settings WHERE name = $1",
+                &[&name],
+            )
+            .unwrap();
+        (rows[0].get::<_, String>(0), rows[0].get::<_, String>(1))
+    };
+
+    let mut config = "host=localhost port=5433 user=postgres"
+        .parse::<Config>()
+        .unwrap();
+
+    let mut client = config.connect(NoTls).unwrap();
+    assert_ne!(source(&mut client, "lc_numeric").1, "client");
+    assert_ne!(source(&mut client, "lc_monetary").1, "client");
+
+    let mut client = config
+        .lc_numeric(Some("POSIX"))
+        .lc_monetary(Some("POSIX"))
+        .connect(NoTls)
+        .unwrap();
+    assert_eq!(
+        source(&mut client, "lc_numeric"),
+        ("POSIX".to_string(), "client".to_string())
+    );
+    assert_eq!(
+        source(&mut client, "lc_monetary"),
+        ("POSIX".to_string(), "client".to_string())
+    );
+
+    assert!(config
+        .lc_monetary(Some("no_such_locale"))
+        .connect(NoTls)
+        .is_err());
+}
+
+#[test]
+fn extra_float_digits() {
+    let mut client = "host=localhost port=5433 user=postgres"
+        .parse::<Config>()
+        .unwrap()
+        .extra_float_digits(Some(3))
+        .connect(NoTls)
+        .unwrap();
+
+    let rows = client.query("SHOW extra_float_digits", &[]).unwrap();
+    assert_eq!(rows[0].get::<_, &str>(0), "3");
+
+    let messages = client
+        .simple_query("SELECT 0.1::FLOAT8 + 0.2::FLOAT8")
+        .unwrap();
+    match &messages[1] {
+        SimpleQueryMessage::Row(row) => {
+            let value = row.get(0).unwrap().parse::<f64>().unwrap();
+            assert_eq!(value, 0.1 + 0.2);
+        }
+        _ => panic!("unexpected message"),
+    }
+}
+
+#[test]
+fn with_role() {
+    let mut client = Client::connect("host=localhost port=5433 user=postgres", NoTls).unwrap();
+
+    client
+        .simple_query(
+            "DO $$ BEGIN
+                 CREATE ROLE \"Tenant \"\"1\"\"\";
+             EXCEPTION WHEN duplicate_object THEN NULL;
+             END $$",
+        )
+        .unwrap();
+
+    let current_user = |client: &mut Client| {
+        let rows = client.query("SELECT current_user::TEXT", &[]).unwrap();
+        rows[0].get::<_, String>(0)
+    };
+
+    let user = client
+        .with_role("Tenant \"1\"", |client| Ok(current_user(client)))
+        .unwrap();
+    assert_eq!(user, "Tenant \"1\"");
+    assert_eq!(current_user(&mut client), "postgres");
+
+    let err = client
+        .with_role("Tenant \"1\"", |client| {
+            client.execute("SELECT 1/0", &[]).map(|_| ())
+        })
+        .unwrap_err();
+    assert_eq!(err.code(), Some(&SqlState::DIVISION_BY_ZERO));
+    assert_eq!(current_user(&mut client), "postgres");
+
+    assert!(client.with_role("\"; DROP", |_| Ok(())).is_err());
+
+    let mut transaction = client.transaction().unwrap();
+    transaction.set_role("Tenant \"1\"").unwrap();
+    let rows = transaction.query("SELECT current_user::TEXT", &[]).unwrap();
+    assert_eq!(rows[0].get::<_, &str>(0), "Tenant \"1\"");
+    transaction.commit().unwrap();
+    assert_eq!(current_user(&mut client), "postgres");
+}
+
+#[test]
+fn state() {
+    let mut client = Client::connect("host=localhost port=5433 user=postgres", NoTls).unwrap();
+    assert_eq!(client.state(), ConnectionState::Idle);
+
+    let mut transaction = client.transaction().unwrap();
+    assert_eq!(transaction.state(), ConnectionState::InTransaction);
+    transaction.execute("SELECT 1/0", &[]).unwrap_err();
+    assert_eq!(transaction.state(), ConnectionState::InFailedTransaction);
+    transaction.rollback().unwrap();
+    assert_eq!(client.state(), ConnectionState::Idle);
+
+    let mut transaction = client.transaction().unwrap();
+    transaction.execute("SELECT 1", &[]).unwrap();
+    assert_eq!(transaction.state(), ConnectionState::InTransaction);
+}
+
+#[test]
+fn query_by_keys() {
+    let mut client = Client::connect("host=localhost port=5433 user=postgres", NoTls).unwrap();
+
+    client
+        .simple_query(
+            "CREATE TEMPORARY TABLE \"Person\" (id INT PRIMARY KEY, name TEXT NOT NULL);
+             INSERT INTO \"Person\" VALUES (1, 'alice'), (2, 'bob'), (3, 'carol')",
+        )
+        .unwrap();
+
+    let rows = client.query_by_keys("Person", "id", &[3i32, 1, 4]).unwrap();
+    let mut names = rows
+        .iter()
+        .map(|row| row.get::<_, String>("name"))
+        .collect::<Vec<_>>();
+    names.sort();
+    assert_eq!(names, ["alice", "carol"]);
+
+    let rows = client.query_by_keys::<i32>("Person", "id", &[]).unwrap();
+    assert!(rows.is_empty());
+
+    let mut transaction = client.transaction().unwrap();
+    let rows = transaction
+        .query_by_keys("Person", "name", &["bob"])
+        .unwrap();
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].get::<_, i32>("id"), 2);
+}
+
+#[test]
+fn upsert_counts() {
+    let mut client = Client::connect("host=localhost port=5433 user=postgres", NoTls).unwrap();
+
+    client
+        .simple_query("CREATE TEMPORARY TABLE foo (id INT PRIMARY KEY, name TEXT)")
+        .unwrap();
+
+    let insert = "INSERT INTO foo (id, name) VALUES ($1, $2) ON CONFLICT DO NOTHING";
+    assert_eq!(client.execute(insert, &[&1i32, &"alice"]).unwrap(), 1);
+    assert_eq!(client.execute(insert, &[&1i32, &"bob"]).unwrap(), 0);
+
+    let upsert = "INSERT INTO foo (id, name) VALUES ($1, $2)
+                  ON CONFLICT (id) DO UPDATE SET name = EXCLUDED.name
+                  RETURNING (xmax = 0) AS inserted";
+    let rows = client.query(upsert, &[&2i32, &"bob"]).unwrap();
+    assert!(rows[0].get::<_, bool>("inserted"));
+    let rows = client.query(upsert, &[&1i32, &"carol"]).unwrap();
+    assert!(!rows[0].get::<_, bool>("inserted"));
+}
+
+#[test]
+fn take_error() {
+    let mut client = Client::connect("host=localhost port=5433 user=postgres", NoTls).unwrap();
+    let mut admin = Client::connect("host=localhost port=5433 user=postgres", NoTls).unwrap();
+
+    assert!(client.take_error().is_none());
+
+    let pid = client.query("SELECT pg_backend_pid()", &[]).unwrap()[0].get::<_, i32>(0);
+    admin
+        .execute("SELECT pg_terminate_backend($1)", &[&pid])
+        .unwrap();
+
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while !client.is_closed() {
+        assert!(Instant::now() < deadline, "connection was not closed");
+        thread::sleep(Duration::from_millis(10));
+    }
+
+    let error = client.take_error().unwrap();
+    assert_eq!(error.code(), &SqlState::ADMIN_SHUTDOWN);
+    assert!(client.take_error().is_none());
+}
+
+#[test]
+fn statement_name_prefix() {
+    let mut client = "host=localhost port=5433 user=postgres"
+        .parse::<Config>()
+        .unwrap()
+        .statement_name_prefix("mylib_")
+        .connect(NoTls)
+        .unwrap();
+
+    client.prepare("SELECT 1").unwrap();
+    let rows = client
+        .query("SELECT name FROM pg_prepared_statements", &[])
+        .unwrap();
+    assert!(!rows.is_empty());
+    for row in &rows {
+        assert!(row.get::<_, &str>(0).starts_with("mylib_"));
+    }
+}
+
+#[test]
+fn explain() {
+    struct RawJson(String);
+
+    impl<'a> FromSql<'a> for RawJson {
+        fn from_sql(
+            _: &Type,
+            raw: &'a [u8],
+        ) -> Result<RawJson, Box<dyn std::error::Error + Sync + Send>> {
+            Ok(RawJson(std::str::from_utf8(raw)?.to_string()))
+        }
+
+        accepts!(JSON);
+    }
+
+    let mut client = Client::connect("host=localhost port=5433 user=postgres", NoTls).unwrap();
+
+    client
+        .simple_query("CREATE TEMPORARY TABLE foo (id INT PRIMARY KEY, name TEXT)")
+        .unwrap();
+
+    let plan = client
+        .explain("SELECT name FROM foo WHERE id = $1", &[&1i32])
+        .unwrap();
+    assert!(plan[0].contains("foo"), "{:?}", plan);
+
+    let plan = client
+        .explain_json::<RawJson>("SELECT name FROM foo WHERE id = $1", &[&1i32])
+        .unwrap();
+    assert!(plan.0.contains("\"Relation Name\": \"foo\""), "{}", plan.0);
+
+    let mut transaction = client.transaction().unwrap();
+    assert!(!transaction
+        .explain("SELECT name FROM foo", &[])
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn get_tuple() {
+    let mut client = Client::connect("host=localhost port=5433 user=postgres", NoTls).unwrap();
+
+    client
+        .simple_query(
+            "CREATE TEMPORARY TABLE foo (
+                id SERIAL PRIMARY KEY,
+                name TEXT NOT NULL,
+                created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+            )",
+        )
+        .unwrap();
+
+    let rows = client
+        .query(
+            "INSERT INTO foo (name) VALUES ('alice'), ('bob') RETURNING id, created_at",
+            &[],
+        )
+        .unwrap();
+    let inserted = rows
+        .into_iter()
+        .map(|r| r.get_tuple::<(i32, SystemTime)>())
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(inserted.iter().map(|r| r.0).collect::<Vec<_>>(), vec![1, 2]);
+
+    let rows = client.query("SELECT id, name FROM foo", &[]).unwrap();
+    let (_, name) = rows[0].get_tuple::<(i32, &str)>().unwrap();
+    assert_eq!(name, "alice");
+    let err = rows[0].get_tuple::<(i32,)>().unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "invalid column: expected 1 columns but the row has 2"
+    );
+    let err = rows[0].get_tuple::<(i32, &str, i32)>().unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "invalid column: expected 3 columns but the row has 2"
+    );
+    assert!(rows[0].get_tuple::<(i32, i32)>().is_err());
+}
+
+#[test]
+fn transaction_duration() {
+    let mut client = Client::connect("host=localhost port=5433 user=postgres", NoTls).unwrap();
+
+    assert_eq!(client.transaction_duration(), None);
+
+    client.simple_query("BEGIN").unwrap();
+    thread::sleep(Duration::from_millis(50));
+    let duration = client.transaction_duration().unwrap();
+    assert!(duration >= Duration::from_millis(50));
+
+    client.simple_query("SELECT 1").unwrap();
+    assert!(client.transaction_duration().unwrap() >= duration);
+
+    client.simple_query("COMMIT").unwrap();
+    assert_eq!(client.transaction_duration(), None);
+}
+
+#[test]
+fn query_one() {
+    let mut client = Client::connect("host=localhost port=5433 user=postgres", NoTls).unwrap();
+
+    let row = client.query_one("SELECT 1::INT4", &[]).unwrap();
+    assert_eq!(row.get::<_, i32>(0), 1);
+
+    let (id, name) = client
+        .query_one_as::<_, (i32, String)>("SELECT 1::INT4, 'alice'", &[])
+        .unwrap();
+    assert_eq!(id, 1);
+    assert_eq!(name, "alice");
+
+    let err = client.query_one("SELECT 1 WHERE false", &[]).err().unwrap();
+    assert!(err.to_string().contains("unexpected number of rows"));
+
+    let err = client
+        .query_one("SELECT generate_series(1, 2)", &[])
+        .err()
+        .unwrap();
+    assert!(err.to_string().contains("unexpected number of rows"));
+
+    let mut transaction = client.transaction().unwrap();
+    let (value,) = transaction
+        .query_one_as::<_, (i32,)>("SELECT 2::INT4", &[])
+        .unwrap();
+    assert_eq!(value, 2);
+}
+
+#[test]
+fn query_named() {
+    let mut client = Client::connect("host=localhost port=5433 user=postgres", NoTls).unwrap();
+
+    let mut params = HashMap::<&str, &dyn ToSql>::new();
+    params.insert("a", &1i32);
+    params.insert("b", &"foo");
+    params.insert("unused", &true);
+    let rows = client
+        .query_named("SELECT :a::INT4, :b::TEXT, ':a', :a + 1", &params)
+        .unwrap();
+    assert_eq!(rows[0].get::<_, i32>(0), 1);
+    assert_eq!(rows[0].get::<_, &str>(1), "foo");
+    assert_eq!(rows[0].get::<_, &str>(2), ":a");
+    assert_eq!(rows[0].get::<_, i32>(3), 2);
+
+    let err = client
+        .query_named("SELECT :missing::INT4", &params)
+        .err()
+        .unwrap();
+    assert!(err.to_string().contains(":missing"), "{}", err);
+
+    let rows = client
+        .query_named(
+            "SELECT arr[lo:hi], arr[:hi], ARRAY[:a::INT4, :a] FROM (SELECT ARRAY[1, 2, 3] AS arr, 2 AS lo, 3 AS hi) t",
+            &params,
+        )
+        .unwrap();
+    assert_eq!(rows[0].get::<_, Vec<i32>>(0), vec![2, 3]);
+    assert_eq!(rows[0].get::<_, Vec<i32>>(1), vec![1, 2, 3]);
+    assert_eq!(rows[0].get::<_, Vec<i32>>(2), vec![1, 1]);
+}
+
+#[test]
+fn query_iter_cancellable() {
+    let mut client = Client::connect("host=localhost port=5433 user=postgres", NoTls).unwrap();
+
+    let start = Instant::now();
+    let mut it = client
+        .query_iter_cancellable(
+            // the server buffers its output, so produce enough data for the first rows to be sent before sleeping
+            "SELECT x, repeat('a', 1000) FROM generate_series(1, 100) x, \
+             LATERAL pg_sleep(CASE WHEN x = 100 THEN 30 ELSE 0 END)",
+            &[],
+            NoTls,
+        )
+        .unwrap();
+    assert_eq!(it.next().unwrap().unwrap().get::<_, i32>(0), 1);
+    drop(it);
+
+    let rows = client.query("SELECT 1::INT4", &[]).unwrap();
+    assert_eq!(rows[0].get::<_, i32>(0), 1);
+    assert!(start.elapsed() < Duration::from_secs(10));
+
+    // exhausted iterators don't cancel anything
+    let mut it = client
+        .query_iter_cancellable("SELECT 1::INT4", &[], NoTls)
+        .unwrap();
+    while it.next().unwrap().is_some() {}
+    drop(it);
+    client.execute("SELECT pg_sleep(0.2)", &[]).unwrap();
+}
+
+#[test]
+fn query_iter_cancellable_custom_executor() {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let mut client = "host=localhost port=5433 user=postgres"
+        .parse::<Config>()
+        .unwrap()
+        .executor(runtime.executor())
+        .connect(NoTls)
+        .unwrap();
+
+    let start = Instant::now();
+    let mut it = client
+        .query_iter_cancellable(
+            "SELECT x, repeat('a', 1000) FROM generate_series(1, 100) x, \
+             LATERAL pg_sleep(CASE WHEN x = 100 THEN 30 ELSE 0 END)",
+            &[],
+            NoTls,
+        )
+        .unwrap();
+    assert_eq!(it.next().unwrap().unwrap().get::<_, i32>(0), 1);
+    drop(it);
+    assert!(start.elapsed() < Duration::from_secs(10));
+
+    assert_eq!(client.state(), ConnectionState::Idle);
+    let rows = client.query("SELECT 1::INT4", &[]).unwrap();
+    assert_eq!(rows[0].get::<_, i32>(0), 1);
+}
+
+#[test]
+fn connect_another() {
+    let mut client = Client::connect("host=localhost port=5433 user=postgres", NoTls).unwrap();
+    client
+        .simple_query("CREATE TEMPORARY TABLE foo (id INT)")
+        .unwrap();
+
+    let mut other = client.connect_another(NoTls).unwrap();
+    let pid = |client: &mut Client| {
+        client.query("SELECT pg_backend_pid()", &[]).unwrap()[0].get::<_, i32>(0)
+    };
+    assert_ne!(pid(&mut client), pid(&mut other));
+
+    // temporary tables are private to the session that created them
+    assert!(other.execute("SELECT * FROM foo", &[]).is_err());
+    assert_eq!(client.execute("SELECT * FROM foo", &[]).unwrap(), 0);
+}
+
+#[test]
+fn query_field() {
+    let mut client = Client::connect("host=localhost port=5433 user=postgres", NoTls).unwrap();
+
+    let mut reader = client
+        .query_field("SELECT decode(repeat($1, 50000), 'hex')", &[&"0102"])
+        .unwrap();
+    let mut buf = vec![];
+    io::copy(&mut reader, &mut buf).unwrap();
+    drop(reader);
+    assert_eq!(buf.len(), 100_000);
+    assert!(buf.chunks(2).all(|c| c == [1, 2]));
+
+    assert!(client.query_field("SELECT 1, 2", &[]).is_err());
+}
+
+#[cfg(feature = "derive")]
+#[test]
+fn derive() {
+    use crate::row::FromRow;
+
+    #[derive(Debug, PartialEq, ToSql, FromSql)]
+    #[postgres(transparent, crate = "crate")]
+    struct UserId(i32);
+
+    #[derive(Debug, PartialEq, FromRow)]
+    #[postgres(crate = "crate")]
+    struct User {
+        id: UserId,
+        name: String,
+    }
+
+    let mut client = Client::connect("host=localhost port=5433 user=postgres", NoTls).unwrap();
+
+    let user = client
+        .query_one_as::<_, User>("SELECT $1::INT4 AS id, 'alice' AS name", &[&UserId(1)])
+        .unwrap();
+    assert_eq!(
+        user,
+        User {
+            id: UserId(1),
+            name: "alice".to_string(),
+        }
+    );
+}
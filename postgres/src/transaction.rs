@@ -1,12 +1,14 @@
 use fallible_iterator::FallibleIterator;
 use futures::Future;
+use std::collections::HashMap;
 use std::io::Read;
-use tokio_postgres::types::{ToSql, Type};
-use tokio_postgres::{Error, Row, SimpleQueryMessage};
+use tokio_postgres::row::FromRow;
+use tokio_postgres::types::{Format, FromSqlOwned, Oid, ToSql, Type};
+use tokio_postgres::{ConnectionState, Error, Row, SimpleQueryMessage};
 
 use crate::{
-    Client, CopyOutReader, Portal, QueryIter, QueryPortalIter, SimpleQueryIter, Statement,
-    ToStatement,
+    quote_identifier, Client, CopyOutReader, Portal, QueryIter, QueryPortalIter, SimpleQueryIter,
+    Statement, ToStatement,
 };
 
 /// A representation of a PostgreSQL database transaction.
@@ -76,6 +78,31 @@ impl<'a> Transaction<'a> {
         self.client.prepare_typed(query, types)
     }
 
+    /// Like `Client::prepare_with_result_formats`.
+    pub fn prepare_with_result_formats(
+        &mut self,
+        query: &str,
+        result_formats: &[Format],
+    ) -> Result<Statement, Error> {
+        self.client
+            .prepare_with_result_formats(query, result_formats)
+    }
+
+    /// Like `Client::prepare_and_query`.
+    pub fn prepare_and_query(
+        &mut self,
+        query: &str,
+        param_types: &[Type],
+        params: &[&dyn ToSql],
+    ) -> Result<(Statement, Vec<Row>), Error> {
+        self.client.prepare_and_query(query, param_types, params)
+    }
+
+    /// Like `Client::close_statement`.
+    pub fn close_statement(&mut self, statement: Statement) -> Result<(), Error> {
+        self.client.close_statement(statement)
+    }
+
     /// Like `Client::execute`.
     pub fn execute<T>(&mut self, query: &T, params: &[&dyn ToSql]) -> Result<u64, Error>
     where
@@ -92,6 +119,32 @@ impl<'a> Transaction<'a> {
         self.client.query(query, params)
     }
 
+    /// Like `Client::query_one`.
+    pub fn query_one<T>(&mut self, query: &T, params: &[&dyn ToSql]) -> Result<Row, Error>
+    where
+        T: ?Sized + ToStatement,
+    {
+        self.client.query_one(query, params)
+    }
+
+    /// Like `Client::query_one_as`.
+    pub fn query_one_as<T, R>(&mut self, query: &T, params: &[&dyn ToSql]) -> Result<R, Error>
+    where
+        T: ?Sized + ToStatement,
+        R: for<'b> FromRow<'b>,
+    {
+        self.client.query_one_as(query, params)
+    }
+
+    /// Like `Client::query_named`.
+    pub fn query_named(
+        &mut self,
+        query: &str,
+        params: &HashMap<&str, &dyn ToSql>,
+    ) -> Result<Vec<Row>, Error> {
+        self.client.query_named(query, params)
+    }
+
     /// Like `Client::query_iter`.
     pub fn query_iter<T>(
         &mut self,
@@ -104,16 +157,38 @@ impl<'a> Transaction<'a> {
         self.client.query_iter(query, params)
     }
 
+    /// Like `Client::query_by_keys`.
+    pub fn query_by_keys<K>(
+        &mut self,
+        table: &str,
+        column: &str,
+        keys: &[K],
+    ) -> Result<Vec<Row>, Error>
+    where
+        K: ToSql,
+    {
+        self.client.query_by_keys(table, column, keys)
+    }
+
+    /// Like `Client::explain`.
+    pub fn explain(&mut self, query: &str, params: &[&dyn ToSql]) -> Result<Vec<String>, Error> {
+        self.client.explain(query, params)
+    }
+
+    /// Like `Client::explain_json`.
+    pub fn explain_json<T>(&mut self, query: &str, params: &[&dyn ToSql]) -> Result<T, Error>
+    where
+        T: FromSqlOwned,
+    {
+        self.client.explain_json(query, params)
+    }
+
     /// Binds parameters to a statement, creating a "portal".
     ///
     /// Portals can be used with the `query_portal` method to page through the results of a query without being forced
     /// to consume them all immediately.
     ///
     /// Portals are automatically closed when the transaction they were created in is closed.
-    ///
-    /// # Panics
-    ///
-    /// Panics if the number of parameters provided does not match the number expected.
     pub fn bind<T>(&mut self, query: &T, params: &[&dyn ToSql]) -> Result<Portal, Error>
     where
         T: ?Sized + ToStatement,
@@ -142,6 +217,103 @@ impl<'a> Transaction<'a> {
         ))
     }
 
+    /// Declares a cursor over the results of a query.
+    ///
+    /// Rows can be fetched from the cursor with `query_cursor`. The cursor is closed automatically when the
+    /// transaction ends.
+    pub fn declare_cursor(
+        &mut self,
+        name: &str,
+        query: &str,
+        params: &[&dyn ToSql],
+    ) -> Result<(), Error> {
+        let query = format!(
+            "DECLARE {} NO SCROLL CURSOR FOR {}",
+            quote_identifier(name),
+            query
+        );
+        self.client.execute(&*query, params)?;
+        Ok(())
+    }
+
+    /// Like `declare_cursor`, but declares a `WITH HOLD` cursor which remains open after the transaction commits.
+    ///
+    /// When the transaction commits, the server materializes the cursor's remaining results, after which they can be
+    /// fetched with `Client::query_cursor` while other transactions run on the connection. The cursor is still closed
+    /// if the transaction rolls back. It otherwise stays open until it's closed with `Client::close_cursor` or the
+    /// session ends.
+    pub fn declare_cursor_with_hold(
+        &mut self,
+        name: &str,
+        query: &str,
+        params: &[&dyn ToSql],
+    ) -> Result<(), Error> {
+        let query = format!(
+            "DECLARE {} NO SCROLL CURSOR WITH HOLD FOR {}",
+            quote_identifier(name),
+            query
+        );
+        self.client.execute(&*query, params)?;
+        Ok(())
+    }
+
+    /// Fetches rows from an existing cursor, such as one declared with `DECLARE` or returned as a `refcursor` by a
+    /// function.
+    ///
+    /// Like `query_portal`, this can be called repeatedly to page through the cursor's results. If the requested
+    /// number of rows is negative or 0, all remaining rows will be returned.
+    pub fn query_cursor(&mut self, name: &str, max_rows: i32) -> Result<Vec<Row>, Error> {
+        self.client.query_cursor(name, max_rows)
+    }
+
+    /// Closes a cursor.
+    pub fn close_cursor(&mut self, name: &str) -> Result<(), Error> {
+        self.client.close_cursor(name)
+    }
+
+    /// Sets the role used to execute the remainder of the transaction, via `SET LOCAL ROLE`.
+    ///
+    /// The role is reset automatically when the transaction commits or rolls back, so it cannot leak to later uses of
+    /// the connection. If this is called in a nested transaction, the role is reset when it rolls back, but persists
+    /// in the enclosing transaction if it commits. The role name is quoted as an identifier, so it is matched
+    /// case-sensitively.
+    pub fn set_role(&mut self, role: &str) -> Result<(), Error> {
+        self.client
+            .simple_query(&format!("SET LOCAL ROLE {}", quote_identifier(role)))?;
+        Ok(())
+    }
+
+    /// Returns the OIDs of all of the large objects in the database, in ascending order.
+    pub fn large_objects(&mut self) -> Result<Vec<Oid>, Error> {
+        let rows = self
+            .client
+            .query("SELECT oid FROM pg_largeobject_metadata ORDER BY oid", &[])?;
+        Ok(rows.iter().map(|row| row.get(0)).collect())
+    }
+
+    /// Returns the size in bytes of a large object.
+    ///
+    /// The object is briefly opened for reading to seek to its end.
+    pub fn large_object_size(&mut self, oid: Oid) -> Result<u64, Error> {
+        // INV_READ
+        let fd = self
+            .client
+            .query_one("SELECT lo_open($1, 262144)", &[&oid])?
+            .get::<_, i32>(0);
+        let size = self
+            .client
+            .query_one("SELECT lo_lseek64($1, 0, 2)", &[&fd])?
+            .get::<_, i64>(0);
+        self.client.execute("SELECT lo_close($1)", &[&fd])?;
+        Ok(size as u64)
+    }
+
+    /// Deletes a large object, via `lo_unlink`.
+    pub fn unlink_large_object(&mut self, oid: Oid) -> Result<(), Error> {
+        self.client.execute("SELECT lo_unlink($1)", &[&oid])?;
+        Ok(())
+    }
+
     /// Like `Client::copy_in`.
     pub fn copy_in<T, R>(
         &mut self,
@@ -156,6 +328,20 @@ impl<'a> Transaction<'a> {
         self.client.copy_in(query, params, reader)
     }
 
+    /// Like `Client::copy_in_typed`.
+    pub fn copy_in_typed<'b, I, R>(
+        &mut self,
+        table: &str,
+        columns: &[&str],
+        rows: I,
+    ) -> Result<u64, Error>
+    where
+        I: IntoIterator<Item = R>,
+        R: AsRef<[&'b dyn ToSql]>,
+    {
+        self.client.copy_in_typed(table, columns, rows)
+    }
+
     /// Like `Client::copy_out`.
     pub fn copy_out<T>(
         &mut self,
@@ -168,6 +354,18 @@ impl<'a> Transaction<'a> {
         self.client.copy_out(query, params)
     }
 
+    /// Like `Client::query_field`.
+    pub fn query_field<T>(
+        &mut self,
+        query: &T,
+        params: &[&dyn ToSql],
+    ) -> Result<CopyOutReader<'_>, Error>
+    where
+        T: ?Sized + ToStatement,
+    {
+        self.client.query_field(query, params)
+    }
+
     /// Like `Client::simple_query`.
     pub fn simple_query(&mut self, query: &str) -> Result<Vec<SimpleQueryMessage>, Error> {
         self.client.simple_query(query)
@@ -178,6 +376,11 @@ impl<'a> Transaction<'a> {
         self.client.simple_query_iter(query)
     }
 
+    /// Like `Client::state`.
+    pub fn state(&mut self) -> ConnectionState {
+        self.client.state()
+    }
+
     /// Like `Client::transaction`.
     pub fn transaction(&mut self) -> Result<Transaction<'_>, Error> {
         let depth = self.depth + 1;
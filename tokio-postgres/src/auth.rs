@@ -0,0 +1,39 @@
+//! Pluggable authentication.
+//!
+//! The client natively supports cleartext, MD5, and SCRAM-SHA-256 password authentication. Other mechanisms which
+//! exchange opaque tokens with the server, such as GSSAPI and SSPI, can be supported by providing an `Authenticator`
+//! to the connection's `Config`.
+
+use std::error::Error;
+
+/// An authentication mechanism requested by the server which can be handled by an `Authenticator`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum AuthenticationMethod {
+    /// GSSAPI authentication, typically backed by Kerberos.
+    Gss,
+    /// SSPI authentication, used on Windows.
+    Sspi,
+}
+
+/// A handler for authentication mechanisms which are not natively supported.
+pub trait Authenticator: Sync + Send {
+    /// Begins an authentication exchange using the mechanism requested by the server.
+    ///
+    /// An error will abort the connection attempt.
+    fn start(
+        &self,
+        method: AuthenticationMethod,
+    ) -> Result<Box<dyn AuthenticationExchange>, Box<dyn Error + Sync + Send>>;
+}
+
+/// The state of an in-progress authentication exchange.
+pub trait AuthenticationExchange: Send {
+    /// Processes a message from the server, returning the token to send in response, if any.
+    ///
+    /// This is first called with `None` when the exchange starts, and then with the data of each continuation message
+    /// sent by the server until it reports that authentication has completed.
+    fn step(
+        &mut self,
+        data: Option<&[u8]>,
+    ) -> Result<Option<Vec<u8>>, Box<dyn Error + Sync + Send>>;
+}
@@ -0,0 +1,165 @@
+//! Binary `COPY` support.
+//!
+//! `COPY ... FROM STDIN (FORMAT binary)` is the fastest way to load large amounts of data, but the format requires a
+//! header, a field count and length prefix for every row and value, and a trailer. `BinaryCopyWriter` handles that
+//! bookkeeping, encoding each value with its `ToSql` implementation so the resulting buffer can be passed to
+//! `Client::copy_in`.
+use std::error;
+use std::fmt;
+use std::mem;
+
+use crate::types::{Format, IsNull, ToSql, Type};
+use crate::Error;
+
+const MAGIC: &[u8] = b"PGCOPY\n\xff\r\n\0";
+
+/// A buffer of rows encoded in the binary `COPY` format.
+#[derive(Debug)]
+pub struct BinaryCopyWriter {
+    types: Vec<Type>,
+    buf: Vec<u8>,
+    rows: usize,
+}
+
+impl BinaryCopyWriter {
+    /// Creates a new writer for rows whose columns have the specified types.
+    ///
+    /// The types must match the columns listed in the `COPY` statement, in order.
+    pub fn new(types: &[Type]) -> BinaryCopyWriter {
+        let mut buf = MAGIC.to_vec();
+        // flags and header extension length
+        buf.extend_from_slice(&[0; 8]);
+
+        BinaryCopyWriter {
+            types: types.to_vec(),
+            buf,
+            rows: 0,
+        }
+    }
+
+    /// Encodes a row.
+    ///
+    /// If the number of values does not match the number of columns, there are more columns than the format allows,
+    /// or a value cannot be encoded as its column's type, an error identifying the row is returned and the writer is
+    /// left unchanged. Values whose `encode_format` is `Format::Text` cannot be used, since every field of a binary
+    /// `COPY` must be in the binary format.
+    pub fn write(&mut self, values: &[&dyn ToSql]) -> Result<(), Error> {
+        let row = self.rows;
+        if values.len() != self.types.len() {
+            return Err(Error::copy_row(
+                Box::new(ArityMismatch {
+                    expected: self.types.len(),
+                    actual: values.len(),
+                }),
+                row,
+            ));
+        }
+        if self.types.len() > i16::max_value() as usize {
+            return Err(Error::copy_row(
+                Box::new(TooManyColumns(self.types.len())),
+                row,
+            ));
+        }
+
+        let start = self.buf.len();
+        if let Err(e) = self.write_values(values) {
+            self.buf.truncate(start);
+            return Err(Error::copy_row(Box::new(e), row));
+        }
+
+        self.rows += 1;
+        Ok(())
+    }
+
+    fn write_values(&mut self, values: &[&dyn ToSql]) -> Result<(), Error> {
+        self.buf
+            .extend_from_slice(&(self.types.len() as i16).to_be_bytes());
+
+        for (idx, (value, ty)) in values.iter().zip(&self.types).enumerate() {
+            if value.encode_format() != Format::Binary {
+                return Err(Error::to_sql(
+                    "binary COPY values must be encoded in the binary format".into(),
+                    idx,
+                ));
+            }
+
+            let base = self.buf.len();
+            self.buf.extend_from_slice(&[0; 4]);
+            let len = match value
+                .to_sql_checked(ty, &mut self.buf)
+                .map_err(|e| Error::to_sql(e, idx))?
+            {
+                IsNull::Yes => -1,
+                IsNull::No => {
+                    let len = self.buf.len() - base - 4;
+                    if len > i32::max_value() as usize {
+                        return Err(Error::to_sql("value too large to transmit".into(), idx));
+                    }
+                    len as i32
+                }
+            };
+            self.buf[base..base + 4].copy_from_slice(&len.to_be_bytes());
+        }
+
+        Ok(())
+    }
+
+    /// Returns the number of rows written so far.
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Returns the number of encoded bytes which have not yet been taken from the writer.
+    pub fn buffered_bytes(&self) -> usize {
+        self.buf.len()
+    }
+
+    /// Removes and returns the data encoded so far.
+    ///
+    /// This allows rows to be sent as they are written rather than buffering the entire `COPY` in memory. The
+    /// remaining data, including the trailer, is returned by `finish`.
+    pub fn take_data(&mut self) -> Vec<u8> {
+        let capacity = self.buf.capacity();
+        mem::replace(&mut self.buf, Vec::with_capacity(capacity))
+    }
+
+    /// Consumes the writer, returning the encoded data including the trailer.
+    pub fn finish(mut self) -> Vec<u8> {
+        self.buf.extend_from_slice(&[0xff, 0xff]);
+        self.buf
+    }
+}
+
+#[derive(Debug)]
+struct ArityMismatch {
+    expected: usize,
+    actual: usize,
+}
+
+impl fmt::Display for ArityMismatch {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            fmt,
+            "expected {} values but got {}",
+            self.expected, self.actual
+        )
+    }
+}
+
+impl error::Error for ArityMismatch {}
+
+#[derive(Debug)]
+struct TooManyColumns(usize);
+
+impl fmt::Display for TooManyColumns {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            fmt,
+            "{} columns is more than the maximum of {}",
+            self.0,
+            i16::max_value()
+        )
+    }
+}
+
+impl error::Error for TooManyColumns {}
@@ -16,13 +16,14 @@ use std::sync::Arc;
 use std::time::Duration;
 use tokio_io::{AsyncRead, AsyncWrite};
 
+use crate::auth::Authenticator;
 #[cfg(feature = "runtime")]
 use crate::impls::Connect;
 use crate::impls::ConnectRaw;
 #[cfg(feature = "runtime")]
 use crate::proto::ConnectFuture;
 use crate::proto::ConnectRawFuture;
-use crate::{Error, TlsConnect};
+use crate::{Error, TlsConnect, Version};
 #[cfg(feature = "runtime")]
 use crate::{MakeTlsConnect, Socket};
 
@@ -42,14 +43,35 @@ pub enum TargetSessionAttrs {
 pub enum SslMode {
     /// Do not use TLS.
     Disable,
+    /// Connect without TLS, but retry with TLS if the server rejects the session.
+    Allow,
     /// Attempt to connect with TLS but allow sessions without.
     Prefer,
     /// Require the use of TLS.
     Require,
+    /// Require the use of TLS with a verified server certificate.
+    ///
+    /// Verification is performed by the `TlsConnect` implementation, and the connection fails if it does not support
+    /// it. See `TlsConnect::require_verification`.
+    VerifyCa,
+    /// Require the use of TLS with a verified server certificate matching the hostname.
+    ///
+    /// Verification is performed by the `TlsConnect` implementation, and the connection fails if it does not support
+    /// it. See `TlsConnect::require_verification`.
+    VerifyFull,
     #[doc(hidden)]
     __NonExhaustive,
 }
 
+/// Replication mode configuration.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ReplicationMode {
+    /// Physical replication of the entire database cluster.
+    Physical,
+    /// Logical replication of the database named by `dbname`.
+    Logical,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub(crate) enum Host {
     Tcp(String),
@@ -57,6 +79,15 @@ pub(crate) enum Host {
     Unix(PathBuf),
 }
 
+#[derive(Clone)]
+pub(crate) struct AuthenticatorRef(pub(crate) Arc<dyn Authenticator>);
+
+impl PartialEq for AuthenticatorRef {
+    fn eq(&self, other: &AuthenticatorRef) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
 #[derive(Clone, PartialEq)]
 pub(crate) struct Inner {
     pub(crate) user: Option<String>,
@@ -70,11 +101,29 @@ pub(crate) struct Inner {
     pub(crate) connect_timeout: Option<Duration>,
     pub(crate) keepalives: bool,
     pub(crate) keepalives_idle: Duration,
+    pub(crate) proxy: Option<(String, u16)>,
+    pub(crate) proxy_user: Option<String>,
+    pub(crate) proxy_password: Option<Vec<u8>>,
     pub(crate) target_session_attrs: TargetSessionAttrs,
+    pub(crate) read_buffer_size: usize,
+    pub(crate) write_buffer_size: usize,
+    pub(crate) max_message_size: usize,
+    pub(crate) authenticator: Option<AuthenticatorRef>,
+    pub(crate) timezone: Option<String>,
+    pub(crate) client_encoding: String,
+    pub(crate) lc_numeric: Option<String>,
+    pub(crate) lc_monetary: Option<String>,
+    pub(crate) extra_float_digits: Option<i32>,
+    pub(crate) min_server_version: Option<Version>,
+    pub(crate) statement_name_prefix: String,
+    pub(crate) replication_mode: Option<ReplicationMode>,
 }
 
 /// Connection configuration.
 ///
+/// In addition to the parameters configured here, the client sets the `client_encoding` session parameter to `UTF8`
+/// and `TimeZone` to `GMT` on startup unless configured otherwise with the `client_encoding` and `timezone` methods.
+///
 /// Configuration can be parsed from libpq-style connection strings. These strings come in two formats:
 ///
 /// # Key-Value
@@ -87,10 +136,15 @@ pub(crate) struct Inner {
 /// * `user` - The username to authenticate with. Required.
 /// * `password` - The password to authenticate with.
 /// * `dbname` - The name of the database to connect to. Defaults to the username.
-/// * `options` - Command line options used to configure the server.
+/// * `options` - Command line options used to configure the server, such as
+///     `'-c search_path=myschema -c statement_timeout=5000'`. Spaces within an option's value must be escaped with a
+///     backslash, which itself must be escaped in the connection string.
 /// * `application_name` - Sets the `application_name` parameter on the server.
-/// * `sslmode` - Controls usage of TLS. If set to `disable`, TLS will not be used. If set to `prefer`, TLS will be used
-///     if available, but not used otherwise. If set to `require`, TLS will be forced to be used. Defaults to `prefer`.
+/// * `sslmode` - Controls usage of TLS. If set to `disable`, TLS will not be used. If set to `allow`, TLS will only be
+///     used if the server rejects an unencrypted session. If set to `prefer`, TLS will be used if available, but not
+///     used otherwise. If set to `require`, TLS will be forced to be used. `verify-ca` and `verify-full` also force TLS
+///     to be used, and additionally require the TLS connector to verify the server's certificate chain and, for
+///     `verify-full`, its hostname; the connection fails if the connector can't. Defaults to `prefer`.
 /// * `host` - The host to connect to. On Unix platforms, if the host starts with a `/` character it is treated as the
 ///     path to the directory containing Unix domain sockets. Otherwise, it is treated as a hostname. Multiple hosts
 ///     can be specified, separated by commas. Each host will be tried in turn when connecting. Required if connecting
@@ -99,14 +153,24 @@ pub(crate) struct Inner {
 ///     either 1, in which case it will be used for all hosts, or the same as the number of hosts. Defaults to 5432 if
 ///     omitted or the empty string.
 /// * `connect_timeout` - The time limit in seconds applied to each socket-level connection attempt. Note that hostnames
-///     can resolve to multiple IP addresses, and this limit is applied to each address. Defaults to no timeout.
+///     can resolve to multiple IP addresses, and this limit is applied to each address. When connecting through a
+///     proxy, the limit covers both the connection to the proxy and the proxy's handshake. Defaults to no timeout.
 /// * `keepalives` - Controls the use of TCP keepalive. A value of 0 disables keepalive and nonzero integers enable it.
 ///     This option is ignored when connecting with Unix sockets. Defaults to on.
 /// * `keepalives_idle` - The number of seconds of inactivity after which a keepalive message is sent to the server.
 ///     This option is ignored when connecting with Unix sockets. Defaults to 2 hours.
+/// * `proxy` - The `host:port` address of a SOCKS5 proxy through which TCP connections are made. The proxy connects to
+///     each host in turn on the client's behalf, resolving hostnames itself. The port defaults to 1080 if omitted.
+///     IPv6 addresses must be enclosed in brackets, as in `[::1]:1080`. This option is ignored when connecting with
+///     Unix sockets.
+/// * `proxy_user` - The username to authenticate with the SOCKS5 proxy. If omitted, the proxy must allow connections
+///     without authentication.
+/// * `proxy_password` - The password to authenticate with the SOCKS5 proxy. Requires `proxy_user` to be set.
 /// * `target_session_attrs` - Specifies requirements of the session. If set to `read-write`, the client will check that
 ///     the `transaction_read_write` session parameter is set to `on`. This can be used to connect to the primary server
 ///     in a database cluster as opposed to the secondary read-only mirrors. Defaults to `all`.
+/// * `replication` - The replication mode: `true` for physical replication or `database` for logical replication.
+/// * `client_encoding` - The character encoding of text sent to and received from the server. Defaults to `UTF8`.
 ///
 /// ## Examples
 ///
@@ -170,7 +234,22 @@ impl Config {
             connect_timeout: None,
             keepalives: true,
             keepalives_idle: Duration::from_secs(2 * 60 * 60),
+            proxy: None,
+            proxy_user: None,
+            proxy_password: None,
             target_session_attrs: TargetSessionAttrs::Any,
+            read_buffer_size: 8 * 1024,
+            write_buffer_size: 8 * 1024,
+            max_message_size: 64 * 1024 * 1024,
+            authenticator: None,
+            timezone: Some("GMT".to_string()),
+            client_encoding: "UTF8".to_string(),
+            lc_numeric: None,
+            lc_monetary: None,
+            extra_float_digits: None,
+            min_server_version: None,
+            statement_name_prefix: "s".to_string(),
+            replication_mode: None,
         }))
     }
 
@@ -221,8 +300,10 @@ impl Config {
 
     /// Adds a host to the configuration.
     ///
-    /// Multiple hosts can be specified by calling this method multiple times, and each will be tried in order. On Unix
-    /// systems, a host starting with a `/` is interpreted as a path to a directory containing Unix domain sockets.
+    /// Multiple hosts can be specified by calling this method multiple times, and each will be tried in order. If none
+    /// of them can be connected to, the source of the returned error is a `ConnectAttempts` listing the error
+    /// encountered with each host. On Unix systems, a host starting with a `/` is interpreted as a path to a directory
+    /// containing Unix domain sockets.
     pub fn host(&mut self, host: &str) -> &mut Config {
         #[cfg(unix)]
         {
@@ -264,7 +345,8 @@ impl Config {
     /// Sets the timeout applied to socket-level connection attempts.
     ///
     /// Note that hostnames can resolve to multiple IP addresses, and this timeout will apply to each address of each
-    /// host separately. Defaults to no limit.
+    /// host separately. When connecting through a proxy, the timeout covers both the connection to the proxy and the
+    /// proxy's handshake. Defaults to no limit.
     pub fn connect_timeout(&mut self, connect_timeout: Duration) -> &mut Config {
         Arc::make_mut(&mut self.0).connect_timeout = Some(connect_timeout);
         self
@@ -286,6 +368,35 @@ impl Config {
         self
     }
 
+    /// Sets a SOCKS5 proxy through which TCP connections are made.
+    ///
+    /// The proxy is asked to connect to each host on the client's behalf, so hostnames are resolved by the proxy rather
+    /// than the client. TLS, if used, is negotiated with the server over the proxied connection. This is ignored for
+    /// Unix domain socket connections. Defaults to `None`.
+    pub fn proxy(&mut self, proxy: Option<(&str, u16)>) -> &mut Config {
+        Arc::make_mut(&mut self.0).proxy = proxy.map(|(host, port)| (host.to_string(), port));
+        self
+    }
+
+    /// Sets the username to authenticate with the SOCKS5 proxy.
+    ///
+    /// If no username is set, only connections without authentication are offered to the proxy.
+    pub fn proxy_user(&mut self, proxy_user: &str) -> &mut Config {
+        Arc::make_mut(&mut self.0).proxy_user = Some(proxy_user.to_string());
+        self
+    }
+
+    /// Sets the password to authenticate with the SOCKS5 proxy.
+    ///
+    /// A username must also be set with `proxy_user`, or connecting will fail.
+    pub fn proxy_password<T>(&mut self, proxy_password: T) -> &mut Config
+    where
+        T: AsRef<[u8]>,
+    {
+        Arc::make_mut(&mut self.0).proxy_password = Some(proxy_password.as_ref().to_vec());
+        self
+    }
+
     /// Sets the requirements of the session.
     ///
     /// This can be used to connect to the primary server in a clustered database rather than one of the read-only
@@ -298,6 +409,127 @@ impl Config {
         self
     }
 
+    /// Sets the `TimeZone` session parameter sent to the server on startup.
+    ///
+    /// The session time zone is used by the server whenever it converts between `TIMESTAMP WITH TIME ZONE` values and
+    /// local times, both in the text format of values such as those returned by `simple_query` and within SQL itself,
+    /// for example when casting to `TEXT` or `DATE`, or in `date_trunc` and `extract`. Binary values are unaffected.
+    /// If `None`, no time zone is sent and the server's default is used. Defaults to `GMT`.
+    pub fn timezone(&mut self, timezone: Option<&str>) -> &mut Config {
+        Arc::make_mut(&mut self.0).timezone = timezone.map(ToString::to_string);
+        self
+    }
+
+    /// Sets the `client_encoding` session parameter sent to the server on startup.
+    ///
+    /// `UTF8` is strongly recommended, since strings are always encoded and decoded as UTF-8 by the client. With any
+    /// other encoding, text values must be retrieved as `EncodedText` and decoded by the caller. The
+    /// connection fails if the server's encoding later changes from the one configured here, for example by a
+    /// `SET client_encoding` command. Defaults to `UTF8`.
+    pub fn client_encoding(&mut self, client_encoding: &str) -> &mut Config {
+        Arc::make_mut(&mut self.0).client_encoding = client_encoding.to_string();
+        self
+    }
+
+    /// Sets the `lc_numeric` session parameter sent to the server on startup.
+    ///
+    /// This controls the formatting of numbers by functions such as `to_char`. Values sent and received in the
+    /// binary format are not affected. If `None`, the server's default is used. Defaults to `None`.
+    pub fn lc_numeric(&mut self, lc_numeric: Option<&str>) -> &mut Config {
+        Arc::make_mut(&mut self.0).lc_numeric = lc_numeric.map(ToString::to_string);
+        self
+    }
+
+    /// Sets the `lc_monetary` session parameter sent to the server on startup.
+    ///
+    /// This controls the formatting of `MONEY` values in the text format, including their currency symbol and decimal
+    /// separator. Values sent and received in the binary format are not affected. If `None`, the server's default is
+    /// used. Defaults to `None`.
+    pub fn lc_monetary(&mut self, lc_monetary: Option<&str>) -> &mut Config {
+        Arc::make_mut(&mut self.0).lc_monetary = lc_monetary.map(ToString::to_string);
+        self
+    }
+
+    /// Sets the `extra_float_digits` session parameter sent to the server on startup.
+    ///
+    /// This controls the precision of `REAL` and `DOUBLE PRECISION` values in the text format, such as those returned
+    /// by `simple_query`. Values sent and received in the binary format are always exact. Servers before Postgres 12
+    /// round floats in the text format unless this is set to 3, while newer servers produce exact output whenever it
+    /// is positive, as it is by default. If `None`, the server's default is used. Defaults to `None`.
+    pub fn extra_float_digits(&mut self, extra_float_digits: Option<i32>) -> &mut Config {
+        Arc::make_mut(&mut self.0).extra_float_digits = extra_float_digits;
+        self
+    }
+
+    /// Sets the oldest server version the connection will accept.
+    ///
+    /// The version is checked against the `server_version` parameter reported by the server at the end of startup,
+    /// and the connection fails with an error whose source is an `UnsupportedServerVersion` if the server is older.
+    /// Defaults to `None`, which accepts any version.
+    pub fn require_server_version(&mut self, version: Option<Version>) -> &mut Config {
+        Arc::make_mut(&mut self.0).min_server_version = version;
+        self
+    }
+
+    /// Sets the prefix of the names of statements prepared by the client.
+    ///
+    /// Each statement is named with the prefix followed by a counter which is never reused, so statements prepared by
+    /// the client can't collide with each other. Libraries which also prepare statements on the connection with the
+    /// `PREPARE` command or through another driver layer can use a distinct prefix to avoid collisions with those
+    /// names. Defaults to `s`.
+    pub fn statement_name_prefix(&mut self, statement_name_prefix: &str) -> &mut Config {
+        Arc::make_mut(&mut self.0).statement_name_prefix = statement_name_prefix.to_string();
+        self
+    }
+
+    /// Sets the initial size of the buffer used to read messages from the server.
+    ///
+    /// Larger buffers reduce the number of reads needed for queries returning wide rows. Defaults to 8 KiB.
+    pub fn read_buffer_size(&mut self, read_buffer_size: usize) -> &mut Config {
+        Arc::make_mut(&mut self.0).read_buffer_size = read_buffer_size;
+        self
+    }
+
+    /// Sets the initial size of the buffer used to write messages to the server.
+    ///
+    /// Defaults to 8 KiB.
+    pub fn write_buffer_size(&mut self, write_buffer_size: usize) -> &mut Config {
+        Arc::make_mut(&mut self.0).write_buffer_size = write_buffer_size;
+        self
+    }
+
+    /// Sets the maximum size of a single message accepted from the server.
+    ///
+    /// A message whose declared length exceeds this limit causes the connection to fail rather than attempting to
+    /// buffer it, protecting against corrupt or malicious length prefixes. Queries returning very large values, such
+    /// as big `bytea` fields, may need a larger limit. Defaults to 64 MiB.
+    pub fn max_message_size(&mut self, max_message_size: usize) -> &mut Config {
+        Arc::make_mut(&mut self.0).max_message_size = max_message_size;
+        self
+    }
+
+    /// Sets the replication mode of the session.
+    ///
+    /// Replication connections accept the commands of the streaming replication protocol, such as
+    /// `IDENTIFY_SYSTEM` and `START_REPLICATION`, through `Client::simple_query` and `Client::copy_both_simple`.
+    /// Defaults to `None`, which creates a normal connection.
+    pub fn replication_mode(&mut self, replication_mode: Option<ReplicationMode>) -> &mut Config {
+        Arc::make_mut(&mut self.0).replication_mode = replication_mode;
+        self
+    }
+
+    /// Sets the handler used for authentication mechanisms which are not natively supported, such as GSSAPI and
+    /// SSPI.
+    ///
+    /// Without an authenticator, connections to servers requesting those mechanisms will fail.
+    pub fn authenticator<A>(&mut self, authenticator: A) -> &mut Config
+    where
+        A: Authenticator + 'static,
+    {
+        Arc::make_mut(&mut self.0).authenticator = Some(AuthenticatorRef(Arc::new(authenticator)));
+        self
+    }
+
     fn param(&mut self, key: &str, value: &str) -> Result<(), Error> {
         match key {
             "user" => {
@@ -318,8 +550,11 @@ impl Config {
             "sslmode" => {
                 let mode = match value {
                     "disable" => SslMode::Disable,
+                    "allow" => SslMode::Allow,
                     "prefer" => SslMode::Prefer,
                     "require" => SslMode::Require,
+                    "verify-ca" => SslMode::VerifyCa,
+                    "verify-full" => SslMode::VerifyFull,
                     _ => return Err(Error::config_parse(Box::new(InvalidValue("sslmode")))),
                 };
                 self.ssl_mode(mode);
@@ -362,6 +597,39 @@ impl Config {
                     self.keepalives_idle(Duration::from_secs(keepalives_idle as u64));
                 }
             }
+            "proxy" => {
+                let invalid = || Error::config_parse(Box::new(InvalidValue("proxy")));
+                // IPv6 addresses must be bracketed to separate them from the port
+                let (host, port) = if value.starts_with('[') {
+                    let end = value.find(']').ok_or_else(invalid)?;
+                    let port = match &value[end + 1..] {
+                        "" => None,
+                        rest if rest.starts_with(':') => Some(&rest[1..]),
+                        _ => return Err(invalid()),
+                    };
+                    (&value[1..end], port)
+                } else {
+                    match value.find(':') {
+                        Some(idx) if value[idx + 1..].contains(':') => return Err(invalid()),
+                        Some(idx) => (&value[..idx], Some(&value[idx + 1..])),
+                        None => (value, None),
+                    }
+                };
+                let port = match port {
+                    Some(port) => port.parse::<u16>().map_err(|_| invalid())?,
+                    None => 1080,
+                };
+                if host.is_empty() || host.contains(']') {
+                    return Err(invalid());
+                }
+                self.proxy(Some((host, port)));
+            }
+            "proxy_user" => {
+                self.proxy_user(value);
+            }
+            "proxy_password" => {
+                self.proxy_password(value);
+            }
             "target_session_attrs" => {
                 let target_session_attrs = match &*value {
                     "any" => TargetSessionAttrs::Any,
@@ -374,6 +642,18 @@ impl Config {
                 };
                 self.target_session_attrs(target_session_attrs);
             }
+            "replication" => {
+                let replication_mode = match value {
+                    "true" | "on" | "yes" | "1" => Some(ReplicationMode::Physical),
+                    "database" => Some(ReplicationMode::Logical),
+                    "false" | "off" | "no" | "0" => None,
+                    _ => return Err(Error::config_parse(Box::new(InvalidValue("replication")))),
+                };
+                self.replication_mode(replication_mode);
+            }
+            "client_encoding" => {
+                self.client_encoding(value);
+            }
             key => {
                 return Err(Error::config_parse(Box::new(UnknownOption(
                     key.to_string(),
@@ -440,7 +720,28 @@ impl fmt::Debug for Config {
             .field("connect_timeout", &self.0.connect_timeout)
             .field("keepalives", &self.0.keepalives)
             .field("keepalives_idle", &self.0.keepalives_idle)
+            .field("proxy", &self.0.proxy)
+            .field("proxy_user", &self.0.proxy_user)
+            .field(
+                "proxy_password",
+                &self.0.proxy_password.as_ref().map(|_| Redaction {}),
+            )
             .field("target_session_attrs", &self.0.target_session_attrs)
+            .field("timezone", &self.0.timezone)
+            .field("client_encoding", &self.0.client_encoding)
+            .field("lc_numeric", &self.0.lc_numeric)
+            .field("lc_monetary", &self.0.lc_monetary)
+            .field("extra_float_digits", &self.0.extra_float_digits)
+            .field("min_server_version", &self.0.min_server_version)
+            .field("statement_name_prefix", &self.0.statement_name_prefix)
+            .field("replication_mode", &self.0.replication_mode)
+            .field("read_buffer_size", &self.0.read_buffer_size)
+            .field("write_buffer_size", &self.0.write_buffer_size)
+            .field("max_message_size", &self.0.max_message_size)
+            .field(
+                "authenticator",
+                &self.0.authenticator.as_ref().map(|_| Redaction {}),
+            )
             .finish()
     }
 }
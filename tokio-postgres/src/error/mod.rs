@@ -1,11 +1,13 @@
 //! Errors.
 
 use fallible_iterator::FallibleIterator;
-use postgres_protocol::message::backend::{ErrorFields, ErrorResponseBody};
+use postgres_protocol::message::backend::{ErrorFields, ErrorResponseBody, Message};
 use std::error::{self, Error as _Error};
 use std::fmt;
 use std::io;
 
+use crate::Version;
+
 pub use self::sqlstate::*;
 
 #[allow(clippy::unreadable_literal)]
@@ -315,6 +317,147 @@ impl fmt::Display for DbError {
 
 impl error::Error for DbError {}
 
+/// Details of a message received from the server at a point in the protocol where it was not expected.
+///
+/// This is the source of errors reporting an unexpected message, and indicates that the client and server have lost
+/// track of the state of the protocol.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnexpectedMessage {
+    expected: &'static str,
+    received: &'static str,
+}
+
+impl UnexpectedMessage {
+    /// Returns a description of the message or messages the client was expecting.
+    pub fn expected(&self) -> &str {
+        self.expected
+    }
+
+    /// Returns the type of the message that was received, such as `DataRow`.
+    pub fn received(&self) -> &str {
+        self.received
+    }
+}
+
+impl fmt::Display for UnexpectedMessage {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            fmt,
+            "expected {} but received {}",
+            self.expected, self.received
+        )
+    }
+}
+
+impl error::Error for UnexpectedMessage {}
+
+/// Details of a server which is older than the version required by `Config::require_server_version`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnsupportedServerVersion {
+    found: Option<Version>,
+    required: Version,
+}
+
+impl UnsupportedServerVersion {
+    /// Returns the version of the server, or `None` if it did not report a valid `server_version`.
+    pub fn found(&self) -> Option<Version> {
+        self.found
+    }
+
+    /// Returns the oldest version the connection was configured to accept.
+    pub fn required(&self) -> Version {
+        self.required
+    }
+}
+
+impl fmt::Display for UnsupportedServerVersion {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.found {
+            Some(found) => write!(
+                fmt,
+                "server version {} is older than the required version {}",
+                found, self.required
+            ),
+            None => write!(
+                fmt,
+                "server did not report a valid version, but version {} is required",
+                self.required
+            ),
+        }
+    }
+}
+
+impl error::Error for UnsupportedServerVersion {}
+
+/// The errors encountered while attempting to connect to each of several hosts.
+///
+/// This is the source of the error returned when a connection configured with multiple hosts fails to connect to any
+/// of them, for example because all of them are read-only and a read-write session was requested.
+#[cfg(feature = "runtime")]
+#[derive(Debug)]
+pub struct ConnectAttempts(Vec<(String, Error)>);
+
+#[cfg(feature = "runtime")]
+impl ConnectAttempts {
+    /// Returns a description of each host that was tried, in order, along with the error encountered connecting to
+    /// it.
+    pub fn attempts(&self) -> &[(String, Error)] {
+        &self.0
+    }
+}
+
+#[cfg(feature = "runtime")]
+impl fmt::Display for ConnectAttempts {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.write_str("no host could be connected to")?;
+        for (host, error) in &self.0 {
+            write!(fmt, "; {}: {}", host, error)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "runtime")]
+impl error::Error for ConnectAttempts {}
+
+fn message_name(message: &Message) -> &'static str {
+    match message {
+        Message::AuthenticationCleartextPassword => "AuthenticationCleartextPassword",
+        Message::AuthenticationGss => "AuthenticationGSS",
+        Message::AuthenticationKerberosV5 => "AuthenticationKerberosV5",
+        Message::AuthenticationMd5Password(_) => "AuthenticationMD5Password",
+        Message::AuthenticationOk => "AuthenticationOk",
+        Message::AuthenticationScmCredential => "AuthenticationSCMCredential",
+        Message::AuthenticationSspi => "AuthenticationSSPI",
+        Message::AuthenticationGssContinue(_) => "AuthenticationGSSContinue",
+        Message::AuthenticationSasl(_) => "AuthenticationSASL",
+        Message::AuthenticationSaslContinue(_) => "AuthenticationSASLContinue",
+        Message::AuthenticationSaslFinal(_) => "AuthenticationSASLFinal",
+        Message::BackendKeyData(_) => "BackendKeyData",
+        Message::BindComplete => "BindComplete",
+        Message::CloseComplete => "CloseComplete",
+        Message::CommandComplete(_) => "CommandComplete",
+        Message::CopyData(_) => "CopyData",
+        Message::CopyDone => "CopyDone",
+        Message::CopyInResponse(_) => "CopyInResponse",
+        Message::CopyOutResponse(_) => "CopyOutResponse",
+        Message::CopyBothResponse(_) => "CopyBothResponse",
+        Message::DataRow(_) => "DataRow",
+        Message::EmptyQueryResponse => "EmptyQueryResponse",
+        Message::ErrorResponse(_) => "ErrorResponse",
+        Message::NoData => "NoData",
+        Message::NoticeResponse(_) => "NoticeResponse",
+        Message::NotificationResponse(_) => "NotificationResponse",
+        Message::ParameterDescription(_) => "ParameterDescription",
+        Message::ParameterStatus(_) => "ParameterStatus",
+        Message::ParseComplete => "ParseComplete",
+        Message::PortalSuspended => "PortalSuspended",
+        Message::ReadyForQuery(_) => "ReadyForQuery",
+        Message::RowDescription(_) => "RowDescription",
+        _ => "an unknown message",
+    }
+}
+
 /// Represents the position of an error in a query.
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub enum ErrorPosition {
@@ -335,16 +478,24 @@ enum Kind {
     UnexpectedMessage,
     Tls,
     ToSql(usize),
-    FromSql(usize),
+    ParameterCount(usize, usize),
+    MissingParameter(String),
+    FromSql(usize, String),
     Column,
+    RowCount,
     CopyInStream,
+    CopyRow(usize),
     Closed,
+    ClosedAfterSend,
+    Deallocated,
     Db,
     Parse,
     Encode,
     Authentication,
     ConfigParse,
     Config,
+    Encoding,
+    ServerVersion,
     #[cfg(feature = "runtime")]
     Connect,
 }
@@ -373,16 +524,32 @@ impl fmt::Display for Error {
             Kind::UnexpectedMessage => fmt.write_str("unexpected message from server")?,
             Kind::Tls => fmt.write_str("error performing TLS handshake")?,
             Kind::ToSql(idx) => write!(fmt, "error serializing parameter {}", idx)?,
-            Kind::FromSql(idx) => write!(fmt, "error deserializing column {}", idx)?,
+            Kind::ParameterCount(expected, got) => {
+                write!(fmt, "expected {} parameters but got {}", expected, got)?
+            }
+            Kind::MissingParameter(ref name) => {
+                write!(fmt, "no value provided for parameter `:{}`", name)?
+            }
+            Kind::FromSql(idx, ref name) => {
+                write!(fmt, "error deserializing column {} (`{}`)", idx, name)?
+            }
             Kind::Column => fmt.write_str("invalid column")?,
+            Kind::RowCount => fmt.write_str("query returned an unexpected number of rows")?,
             Kind::CopyInStream => fmt.write_str("error from a copy_in stream")?,
+            Kind::CopyRow(idx) => write!(fmt, "error encoding copy row {}", idx)?,
             Kind::Closed => fmt.write_str("connection closed")?,
+            Kind::ClosedAfterSend => {
+                fmt.write_str("connection closed before the response to a request was received")?
+            }
+            Kind::Deallocated => fmt.write_str("statement was deallocated")?,
             Kind::Db => fmt.write_str("db error")?,
             Kind::Parse => fmt.write_str("error parsing response from server")?,
             Kind::Encode => fmt.write_str("error encoding message to server")?,
             Kind::Authentication => fmt.write_str("authentication error")?,
             Kind::ConfigParse => fmt.write_str("invalid connection string")?,
             Kind::Config => fmt.write_str("invalid configuration")?,
+            Kind::Encoding => fmt.write_str("unsupported client encoding")?,
+            Kind::ServerVersion => fmt.write_str("unsupported server version")?,
             #[cfg(feature = "runtime")]
             Kind::Connect => fmt.write_str("error connecting to server")?,
         };
@@ -410,9 +577,48 @@ impl Error {
     /// This is a convenience method that downcasts the cause to a `DbError`
     /// and returns its code.
     pub fn code(&self) -> Option<&SqlState> {
-        self.source()
-            .and_then(|e| e.downcast_ref::<DbError>())
-            .map(DbError::code)
+        self.as_db_error().map(DbError::code)
+    }
+
+    /// Returns the database error which caused this error, if any.
+    ///
+    /// This provides access to every field of the error response sent by the server, such as the constraint or
+    /// table involved.
+    pub fn as_db_error(&self) -> Option<&DbError> {
+        self.source().and_then(|e| e.downcast_ref::<DbError>())
+    }
+
+    /// Determines if the error was caused by the connection to the server closing.
+    pub fn is_closed(&self) -> bool {
+        match self.0.kind {
+            Kind::Closed | Kind::ClosedAfterSend => true,
+            _ => false,
+        }
+    }
+
+    /// Determines if the connection closed after a request was sent to the server, but before its response was
+    /// received.
+    ///
+    /// The server may or may not have executed such a request, so it is not generally safe to retry. If this returns
+    /// `false` for a closed connection, the request was never sent and can be retried on a new connection. For a
+    /// request made inside an explicit transaction, the transaction will have been rolled back by the server if it
+    /// had not been committed, so the whole transaction can be retried.
+    pub fn is_closed_after_send(&self) -> bool {
+        match self.0.kind {
+            Kind::ClosedAfterSend => true,
+            _ => false,
+        }
+    }
+
+    /// Returns the expected and actual number of parameters if the error was caused by executing a statement with
+    /// the wrong number of parameters.
+    ///
+    /// Such errors are detected before anything is sent to the server.
+    pub fn parameter_count(&self) -> Option<(usize, usize)> {
+        match self.0.kind {
+            Kind::ParameterCount(expected, got) => Some((expected, got)),
+            _ => None,
+        }
     }
 
     fn new(kind: Kind, cause: Option<Box<dyn error::Error + Sync + Send>>) -> Error {
@@ -423,10 +629,28 @@ impl Error {
         Error::new(Kind::Closed, None)
     }
 
+    pub(crate) fn closed_after_send() -> Error {
+        Error::new(Kind::ClosedAfterSend, None)
+    }
+
+    pub(crate) fn deallocated() -> Error {
+        Error::new(Kind::Deallocated, None)
+    }
+
     pub(crate) fn unexpected_message() -> Error {
         Error::new(Kind::UnexpectedMessage, None)
     }
 
+    pub(crate) fn unexpected(expected: &'static str, message: &Message) -> Error {
+        Error::new(
+            Kind::UnexpectedMessage,
+            Some(Box::new(UnexpectedMessage {
+                expected,
+                received: message_name(message),
+            })),
+        )
+    }
+
     #[allow(clippy::needless_pass_by_value)]
     pub(crate) fn db(error: ErrorResponseBody) -> Error {
         match DbError::parse(&mut error.fields()) {
@@ -448,14 +672,44 @@ impl Error {
         Error::new(Kind::ToSql(idx), Some(e))
     }
 
-    pub(crate) fn from_sql(e: Box<dyn error::Error + Sync + Send>, idx: usize) -> Error {
-        Error::new(Kind::FromSql(idx), Some(e))
+    pub(crate) fn wrong_parameter_count(expected: usize, got: usize) -> Error {
+        Error::new(Kind::ParameterCount(expected, got), None)
+    }
+
+    pub(crate) fn missing_parameter(name: &str) -> Error {
+        Error::new(Kind::MissingParameter(name.to_string()), None)
+    }
+
+    pub(crate) fn from_sql(
+        e: Box<dyn error::Error + Sync + Send>,
+        idx: usize,
+        name: &str,
+    ) -> Error {
+        Error::new(Kind::FromSql(idx, name.to_string()), Some(e))
     }
 
     pub(crate) fn column() -> Error {
         Error::new(Kind::Column, None)
     }
 
+    pub(crate) fn column_count(expected: usize, got: usize) -> Error {
+        Error::new(
+            Kind::Column,
+            Some(format!("expected {} columns but the row has {}", expected, got).into()),
+        )
+    }
+
+    pub(crate) fn missing_field(column: &str, field: &str) -> Error {
+        Error::new(
+            Kind::Column,
+            Some(format!("no column `{}` for field `{}`", column, field).into()),
+        )
+    }
+
+    pub(crate) fn row_count() -> Error {
+        Error::new(Kind::RowCount, None)
+    }
+
     pub(crate) fn copy_in_stream<E>(e: E) -> Error
     where
         E: Into<Box<dyn error::Error + Sync + Send>>,
@@ -463,6 +717,10 @@ impl Error {
         Error::new(Kind::CopyInStream, Some(e.into()))
     }
 
+    pub(crate) fn copy_row(e: Box<dyn error::Error + Sync + Send>, idx: usize) -> Error {
+        Error::new(Kind::CopyRow(idx), Some(e))
+    }
+
     pub(crate) fn tls(e: Box<dyn error::Error + Sync + Send>) -> Error {
         Error::new(Kind::Tls, Some(e))
     }
@@ -483,8 +741,24 @@ impl Error {
         Error::new(Kind::Config, Some(e))
     }
 
+    pub(crate) fn encoding(e: Box<dyn error::Error + Sync + Send>) -> Error {
+        Error::new(Kind::Encoding, Some(e))
+    }
+
+    pub(crate) fn server_version(found: Option<Version>, required: Version) -> Error {
+        Error::new(
+            Kind::ServerVersion,
+            Some(Box::new(UnsupportedServerVersion { found, required })),
+        )
+    }
+
     #[cfg(feature = "runtime")]
     pub(crate) fn connect(e: io::Error) -> Error {
         Error::new(Kind::Connect, Some(Box::new(e)))
     }
+
+    #[cfg(feature = "runtime")]
+    pub(crate) fn connect_attempts(attempts: Vec<(String, Error)>) -> Error {
+        Error::new(Kind::Connect, Some(Box::new(ConnectAttempts(attempts))))
+    }
 }
@@ -1,11 +1,15 @@
 //! Futures and stream types used in the crate.
 use bytes::{Bytes, IntoBuf};
-use futures::{try_ready, Async, Future, Poll, Stream};
+use futures::{try_ready, Async, Future, Poll, Sink, StartSend, Stream};
 use std::error;
+use std::mem;
 use tokio_io::{AsyncRead, AsyncWrite};
 
 use crate::proto;
-use crate::{Client, Connection, Error, Portal, Row, SimpleQueryMessage, Statement, TlsConnect};
+use crate::{
+    Client, Connection, Error, NamedStatement, Portal, Row, SimpleQueryMessage, Statement,
+    TlsConnect,
+};
 #[cfg(feature = "runtime")]
 use crate::{MakeTlsConnect, Socket};
 
@@ -108,6 +112,39 @@ impl Future for Prepare {
     }
 }
 
+/// The future returned by `Client::prepare_and_query`.
+#[must_use = "futures do nothing unless polled"]
+pub struct PrepareAndQuery(pub(crate) proto::PrepareAndQueryFuture);
+
+impl Future for PrepareAndQuery {
+    type Item = (Statement, Vec<Row>);
+    type Error = Error;
+
+    fn poll(&mut self) -> Poll<(Statement, Vec<Row>), Error> {
+        let (statement, rows) = try_ready!(self.0.poll());
+
+        Ok(Async::Ready((Statement(statement), rows)))
+    }
+}
+
+/// The future returned by `Client::prepare_named`.
+#[must_use = "futures do nothing unless polled"]
+pub struct PrepareNamed(pub(crate) proto::PrepareFuture, pub(crate) Vec<String>);
+
+impl Future for PrepareNamed {
+    type Item = NamedStatement;
+    type Error = Error;
+
+    fn poll(&mut self) -> Poll<NamedStatement, Error> {
+        let statement = try_ready!(self.0.poll());
+
+        Ok(Async::Ready(NamedStatement {
+            statement: Statement(statement),
+            names: mem::replace(&mut self.1, vec![]),
+        }))
+    }
+}
+
 /// The future returned by `Client::query`.
 #[must_use = "streams do nothing unless polled"]
 pub struct Query(pub(crate) proto::QueryStream<proto::Statement>);
@@ -121,6 +158,19 @@ impl Stream for Query {
     }
 }
 
+/// The future returned by `Client::query_one`.
+#[must_use = "futures do nothing unless polled"]
+pub struct QueryOne(pub(crate) proto::QueryOneFuture);
+
+impl Future for QueryOne {
+    type Item = Row;
+    type Error = Error;
+
+    fn poll(&mut self) -> Poll<Row, Error> {
+        self.0.poll()
+    }
+}
+
 /// The future returned by `Client::execute`.
 #[must_use = "futures do nothing unless polled"]
 pub struct Execute(pub(crate) proto::ExecuteFuture);
@@ -134,6 +184,19 @@ impl Future for Execute {
     }
 }
 
+/// The future returned by `Client::close_statement`.
+#[must_use = "futures do nothing unless polled"]
+pub struct CloseStatement(pub(crate) proto::CloseFuture);
+
+impl Future for CloseStatement {
+    type Item = ();
+    type Error = Error;
+
+    fn poll(&mut self) -> Poll<(), Error> {
+        self.0.poll()
+    }
+}
+
 /// The future returned by `Client::bind`.
 #[must_use = "futures do nothing unless polled"]
 pub struct Bind(pub(crate) proto::BindFuture);
@@ -201,6 +264,49 @@ impl Stream for CopyOut {
     }
 }
 
+/// The stream returned by `Client::query_field`.
+#[must_use = "streams do nothing unless polled"]
+pub struct QueryField(pub(crate) proto::FieldStream);
+
+impl Stream for QueryField {
+    type Item = Bytes;
+    type Error = Error;
+
+    fn poll(&mut self) -> Poll<Option<Bytes>, Error> {
+        self.0.poll()
+    }
+}
+
+/// The stream and sink returned by `Client::copy_both_simple`.
+#[must_use = "streams do nothing unless polled"]
+pub struct CopyBoth(pub(crate) proto::CopyBothDuplex);
+
+impl Stream for CopyBoth {
+    type Item = Bytes;
+    type Error = Error;
+
+    fn poll(&mut self) -> Poll<Option<Bytes>, Error> {
+        self.0.poll()
+    }
+}
+
+impl Sink for CopyBoth {
+    type SinkItem = Bytes;
+    type SinkError = Error;
+
+    fn start_send(&mut self, item: Bytes) -> StartSend<Bytes, Error> {
+        self.0.start_send(item)
+    }
+
+    fn poll_complete(&mut self) -> Poll<(), Error> {
+        self.0.poll_complete()
+    }
+
+    fn close(&mut self) -> Poll<(), Error> {
+        self.0.close()
+    }
+}
+
 /// The stream returned by `Client::simple_query`.
 #[must_use = "streams do nothing unless polled"]
 pub struct SimpleQuery(pub(crate) proto::SimpleQueryStream);
@@ -214,6 +320,21 @@ impl Stream for SimpleQuery {
     }
 }
 
+/// The stream returned by `Client::raw_request`.
+#[cfg(feature = "raw-protocol")]
+#[must_use = "streams do nothing unless polled"]
+pub struct RawRequest(pub(crate) proto::RawStream);
+
+#[cfg(feature = "raw-protocol")]
+impl Stream for RawRequest {
+    type Item = (u8, Vec<u8>);
+    type Error = Error;
+
+    fn poll(&mut self) -> Poll<Option<(u8, Vec<u8>)>, Error> {
+        self.0.poll()
+    }
+}
+
 /// The future returned by `TransactionBuilder::build`.
 #[must_use = "futures do nothing unless polled"]
 pub struct Transaction<T>(pub(crate) proto::TransactionFuture<T, T::Item, T::Error>)
@@ -113,8 +113,11 @@
 
 use bytes::IntoBuf;
 use futures::{Future, Poll, Stream};
+use std::collections::HashMap;
 use std::error::Error as StdError;
 use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
 use tokio_io::{AsyncRead, AsyncWrite};
 
 pub use crate::config::Config;
@@ -128,22 +131,28 @@ pub use crate::stmt::Column;
 use crate::tls::MakeTlsConnect;
 pub use crate::tls::NoTls;
 use crate::tls::TlsConnect;
-use crate::types::{ToSql, Type};
+use crate::types::{Format, ToSql, Type};
+pub use crate::version::{ParseVersionError, Version};
 
+pub mod auth;
+pub mod binary_copy;
 pub mod config;
 pub mod error;
 pub mod impls;
+mod named;
 mod proto;
+pub mod replication;
 pub mod row;
 #[cfg(feature = "runtime")]
 mod socket;
 mod stmt;
 pub mod tls;
 pub mod types;
+mod version;
 
-fn next_statement() -> String {
+fn next_statement(prefix: &str) -> String {
     static ID: AtomicUsize = AtomicUsize::new(0);
-    format!("s{}", ID.fetch_add(1, Ordering::SeqCst))
+    format!("{}{}", prefix, ID.fetch_add(1, Ordering::SeqCst))
 }
 
 fn next_portal() -> String {
@@ -186,16 +195,76 @@ impl Client {
     /// The list of types may be smaller than the number of parameters - the types of the remaining parameters will be
     /// inferred. For example, `client.prepare_typed(query, &[])` is equivalent to `client.prepare(query)`.
     pub fn prepare_typed(&mut self, query: &str, param_types: &[Type]) -> impls::Prepare {
-        impls::Prepare(self.0.prepare(next_statement(), query, param_types))
+        impls::Prepare(
+            self.0
+                .prepare(self.0.next_statement(), query, param_types, &[]),
+        )
+    }
+
+    /// Like `prepare`, but allows the formats the result columns are requested in to be explicitly specified.
+    ///
+    /// The list of formats may be smaller than the number of columns - the remaining columns are requested in the
+    /// binary format, unless their type has no binary format. Values of columns requested in the text format are
+    /// decoded with `FromSql::from_sql_text`, and can always be retrieved as strings.
+    ///
+    /// Formats are not negotiated per column automatically: every column is requested in the binary format by default,
+    /// even if no `FromSql` implementation can decode its type's binary format, and only columns explicitly requested
+    /// in the text format here are retrieved as text. Columns of types without a `FromSql` implementation should be
+    /// requested in the text format to read them as strings.
+    pub fn prepare_with_result_formats(
+        &mut self,
+        query: &str,
+        result_formats: &[Format],
+    ) -> impls::Prepare {
+        impls::Prepare(
+            self.0
+                .prepare(self.0.next_statement(), query, &[], result_formats),
+        )
+    }
+
+    /// Like `prepare`, but the query refers to its parameters by name with `:name` placeholders rather than by
+    /// position.
+    ///
+    /// Each distinct name is bound to a single parameter, so a name can be used several times in the same query.
+    /// Placeholders are not recognized inside string literals, quoted identifiers or comments, and `::` casts and the
+    /// `:` separating the bounds of an array slice are left alone. The statement is executed with `query_named`.
+    pub fn prepare_named(&mut self, query: &str) -> impls::PrepareNamed {
+        let (query, names) = named::rewrite(query);
+        impls::PrepareNamed(
+            self.0.prepare(self.0.next_statement(), &query, &[], &[]),
+            names.into_iter().map(ToString::to_string).collect(),
+        )
+    }
+
+    /// Prepares a statement and executes it with the given parameters in a single round trip, returning the statement
+    /// along with the resulting rows.
+    ///
+    /// Unlike `prepare_typed`, the type of every parameter must be specified, since the parameters are encoded before
+    /// the server has described the statement. For the same reason, every column of the result is requested in the
+    /// binary format. The returned statement can be used with `query` and `execute` as normal.
+    ///
+    /// An error is returned if the number of parameters provided does not match the number of parameter types.
+    pub fn prepare_and_query(
+        &mut self,
+        query: &str,
+        param_types: &[Type],
+        params: &[&dyn ToSql],
+    ) -> impls::PrepareAndQuery {
+        impls::PrepareAndQuery(self.0.prepare_and_query(
+            self.0.next_statement(),
+            query,
+            param_types,
+            params.iter().cloned(),
+        ))
     }
 
     /// Executes a statement, returning the number of rows modified.
     ///
     /// If the statement does not modify any rows (e.g. `SELECT`), 0 is returned.
     ///
-    /// # Panics
-    ///
-    /// Panics if the number of parameters provided does not match the number expected.
+    /// For an `INSERT ... ON CONFLICT DO NOTHING` of a single row, 1 is returned if the row was inserted and 0 if it
+    /// was skipped. `ON CONFLICT DO UPDATE` reports 1 for both an insert and an update; to tell them apart, use
+    /// `RETURNING (xmax = 0) AS inserted` with `query` instead.
     pub fn execute(&mut self, statement: &Statement, params: &[&dyn ToSql]) -> impls::Execute {
         self.execute_iter(statement, params.iter().cloned())
     }
@@ -212,10 +281,6 @@ impl Client {
     }
 
     /// Executes a statement, returning a stream of the resulting rows.
-    ///
-    /// # Panics
-    ///
-    /// Panics if the number of parameters provided does not match the number expected.
     pub fn query(&mut self, statement: &Statement, params: &[&dyn ToSql]) -> impls::Query {
         self.query_iter(statement, params.iter().cloned())
     }
@@ -231,15 +296,35 @@ impl Client {
         impls::Query(self.0.query(&statement.0, params))
     }
 
+    /// Like [`query`], but for a statement created by `prepare_named`, taking its parameters by name.
+    ///
+    /// Names in `params` which aren't used by the statement are ignored, and an error is returned if a name used by
+    /// the statement is missing.
+    ///
+    /// [`query`]: #method.query
+    pub fn query_named(
+        &mut self,
+        statement: &NamedStatement,
+        params: &HashMap<&str, &dyn ToSql>,
+    ) -> impls::Query {
+        impls::Query(
+            self.0
+                .query_named(&statement.statement.0, &statement.names, params),
+        )
+    }
+
+    /// Executes a statement which returns a single row, returning it.
+    ///
+    /// An error is returned if the statement returns no rows or more than one row.
+    pub fn query_one(&mut self, statement: &Statement, params: &[&dyn ToSql]) -> impls::QueryOne {
+        impls::QueryOne(self.0.query_one(&statement.0, params.iter().cloned()))
+    }
+
     /// Binds a statement to a set of parameters, creating a `Portal` which can be incrementally queried.
     ///
     /// Portals only last for the duration of the transaction in which they are created - in particular, a portal
     /// created outside of a transaction is immediately destroyed. Portals can only be used on the connection that
     /// created them.
-    ///
-    /// # Panics
-    ///
-    /// Panics if the number of parameters provided does not match the number expected.
     pub fn bind(&mut self, statement: &Statement, params: &[&dyn ToSql]) -> impls::Bind {
         self.bind_iter(statement, params.iter().cloned())
     }
@@ -255,6 +340,15 @@ impl Client {
         impls::Bind(self.0.bind(&statement.0, next_portal(), params))
     }
 
+    /// Closes a prepared statement, releasing its resources on the server.
+    ///
+    /// Statements are closed automatically when they are dropped, but this happens in the background and any error
+    /// is ignored. This method allows a statement to be closed eagerly, and reports any error returned by the server.
+    /// Any clones of the statement will no longer be usable once it has been closed.
+    pub fn close_statement(&mut self, statement: Statement) -> impls::CloseStatement {
+        impls::CloseStatement(self.0.close_prepared_statement(&statement.0))
+    }
+
     /// Continues execution of a portal, returning a stream of the resulting rows.
     ///
     /// Unlike `query`, portals can be incrementally evaluated by limiting the number of rows returned in each call to
@@ -267,10 +361,6 @@ impl Client {
     ///
     /// The data in the provided stream is passed along to the server verbatim; it is the caller's responsibility to
     /// ensure it uses the proper format.
-    ///
-    /// # Panics
-    ///
-    /// Panics if the number of parameters provided does not match the number expected.
     pub fn copy_in<S>(
         &mut self,
         statement: &Statement,
@@ -309,10 +399,6 @@ impl Client {
     }
 
     /// Executes a `COPY TO STDOUT` statement, returning a stream of the resulting data.
-    ///
-    /// # Panics
-    ///
-    /// Panics if the number of parameters provided does not match the number expected.
     pub fn copy_out(&mut self, statement: &Statement, params: &[&dyn ToSql]) -> impls::CopyOut {
         self.copy_out_iter(statement, params.iter().cloned())
     }
@@ -328,13 +414,50 @@ impl Client {
         impls::CopyOut(self.0.copy_out(&statement.0, params))
     }
 
+    /// Executes a statement which returns a single column, returning a stream of the contents of its values.
+    ///
+    /// Unlike `query`, rows are not buffered in memory before being returned. Each value is instead passed on in
+    /// chunks as it is received, so a single huge value, such as the result of `pg_read_binary_file`, can be written
+    /// to its destination without being held in memory, and without being limited by the maximum message size. The
+    /// chunks contain the raw encoding of the values in the column's format, which for a `BYTEA` column is its bytes.
+    /// The values of consecutive rows are not delimited, and the stream fails with an error if it encounters a `NULL`
+    /// value, which can't be represented in the stream.
+    ///
+    /// The stream fails with an error if the statement does not return exactly one column.
+    pub fn query_field(
+        &mut self,
+        statement: &Statement,
+        params: &[&dyn ToSql],
+    ) -> impls::QueryField {
+        impls::QueryField(self.0.query_field(&statement.0, params.iter().cloned()))
+    }
+
+    /// Executes a command which starts a `COPY BOTH` operation, using the simple query protocol.
+    ///
+    /// This is used by the commands of the streaming replication protocol such as `START_REPLICATION`, which are
+    /// available on connections configured with a replication mode. The returned value is both a stream of the
+    /// contents of the `CopyData` messages sent by the server, and a sink which sends `CopyData` messages to the
+    /// server. Closing the sink ends the operation, after which the stream finishes once the server has completed the
+    /// command. The `replication` module can be used to parse and create the messages of the replication protocol.
+    ///
+    /// # Warning
+    ///
+    /// As with `simple_query`, do not form commands via string concatenation of user-specified data.
+    pub fn copy_both_simple(&mut self, query: &str) -> impls::CopyBoth {
+        impls::CopyBoth(self.0.copy_both_simple(query))
+    }
+
     /// Executes a sequence of SQL statements using the simple query protocol.
     ///
     /// Statements should be separated by semicolons. If an error occurs, execution of the sequence will stop at that
     /// point. The simple query protocol returns the values in rows as strings rather than in their binary encodings,
     /// so the associated row type doesn't work with the `FromSql` trait. Rather than simply returning a stream over the
     /// rows, this method returns a stream over an enum which indicates either the completion of one of the commands,
-    /// or a row of data. This preserves the framing between the separate statements in the request.
+    /// the start of a new result set, or a row of data. This preserves the framing between the separate statements in
+    /// the request.
+    ///
+    /// If the query runs `DEALLOCATE ALL` or `DISCARD ALL`, statements prepared earlier no longer exist on the server,
+    /// and using them will return an error.
     ///
     /// # Warning
     ///
@@ -345,6 +468,26 @@ impl Client {
         impls::SimpleQuery(self.0.simple_query(query))
     }
 
+    /// Sends a raw frontend message to the server, returning a stream over the raw backend messages it responds with.
+    ///
+    /// This is an escape hatch for prototyping support for messages which aren't otherwise exposed by this crate. The
+    /// message is framed from its tag and payload, and each response is yielded as a tag and payload, with the
+    /// `ReadyForQuery` message which ends the request yielded last. Asynchronous messages such as notices and
+    /// notifications are still handled by the `Connection`.
+    ///
+    /// Requires the `raw-protocol` Cargo feature.
+    ///
+    /// # Warning
+    ///
+    /// The caller is responsible for the correctness of the exchange. The message must cause the server to respond
+    /// with `ReadyForQuery` (for example, `Query` or `Sync`), and must not start a `COPY` or otherwise leave the
+    /// session in a state the rest of the client does not expect. Otherwise, the connection will hang or become
+    /// desynchronized.
+    #[cfg(feature = "raw-protocol")]
+    pub fn raw_request(&mut self, tag: u8, payload: &[u8]) -> impls::RawRequest {
+        impls::RawRequest(self.0.raw(tag, payload))
+    }
+
     /// A utility method to wrap a future in a database transaction.
     ///
     /// The returned future will start a transaction and then run the provided future. If the future returns `Ok`, it
@@ -391,13 +534,67 @@ impl Client {
         impls::CancelQueryRaw(self.0.cancel_query_raw(stream, tls_mode))
     }
 
+    /// Returns the configuration the client's connection was opened with.
+    ///
+    /// Passing it to `Config::connect` opens a second, independent connection to the same server with the same
+    /// parameters, for example to run queries in parallel.
+    pub fn config(&self) -> &Config {
+        self.0.config()
+    }
+
     /// Determines if the connection to the server has already closed.
     ///
-    /// In that case, all future queries will fail.
+    /// In that case, all future queries will fail. The connection is closed if an IO error occurs, or if the client
+    /// and server lose track of the state of the protocol (for example, due to an unexpected message). A closed
+    /// connection cannot be recovered, and the client should be dropped. Connection pools can use this method to
+    /// avoid returning broken clients to the pool.
     pub fn is_closed(&self) -> bool {
         self.0.is_closed()
     }
 
+    /// Returns the error the server sent when it closed the connection, if any.
+    ///
+    /// The server reports why it is terminating a session, for example with the `ADMIN_SHUTDOWN` error code when the
+    /// server is shutting down or the backend was terminated by an administrator. This method returns that error
+    /// without communicating with the server, so connection pools can cheaply use it alongside `is_closed` to discard
+    /// and log clients the server has torn down. The error is only available once the `Connection` has processed it,
+    /// and is returned at most once.
+    pub fn take_error(&mut self) -> Option<DbError> {
+        self.0.take_error()
+    }
+
+    /// Returns the state of the session.
+    ///
+    /// The state is updated each time the server finishes processing a request, so it does not reflect requests
+    /// which are still in progress. Note that a request can return an error before the server has finished with it;
+    /// `poll_idle` can be used to wait for all requests to finish. This can be used to detect a transaction which has
+    /// failed and must be rolled back, or by a connection pool to avoid reusing a client which has been left in a
+    /// transaction.
+    pub fn state(&self) -> ConnectionState {
+        if self.0.is_closed() {
+            if self.0.is_desynchronized() {
+                return ConnectionState::Desynchronized;
+            }
+            return ConnectionState::Closed;
+        }
+
+        match self.0.transaction_status() {
+            b'T' => ConnectionState::InTransaction,
+            b'E' => ConnectionState::InFailedTransaction,
+            _ => ConnectionState::Idle,
+        }
+    }
+
+    /// Returns how long the current transaction has been open, or `None` if the session is not in a transaction.
+    ///
+    /// The duration is measured from when the server first reported the session as being in a transaction, which is
+    /// when the response to the `BEGIN` arrives. Like `state`, it is not updated until the server finishes processing
+    /// a request. A connection pool can use this to find clients which have been left idle in a transaction for too
+    /// long, before the server's `idle_in_transaction_session_timeout` terminates the session.
+    pub fn transaction_duration(&self) -> Option<Duration> {
+        self.0.transaction_duration()
+    }
+
     /// Polls the client to check if it is idle.
     ///
     /// A connection is idle if there are no outstanding requests, whether they have begun being polled or not. For
@@ -425,10 +622,35 @@ where
     T: AsyncRead + AsyncWrite,
 {
     /// Returns the value of a runtime parameter for this connection.
+    ///
+    /// Only the parameters the server reports to the client are available, such as `server_version`,
+    /// `server_encoding`, `client_encoding`, `DateStyle`, `IntervalStyle`, `TimeZone`, `integer_datetimes` and
+    /// `standard_conforming_strings`. Their values are kept up to date as they change during the session. Other
+    /// settings, including locale settings like `lc_numeric` and `lc_monetary` which affect the text format of some
+    /// values, are not reported and must be queried with `SHOW` instead.
     pub fn parameter(&self, name: &str) -> Option<&str> {
         self.0.parameter(name)
     }
 
+    /// Returns the version of the server, parsed from the `server_version` runtime parameter.
+    ///
+    /// Returns `None` if the server did not report its version or it could not be parsed.
+    pub fn server_version(&self) -> Option<Version> {
+        self.parameter("server_version")?.parse().ok()
+    }
+
+    /// Sets a callback which will be invoked for every protocol message sent or received on this connection.
+    ///
+    /// The callback is passed the direction of the message, its tag byte, and its total length in bytes. This is
+    /// intended to help diagnose protocol-level issues, and is not invoked for messages exchanged while the connection
+    /// was being established.
+    pub fn set_message_trace<F>(&mut self, trace: F)
+    where
+        F: FnMut(MessageDirection, u8, usize) + 'static + Send,
+    {
+        self.0.set_message_trace(Box::new(trace));
+    }
+
     /// Polls for asynchronous messages from the server.
     ///
     /// The server can send notices as well as notifications asynchronously to the client. Applications which wish to
@@ -451,6 +673,35 @@ where
     }
 }
 
+/// The direction in which a protocol message was sent.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum MessageDirection {
+    /// A message sent from the client to the server.
+    Frontend,
+    /// A message sent from the server to the client.
+    Backend,
+}
+
+/// The state of a session, as reported by the server.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ConnectionState {
+    /// The session is not in a transaction block.
+    Idle,
+    /// The session is in a transaction block.
+    InTransaction,
+    /// The session is in a failed transaction block.
+    ///
+    /// The server will reject all statements until the transaction is rolled back.
+    InFailedTransaction,
+    /// The connection is closed.
+    Closed,
+    /// The connection was closed because the server sent a response the client did not expect.
+    ///
+    /// The client and server no longer agree on the state of the protocol, which usually indicates a bug in the
+    /// client or misuse of `Client::raw_request`. Like `Closed`, the client can no longer be used.
+    Desynchronized,
+}
+
 /// An asynchronous message from the server.
 #[allow(clippy::large_enum_variant)]
 pub enum AsyncMessage {
@@ -478,12 +729,41 @@ impl Statement {
         self.0.params()
     }
 
+    /// Returns the number of parameters the statement expects.
+    ///
+    /// Executing the statement with a different number of parameters fails with an error before anything is sent to
+    /// the server.
+    pub fn parameter_count(&self) -> usize {
+        self.0.params().len()
+    }
+
     /// Returns information about the columns returned when the statement is queried.
     pub fn columns(&self) -> &[Column] {
         self.0.columns()
     }
 }
 
+/// A prepared statement whose parameters are referred to by name.
+///
+/// Created by `Client::prepare_named`.
+#[derive(Clone)]
+pub struct NamedStatement {
+    statement: Statement,
+    names: Vec<String>,
+}
+
+impl NamedStatement {
+    /// Returns the underlying statement, whose parameters are in the same order as `names`.
+    pub fn statement(&self) -> &Statement {
+        &self.statement
+    }
+
+    /// Returns the names of the statement's parameters, in order.
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+}
+
 /// A portal.
 ///
 /// Portals can only be used with the connection that created them, and only exist for the duration of the transaction
@@ -507,6 +787,12 @@ impl TransactionBuilder {
 
 /// Message returned by the `SimpleQuery` stream.
 pub enum SimpleQueryMessage {
+    /// The start of a new result set.
+    ///
+    /// A single query string can contain several statements which return rows. Each of their result sets is preceded
+    /// by this message, which contains the names of the set's columns. The set is terminated by a `CommandComplete`
+    /// message.
+    RowDescription(Arc<[String]>),
     /// A row of data.
     Row(SimpleQueryRow),
     /// A statement in the query has completed.
@@ -0,0 +1,143 @@
+// Rewrites `:name` placeholders to positional `$N` parameters, returning the new query and the names in parameter
+// order. Each distinct name is assigned a single position. Placeholders inside string literals, quoted identifiers,
+// dollar-quoted strings and comments are left alone, as are `::` casts. Directly inside an array subscript a `:` is
+// always a slice bound separator, as in `arr[lo:hi]`.
+pub(crate) fn rewrite(query: &str) -> (String, Vec<&str>) {
+    let bytes = query.as_bytes();
+    let mut out = String::with_capacity(query.len());
+    let mut names: Vec<&str> = vec![];
+    // one entry per open bracket or parenthesis, true for array subscripts
+    let mut nesting = vec![];
+    let mut copied = 0;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'[' => {
+                nesting.push(!follows_array_keyword(bytes, i));
+                i += 1;
+            }
+            b'(' => {
+                nesting.push(false);
+                i += 1;
+            }
+            b']' | b')' => {
+                nesting.pop();
+                i += 1;
+            }
+            b'\'' => {
+                let escapes = i > 0 && (bytes[i - 1] == b'E' || bytes[i - 1] == b'e');
+                i = skip_quoted(bytes, i, b'\'', escapes);
+            }
+            b'"' => i = skip_quoted(bytes, i, b'"', false),
+            b'-' if bytes.get(i + 1) == Some(&b'-') => {
+                i = match query[i..].find('\n') {
+                    Some(end) => i + end + 1,
+                    None => bytes.len(),
+                };
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                i = match query[i + 2..].find("*/") {
+                    Some(end) => i + 2 + end + 2,
+                    None => bytes.len(),
+                };
+            }
+            b'$' => i = skip_dollar_quoted(query, i),
+            b':' if bytes.get(i + 1) == Some(&b':') => i += 2,
+            b':' if nesting.last() == Some(&true) => i += 1,
+            b':' if i + 1 < bytes.len() && is_name_start(bytes[i + 1]) => {
+                let start = i + 1;
+                let mut end = start;
+                while end < bytes.len() && is_name_char(bytes[end]) {
+                    end += 1;
+                }
+                let name = &query[start..end];
+
+                let idx = match names.iter().position(|&n| n == name) {
+                    Some(idx) => idx,
+                    None => {
+                        names.push(name);
+                        names.len() - 1
+                    }
+                };
+
+                out.push_str(&query[copied..i]);
+                out.push('$');
+                out.push_str(&(idx + 1).to_string());
+                copied = end;
+                i = end;
+            }
+            _ => i += 1,
+        }
+    }
+
+    out.push_str(&query[copied..]);
+    (out, names)
+}
+
+// Returns the index after the closing quote of the literal starting at `start`.
+fn skip_quoted(bytes: &[u8], start: usize, quote: u8, escapes: bool) -> usize {
+    let mut i = start + 1;
+    while i < bytes.len() {
+        if escapes && bytes[i] == b'\\' {
+            i += 2;
+        } else if bytes[i] == quote {
+            // a doubled quote is an escaped quote
+            if bytes.get(i + 1) == Some(&quote) {
+                i += 2;
+            } else {
+                return i + 1;
+            }
+        } else {
+            i += 1;
+        }
+    }
+    bytes.len()
+}
+
+// Returns the index after a dollar-quoted string starting at `start`, or just past the `$` if it doesn't start one.
+fn skip_dollar_quoted(query: &str, start: usize) -> usize {
+    let bytes = query.as_bytes();
+    // a `$` following an identifier character is part of the identifier, and `$1` is a positional parameter
+    if start > 0 && is_name_char(bytes[start - 1]) {
+        return start + 1;
+    }
+
+    let mut end = start + 1;
+    while end < bytes.len() && bytes[end] != b'$' {
+        if !is_name_char(bytes[end]) || (end == start + 1 && bytes[end].is_ascii_digit()) {
+            return start + 1;
+        }
+        end += 1;
+    }
+    if end == bytes.len() {
+        return start + 1;
+    }
+
+    let tag = &query[start..=end];
+    match query[end + 1..].find(tag) {
+        Some(close) => end + 1 + close + tag.len(),
+        None => bytes.len(),
+    }
+}
+
+// Determines if the `[` at `idx` opens an `ARRAY[...]` constructor rather than a subscript.
+fn follows_array_keyword(bytes: &[u8], idx: usize) -> bool {
+    let end = bytes[..idx]
+        .iter()
+        .rposition(|b| !b.is_ascii_whitespace())
+        .map_or(0, |i| i + 1);
+    let start = bytes[..end]
+        .iter()
+        .rposition(|&b| !is_name_char(b))
+        .map_or(0, |i| i + 1);
+    bytes[start..end].eq_ignore_ascii_case(b"array")
+}
+
+fn is_name_start(b: u8) -> bool {
+    b.is_ascii_alphabetic() || b == b'_'
+}
+
+fn is_name_char(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b == b'_'
+}
@@ -55,7 +55,7 @@ impl PollBind for Bind {
                 state.name,
                 state.statement,
             ))),
-            Some(_) => Err(Error::unexpected_message()),
+            Some(m) => Err(Error::unexpected("BindComplete", &m)),
             None => Err(Error::closed()),
         }
     }
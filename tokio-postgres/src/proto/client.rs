@@ -6,27 +6,35 @@ use postgres_protocol;
 use postgres_protocol::message::frontend;
 use std::collections::HashMap;
 use std::error::Error as StdError;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::{Arc, Weak};
+use std::time::Duration;
 use tokio_io::{AsyncRead, AsyncWrite};
 
 use crate::proto::bind::BindFuture;
-use crate::proto::codec::FrontendMessage;
+use crate::proto::close::CloseFuture;
+use crate::proto::codec::{FrontendMessage, TransactionState};
 use crate::proto::connection::{Request, RequestMessages};
+use crate::proto::copy_both::CopyBothDuplex;
 use crate::proto::copy_in::{CopyInFuture, CopyInReceiver, CopyMessage};
 use crate::proto::copy_out::CopyOutStream;
 use crate::proto::execute::ExecuteFuture;
+use crate::proto::field::FieldStream;
 use crate::proto::idle::{IdleGuard, IdleState};
 use crate::proto::portal::Portal;
 use crate::proto::prepare::PrepareFuture;
-use crate::proto::query::QueryStream;
+use crate::proto::prepare_and_query::PrepareAndQueryFuture;
+use crate::proto::query::{QueryOneFuture, QueryStream};
+#[cfg(feature = "raw-protocol")]
+use crate::proto::raw::RawStream;
 use crate::proto::responses::{self, Responses};
 use crate::proto::simple_query::SimpleQueryStream;
 use crate::proto::statement::Statement;
 #[cfg(feature = "runtime")]
 use crate::proto::CancelQueryFuture;
 use crate::proto::CancelQueryRawFuture;
-use crate::types::{IsNull, Oid, ToSql, Type};
-use crate::{Config, Error, TlsConnect};
+use crate::types::{Format, IsNull, Oid, ToSql, Type};
+use crate::{Config, DbError, Error, TlsConnect};
 #[cfg(feature = "runtime")]
 use crate::{MakeTlsConnect, Socket};
 
@@ -53,10 +61,12 @@ struct Inner {
     sender: mpsc::UnboundedSender<Request>,
     process_id: i32,
     secret_key: i32,
-    #[cfg_attr(not(feature = "runtime"), allow(dead_code))]
     config: Config,
     #[cfg_attr(not(feature = "runtime"), allow(dead_code))]
     idx: Option<usize>,
+    transaction: Arc<TransactionState>,
+    error: Arc<Mutex<Option<DbError>>>,
+    deallocations: AtomicUsize,
 }
 
 #[derive(Clone)]
@@ -69,6 +79,8 @@ impl Client {
         secret_key: i32,
         config: Config,
         idx: Option<usize>,
+        transaction: Arc<TransactionState>,
+        error: Arc<Mutex<Option<DbError>>>,
     ) -> Client {
         Client(Arc::new(Inner {
             state: Mutex::new(State {
@@ -83,13 +95,40 @@ impl Client {
             secret_key,
             config,
             idx,
+            transaction,
+            error,
+            deallocations: AtomicUsize::new(0),
         }))
     }
 
+    pub fn config(&self) -> &Config {
+        &self.0.config
+    }
+
     pub fn is_closed(&self) -> bool {
         self.0.sender.is_closed()
     }
 
+    pub fn transaction_status(&self) -> u8 {
+        self.0.transaction.status()
+    }
+
+    pub fn is_desynchronized(&self) -> bool {
+        self.0.transaction.is_desynchronized()
+    }
+
+    pub fn transaction_duration(&self) -> Option<Duration> {
+        self.0.transaction.duration()
+    }
+
+    pub fn next_statement(&self) -> String {
+        crate::next_statement(&self.0.config.0.statement_name_prefix)
+    }
+
+    pub fn take_error(&self) -> Option<DbError> {
+        self.0.error.lock().take()
+    }
+
     pub fn poll_idle(&self) -> Poll<(), Error> {
         self.0.idle.poll_idle()
     }
@@ -130,15 +169,39 @@ impl Client {
         self.0.state.lock().typeinfo_composite_query = Some(statement.clone());
     }
 
+    // The number of times all prepared statements have been deallocated by a `DEALLOCATE ALL` or `DISCARD ALL`.
+    pub fn deallocations(&self) -> usize {
+        self.0.deallocations.load(Ordering::SeqCst)
+    }
+
+    pub fn statements_deallocated(&self) {
+        let mut state = self.0.state.lock();
+        state.typeinfo_query = None;
+        state.typeinfo_enum_query = None;
+        state.typeinfo_composite_query = None;
+        self.0.deallocations.fetch_add(1, Ordering::SeqCst);
+    }
+
     pub fn send(&self, request: PendingRequest) -> Result<Responses, Error> {
+        self.send_inner(request, false)
+    }
+
+    // Like `send`, but the fields of the rows in the response are split into `CopyData` messages.
+    pub fn send_streaming_fields(&self, request: PendingRequest) -> Result<Responses, Error> {
+        self.send_inner(request, true)
+    }
+
+    fn send_inner(&self, request: PendingRequest, stream_fields: bool) -> Result<Responses, Error> {
         let (messages, idle) = request.0?;
-        let (sender, receiver) = responses::channel();
+        let (sender, sent, receiver) = responses::channel();
         self.0
             .sender
             .unbounded_send(Request {
                 messages,
                 sender,
+                sent,
                 idle: Some(idle),
+                stream_fields,
             })
             .map(|_| receiver)
             .map_err(|_| Error::closed())
@@ -153,7 +216,31 @@ impl Client {
         SimpleQueryStream::new(self.clone(), pending)
     }
 
-    pub fn prepare(&self, name: String, query: &str, param_types: &[Type]) -> PrepareFuture {
+    #[cfg(feature = "raw-protocol")]
+    pub fn raw(&self, tag: u8, payload: &[u8]) -> RawStream {
+        let pending = self.pending(|buf| {
+            if payload.len() > i32::max_value() as usize - 4 {
+                return Err(Error::encode(std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    "value too large to transmit",
+                )));
+            }
+            buf.push(tag);
+            buf.extend_from_slice(&(payload.len() as i32 + 4).to_be_bytes());
+            buf.extend_from_slice(payload);
+            Ok(())
+        });
+
+        RawStream::new(self.clone(), pending)
+    }
+
+    pub fn prepare(
+        &self,
+        name: String,
+        query: &str,
+        param_types: &[Type],
+        result_formats: &[Format],
+    ) -> PrepareFuture {
         let pending = self.pending(|buf| {
             frontend::parse(&name, query, param_types.iter().map(Type::oid), buf)
                 .map_err(Error::parse)?;
@@ -162,7 +249,32 @@ impl Client {
             Ok(())
         });
 
-        PrepareFuture::new(self.clone(), pending, name)
+        PrepareFuture::new(self.clone(), pending, name, result_formats.to_vec())
+    }
+
+    pub fn prepare_and_query<'a, I>(
+        &self,
+        name: String,
+        query: &str,
+        param_types: &[Type],
+        params: I,
+    ) -> PrepareAndQueryFuture
+    where
+        I: IntoIterator<Item = &'a dyn ToSql>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        let pending = self.pending(|buf| {
+            frontend::parse(&name, query, param_types.iter().map(Type::oid), buf)
+                .map_err(Error::parse)?;
+            frontend::describe(b'S', &name, buf).map_err(Error::parse)?;
+            // the columns aren't known yet, so request all of them in the binary format
+            encode_bind("", &name, param_types, params, Some(1), buf)?;
+            frontend::execute("", 0, buf).map_err(Error::parse)?;
+            frontend::sync(buf);
+            Ok(())
+        });
+
+        PrepareAndQueryFuture::new(self.clone(), pending, name, param_types.to_vec())
     }
 
     pub fn execute<'a, I>(&self, statement: &Statement, params: I) -> ExecuteFuture
@@ -189,6 +301,35 @@ impl Client {
         QueryStream::new(self.clone(), pending, statement.clone())
     }
 
+    pub fn query_named(
+        &self,
+        statement: &Statement,
+        names: &[String],
+        params: &HashMap<&str, &dyn ToSql>,
+    ) -> QueryStream<Statement> {
+        let params = names
+            .iter()
+            .map(|name| match params.get(&**name) {
+                Some(&param) => Ok(param),
+                None => Err(Error::missing_parameter(name)),
+            })
+            .collect::<Result<Vec<_>, _>>();
+        let pending = PendingRequest(
+            params
+                .and_then(|params| self.excecute_message(statement, params))
+                .map(|m| (RequestMessages::Single(m), self.0.idle.guard())),
+        );
+        QueryStream::new(self.clone(), pending, statement.clone())
+    }
+
+    pub fn query_one<'a, I>(&self, statement: &Statement, params: I) -> QueryOneFuture
+    where
+        I: IntoIterator<Item = &'a dyn ToSql>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        QueryOneFuture::new(self.query(statement, params))
+    }
+
     pub fn bind<'a, I>(&self, statement: &Statement, name: String, params: I) -> BindFuture
     where
         I: IntoIterator<Item = &'a dyn ToSql>,
@@ -254,6 +395,55 @@ impl Client {
         CopyOutStream::new(self.clone(), pending, statement.clone())
     }
 
+    pub fn query_field<'a, I>(&self, statement: &Statement, params: I) -> FieldStream
+    where
+        I: IntoIterator<Item = &'a dyn ToSql>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        let pending = if statement.columns().len() == 1 {
+            PendingRequest(
+                self.excecute_message(statement, params)
+                    .map(|m| (RequestMessages::Single(m), self.0.idle.guard())),
+            )
+        } else {
+            PendingRequest(Err(Error::column()))
+        };
+        FieldStream::new(self.clone(), pending, statement.clone())
+    }
+
+    pub fn copy_both_simple(&self, query: &str) -> CopyBothDuplex {
+        let (mut sender, receiver) = mpsc::channel(1);
+        let mut buf = vec![];
+        let pending = PendingRequest(frontend::query(query, &mut buf).map_err(Error::encode).map(
+            |()| {
+                let message = CopyMessage::Message(FrontendMessage::Raw(buf));
+                match sender.start_send(message) {
+                    Ok(AsyncSink::Ready) => {}
+                    _ => unreachable!("channel should have capacity"),
+                }
+                (
+                    RequestMessages::CopyIn {
+                        receiver: CopyInReceiver::copy_both(receiver),
+                        pending_message: None,
+                    },
+                    self.0.idle.guard(),
+                )
+            },
+        ));
+        CopyBothDuplex::new(self.clone(), pending, sender)
+    }
+
+    pub fn close_prepared_statement(&self, statement: &Statement) -> CloseFuture {
+        statement.set_closed();
+        let pending = self.pending(|buf| {
+            frontend::close(b'S', statement.name(), buf).map_err(Error::encode)?;
+            frontend::sync(buf);
+            Ok(())
+        });
+
+        CloseFuture::new(self.clone(), pending)
+    }
+
     pub fn close_statement(&self, name: &str) {
         self.close(b'S', name)
     }
@@ -298,7 +488,9 @@ impl Client {
         let _ = self.0.sender.unbounded_send(Request {
             messages: RequestMessages::Single(FrontendMessage::Raw(buf)),
             sender,
+            sent: Arc::new(AtomicBool::new(false)),
             idle: None,
+            stream_fields: false,
         });
     }
 
@@ -312,38 +504,23 @@ impl Client {
         I: IntoIterator<Item = &'a dyn ToSql>,
         I::IntoIter: ExactSizeIterator,
     {
-        let params = params.into_iter();
-
-        assert!(
-            statement.params().len() == params.len(),
-            "expected {} parameters but got {}",
-            statement.params().len(),
-            params.len()
-        );
+        if statement.deallocations() != self.deallocations() {
+            return Err(Error::deallocated());
+        }
 
         let mut buf = vec![];
-        let mut error_idx = 0;
-        let r = frontend::bind(
+        encode_bind(
             name,
             statement.name(),
-            Some(1),
-            params.zip(statement.params()).enumerate(),
-            |(idx, (param, ty)), buf| match param.to_sql_checked(ty, buf) {
-                Ok(IsNull::No) => Ok(postgres_protocol::IsNull::No),
-                Ok(IsNull::Yes) => Ok(postgres_protocol::IsNull::Yes),
-                Err(e) => {
-                    error_idx = idx;
-                    Err(e)
-                }
-            },
-            Some(1),
+            statement.params(),
+            params,
+            statement.columns().iter().map(|c| match c.format() {
+                Format::Text => 0,
+                Format::Binary => 1,
+            }),
             &mut buf,
-        );
-        match r {
-            Ok(()) => Ok(buf),
-            Err(frontend::BindError::Conversion(e)) => Err(Error::to_sql(e, error_idx)),
-            Err(frontend::BindError::Serialization(e)) => Err(Error::encode(e)),
-        }
+        )?;
+        Ok(buf)
     }
 
     fn excecute_message<'a, I>(
@@ -374,3 +551,52 @@ impl Client {
         }))
     }
 }
+
+fn encode_bind<'a, I, J>(
+    portal: &str,
+    statement: &str,
+    param_types: &[Type],
+    params: I,
+    result_formats: J,
+    buf: &mut Vec<u8>,
+) -> Result<(), Error>
+where
+    I: IntoIterator<Item = &'a dyn ToSql>,
+    I::IntoIter: ExactSizeIterator,
+    J: IntoIterator<Item = i16>,
+{
+    let params = params.into_iter().collect::<Vec<_>>();
+
+    if param_types.len() != params.len() {
+        return Err(Error::wrong_parameter_count(
+            param_types.len(),
+            params.len(),
+        ));
+    }
+
+    let mut error_idx = 0;
+    let r = frontend::bind(
+        portal,
+        statement,
+        params.iter().map(|p| match p.encode_format() {
+            Format::Text => 0,
+            Format::Binary => 1,
+        }),
+        params.iter().zip(param_types).enumerate(),
+        |(idx, (param, ty)), buf| match param.to_sql_checked(ty, buf) {
+            Ok(IsNull::No) => Ok(postgres_protocol::IsNull::No),
+            Ok(IsNull::Yes) => Ok(postgres_protocol::IsNull::Yes),
+            Err(e) => {
+                error_idx = idx;
+                Err(e)
+            }
+        },
+        result_formats,
+        buf,
+    );
+    match r {
+        Ok(()) => Ok(()),
+        Err(frontend::BindError::Conversion(e)) => Err(Error::to_sql(e, error_idx)),
+        Err(frontend::BindError::Serialization(e)) => Err(Error::encode(e)),
+    }
+}
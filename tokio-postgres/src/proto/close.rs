@@ -0,0 +1,50 @@
+use futures::{try_ready, Poll, Stream};
+use postgres_protocol::message::backend::Message;
+use state_machine_future::{transition, RentToOwn, StateMachineFuture};
+
+use crate::proto::client::{Client, PendingRequest};
+use crate::proto::responses::Responses;
+use crate::Error;
+
+#[derive(StateMachineFuture)]
+pub enum Close {
+    #[state_machine_future(start, transitions(ReadCloseComplete))]
+    Start {
+        client: Client,
+        request: PendingRequest,
+    },
+    #[state_machine_future(transitions(Finished))]
+    ReadCloseComplete { receiver: Responses },
+    #[state_machine_future(ready)]
+    Finished(()),
+    #[state_machine_future(error)]
+    Failed(Error),
+}
+
+impl PollClose for Close {
+    fn poll_start<'a>(state: &'a mut RentToOwn<'a, Start>) -> Poll<AfterStart, Error> {
+        let state = state.take();
+        let receiver = state.client.send(state.request)?;
+
+        transition!(ReadCloseComplete { receiver })
+    }
+
+    fn poll_read_close_complete<'a>(
+        state: &'a mut RentToOwn<'a, ReadCloseComplete>,
+    ) -> Poll<AfterReadCloseComplete, Error> {
+        let message = try_ready!(state.receiver.poll());
+
+        match message {
+            Some(Message::CloseComplete) => transition!(Finished(())),
+            Some(Message::ErrorResponse(body)) => Err(Error::db(body)),
+            Some(m) => Err(Error::unexpected("CloseComplete", &m)),
+            None => Err(Error::closed()),
+        }
+    }
+}
+
+impl CloseFuture {
+    pub fn new(client: Client, request: PendingRequest) -> CloseFuture {
+        Close::start(client, request)
+    }
+}
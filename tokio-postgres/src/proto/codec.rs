@@ -1,10 +1,17 @@
-use bytes::{Buf, BytesMut};
+use antidote::Mutex;
+use bytes::{Buf, BufMut, BytesMut};
 use fallible_iterator::FallibleIterator;
 use postgres_protocol::message::backend;
 use postgres_protocol::message::frontend::CopyData;
+use std::cmp;
 use std::io;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio_codec::{Decoder, Encoder};
 
+use crate::MessageDirection;
+
 pub enum FrontendMessage {
     Raw(Vec<u8>),
     CopyData(CopyData<Box<dyn Buf + Send>>),
@@ -24,6 +31,17 @@ impl BackendMessages {
     pub fn empty() -> BackendMessages {
         BackendMessages(BytesMut::new())
     }
+
+    #[cfg(feature = "raw-protocol")]
+    pub fn next_raw(&mut self) -> io::Result<Option<(u8, Vec<u8>)>> {
+        let header = match backend::Header::parse(&self.0)? {
+            Some(header) => header,
+            None => return Ok(None),
+        };
+
+        let message = self.0.split_to(header.len() as usize + 1);
+        Ok(Some((header.tag(), message[5..].to_vec())))
+    }
 }
 
 impl FallibleIterator for BackendMessages {
@@ -35,7 +53,131 @@ impl FallibleIterator for BackendMessages {
     }
 }
 
-pub struct PostgresCodec;
+pub type MessageTrace = Box<dyn FnMut(MessageDirection, u8, usize) + Send>;
+
+// The transaction status reported by the most recent `ReadyForQuery`, shared with the `Client`.
+pub struct TransactionState {
+    status: AtomicUsize,
+    start: Mutex<Option<Instant>>,
+    desynchronized: AtomicBool,
+}
+
+impl TransactionState {
+    pub fn status(&self) -> u8 {
+        self.status.load(Ordering::SeqCst) as u8
+    }
+
+    pub fn is_desynchronized(&self) -> bool {
+        self.desynchronized.load(Ordering::SeqCst)
+    }
+
+    // Records that the connection was closed because the server sent a response the client did not expect.
+    pub fn set_desynchronized(&self) {
+        self.desynchronized.store(true, Ordering::SeqCst);
+    }
+
+    pub fn duration(&self) -> Option<Duration> {
+        self.start.lock().map(|start| start.elapsed())
+    }
+
+    fn update(&self, status: u8) {
+        let mut start = self.start.lock();
+        if status == b'I' {
+            *start = None;
+        } else if start.is_none() {
+            *start = Some(Instant::now());
+        }
+        self.status.store(status as usize, Ordering::SeqCst);
+    }
+}
+
+pub struct PostgresCodec {
+    max_message_size: usize,
+    trace: Option<MessageTrace>,
+    transaction: Arc<TransactionState>,
+    stream_fields: bool,
+    field_remaining: usize,
+}
+
+impl PostgresCodec {
+    pub fn new(max_message_size: usize) -> PostgresCodec {
+        PostgresCodec {
+            max_message_size,
+            trace: None,
+            transaction: Arc::new(TransactionState {
+                status: AtomicUsize::new(b'I' as usize),
+                start: Mutex::new(None),
+                desynchronized: AtomicBool::new(false),
+            }),
+            stream_fields: false,
+            field_remaining: 0,
+        }
+    }
+
+    pub fn transaction_state(&self) -> Arc<TransactionState> {
+        self.transaction.clone()
+    }
+
+    pub fn set_trace(&mut self, trace: MessageTrace) {
+        self.trace = Some(trace);
+    }
+
+    // While set, the field of each single-column `DataRow` is passed on in `CopyData` chunks as it arrives rather
+    // than being buffered into one message, so the size of the field is not limited by the maximum message size.
+    pub fn set_stream_fields(&mut self, stream_fields: bool) {
+        self.stream_fields = stream_fields;
+    }
+
+    fn decode_field_chunk(&mut self, src: &mut BytesMut) -> Option<BackendMessage> {
+        if src.is_empty() {
+            return None;
+        }
+
+        let len = cmp::min(self.field_remaining, src.len());
+        self.field_remaining -= len;
+        let mut buf = BytesMut::with_capacity(len + 5);
+        buf.put_u8(backend::COPY_DATA_TAG);
+        buf.put_i32_be(len as i32 + 4);
+        buf.extend_from_slice(&src.split_to(len));
+
+        Some(BackendMessage::Normal {
+            messages: BackendMessages(buf),
+            request_complete: false,
+        })
+    }
+
+    // Consumes the header of a single-column `DataRow`, returning the length of its field, or `None` if the header
+    // hasn't been received yet. The header of a `NULL` field is left in place so that the row is decoded as a normal
+    // `DataRow`, which is reported as `Some(None)`.
+    fn decode_field_header(&mut self, src: &mut BytesMut) -> io::Result<Option<Option<usize>>> {
+        if src.len() < 11 {
+            return Ok(None);
+        }
+
+        let message_len = i32::from_be_bytes([src[1], src[2], src[3], src[4]]);
+        let fields = i16::from_be_bytes([src[5], src[6]]);
+        let field_len = i32::from_be_bytes([src[7], src[8], src[9], src[10]]);
+        if fields == 1 && message_len == 10 && field_len == -1 {
+            return Ok(Some(None));
+        }
+        if fields != 1 || field_len < 0 || message_len != field_len + 10 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "invalid DataRow for a streamed field",
+            ));
+        }
+
+        if let Some(trace) = &mut self.trace {
+            trace(
+                MessageDirection::Backend,
+                backend::DATA_ROW_TAG,
+                message_len as usize + 1,
+            );
+        }
+        src.advance(11);
+        Ok(Some(Some(field_len as usize)))
+    }
+}
 
 impl Encoder for PostgresCodec {
     type Item = FrontendMessage;
@@ -43,8 +185,25 @@ impl Encoder for PostgresCodec {
 
     fn encode(&mut self, item: FrontendMessage, dst: &mut BytesMut) -> Result<(), io::Error> {
         match item {
-            FrontendMessage::Raw(buf) => dst.extend_from_slice(&buf),
-            FrontendMessage::CopyData(data) => data.write(dst),
+            FrontendMessage::Raw(buf) => {
+                if let Some(trace) = &mut self.trace {
+                    // a single request can contain several messages
+                    let mut idx = 0;
+                    while let Some(header) = backend::Header::parse(&buf[idx..])? {
+                        let len = header.len() as usize + 1;
+                        trace(MessageDirection::Frontend, header.tag(), len);
+                        idx += len;
+                    }
+                }
+                dst.extend_from_slice(&buf);
+            }
+            FrontendMessage::CopyData(data) => {
+                let start = dst.len();
+                data.write(dst);
+                if let Some(trace) = &mut self.trace {
+                    trace(MessageDirection::Frontend, b'd', dst.len() - start);
+                }
+            }
         }
 
         Ok(())
@@ -56,11 +215,38 @@ impl Decoder for PostgresCodec {
     type Error = io::Error;
 
     fn decode(&mut self, src: &mut BytesMut) -> Result<Option<BackendMessage>, io::Error> {
+        if self.field_remaining > 0 {
+            return Ok(self.decode_field_chunk(src));
+        }
+
         let mut idx = 0;
         let mut request_complete = false;
 
         while let Some(header) = backend::Header::parse(&src[idx..])? {
             let len = header.len() as usize + 1;
+            if self.stream_fields && header.tag() == backend::DATA_ROW_TAG {
+                if idx > 0 {
+                    break;
+                }
+                match self.decode_field_header(src)? {
+                    Some(Some(0)) => continue,
+                    Some(Some(field_len)) => {
+                        self.field_remaining = field_len;
+                        return Ok(self.decode_field_chunk(src));
+                    }
+                    Some(None) => {}
+                    None => return Ok(None),
+                }
+            }
+            if len > self.max_message_size {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!(
+                        "message of {} bytes exceeds the maximum size of {} bytes",
+                        len, self.max_message_size
+                    ),
+                ));
+            }
             if src[idx..].len() < len {
                 break;
             }
@@ -70,6 +256,9 @@ impl Decoder for PostgresCodec {
                 | backend::NOTIFICATION_RESPONSE_TAG
                 | backend::PARAMETER_STATUS_TAG => {
                     if idx == 0 {
+                        if let Some(trace) = &mut self.trace {
+                            trace(MessageDirection::Backend, header.tag(), len);
+                        }
                         let message = backend::Message::parse(src)?.unwrap();
                         return Ok(Some(BackendMessage::Async(message)));
                     } else {
@@ -79,9 +268,14 @@ impl Decoder for PostgresCodec {
                 _ => {}
             }
 
+            if let Some(trace) = &mut self.trace {
+                trace(MessageDirection::Backend, header.tag(), len);
+            }
             idx += len;
 
             if header.tag() == backend::READY_FOR_QUERY_TAG {
+                let status = src[idx - 1];
+                self.transaction.update(status);
                 request_complete = true;
                 break;
             }
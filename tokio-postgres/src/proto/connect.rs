@@ -1,7 +1,7 @@
 use futures::{Async, Future, Poll};
 use state_machine_future::{transition, RentToOwn, StateMachineFuture};
 
-use crate::config::Host;
+use crate::config::{Host, SslMode};
 use crate::proto::{Client, ConnectOnceFuture, Connection, MaybeTlsStream};
 use crate::{Config, Error, MakeTlsConnect, Socket};
 
@@ -21,6 +21,8 @@ where
         idx: usize,
         tls: T,
         config: Config,
+        tls_fallback: bool,
+        errors: Vec<(String, Error)>,
     },
     #[state_machine_future(ready)]
     Finished((Client, Connection<MaybeTlsStream<Socket, T::Stream>>)),
@@ -45,22 +47,19 @@ where
             return Err(Error::config("invalid number of ports".into()));
         }
 
-        let hostname = match &config.0.host[0] {
-            Host::Tcp(host) => &**host,
-            // postgres doesn't support TLS over unix sockets, so the choice here doesn't matter
-            #[cfg(unix)]
-            Host::Unix(_) => "",
-        };
-        let tls = state
-            .tls
-            .make_tls_connect(hostname)
-            .map_err(|e| Error::tls(e.into()))?;
+        if config.0.proxy_password.is_some() && config.0.proxy_user.is_none() {
+            return Err(Error::config("proxy user missing".into()));
+        }
+
+        let tls = make_tls_connect(&mut state.tls, &config.0.host[0])?;
 
         transition!(Connecting {
             future: ConnectOnceFuture::new(0, tls, config.clone()),
             idx: 0,
             tls: state.tls,
             config,
+            tls_fallback: false,
+            errors: vec![],
         })
     }
 
@@ -73,23 +72,41 @@ where
                 Ok(Async::NotReady) => return Ok(Async::NotReady),
                 Err(e) => {
                     let state = &mut **state;
+
+                    // with sslmode=allow, retry the same host over TLS if it rejected the unencrypted session
+                    if state.config.0.ssl_mode == SslMode::Allow
+                        && !state.tls_fallback
+                        && e.code().is_some()
+                    {
+                        state
+                            .errors
+                            .push((describe_host(&state.config, state.idx), e));
+                        let mut config = state.config.clone();
+                        config.ssl_mode(SslMode::Require);
+                        let tls = make_tls_connect(&mut state.tls, &config.0.host[state.idx])?;
+                        state.future = ConnectOnceFuture::new(state.idx, tls, config);
+                        state.tls_fallback = true;
+                        continue;
+                    }
+                    state.tls_fallback = false;
+
+                    state
+                        .errors
+                        .push((describe_host(&state.config, state.idx), e));
                     state.idx += 1;
 
                     let host = match state.config.0.host.get(state.idx) {
                         Some(host) => host,
-                        None => return Err(e),
+                        None => {
+                            let mut errors = state.errors.split_off(0);
+                            if errors.len() == 1 {
+                                return Err(errors.pop().unwrap().1);
+                            }
+                            return Err(Error::connect_attempts(errors));
+                        }
                     };
 
-                    let hostname = match host {
-                        Host::Tcp(host) => &**host,
-                        #[cfg(unix)]
-                        Host::Unix(_) => "",
-                    };
-                    let tls = state
-                        .tls
-                        .make_tls_connect(hostname)
-                        .map_err(|e| Error::tls(e.into()))?;
-
+                    let tls = make_tls_connect(&mut state.tls, host)?;
                     state.future = ConnectOnceFuture::new(state.idx, tls, state.config.clone());
                 }
             }
@@ -105,3 +122,32 @@ where
         Connect::start(tls, config)
     }
 }
+
+fn make_tls_connect<T>(tls: &mut T, host: &Host) -> Result<T::TlsConnect, Error>
+where
+    T: MakeTlsConnect<Socket>,
+{
+    let hostname = match host {
+        Host::Tcp(host) => &**host,
+        // postgres doesn't support TLS over unix sockets, so the choice here doesn't matter
+        #[cfg(unix)]
+        Host::Unix(_) => "",
+    };
+    tls.make_tls_connect(hostname)
+        .map_err(|e| Error::tls(e.into()))
+}
+
+fn describe_host(config: &Config, idx: usize) -> String {
+    let port = config
+        .0
+        .port
+        .get(idx)
+        .or_else(|| config.0.port.first())
+        .unwrap_or(&5432);
+
+    match &config.0.host[idx] {
+        Host::Tcp(host) => format!("{}:{}", host, port),
+        #[cfg(unix)]
+        Host::Unix(path) => format!("{}:{}", path.display(), port),
+    }
+}
@@ -1,3 +1,5 @@
+use antidote::Mutex;
+use bytes::BytesMut;
 use fallible_iterator::FallibleIterator;
 use futures::sync::mpsc;
 use futures::{sink, Async, AsyncSink};
@@ -9,13 +11,19 @@ use postgres_protocol::message::frontend;
 use state_machine_future::{transition, RentToOwn, StateMachineFuture};
 use std::collections::HashMap;
 use std::io;
-use tokio_codec::Framed;
+use std::sync::Arc;
+use tokio_codec::{Framed, FramedParts};
 use tokio_io::{AsyncRead, AsyncWrite};
 
+use crate::auth::{AuthenticationExchange, AuthenticationMethod};
+use crate::config::ReplicationMode;
 use crate::proto::codec::{BackendMessage, BackendMessages};
-use crate::proto::{Client, Connection, FrontendMessage, MaybeTlsStream, PostgresCodec, TlsFuture};
+use crate::proto::{
+    check_client_encoding, Client, Connection, FrontendMessage, MaybeTlsStream, PostgresCodec,
+    TlsFuture,
+};
 use crate::tls::ChannelBinding;
-use crate::{Config, Error, TlsConnect};
+use crate::{Config, Error, TlsConnect, Version};
 
 pub struct StartupStream<S, T> {
     inner: Framed<MaybeTlsStream<S, T>, PostgresCodec>,
@@ -85,7 +93,13 @@ where
         idx: Option<usize>,
         channel_binding: ChannelBinding,
     },
-    #[state_machine_future(transitions(ReadingInfo, SendingPassword, SendingSasl))]
+    #[state_machine_future(transitions(
+        ReadingInfo,
+        SendingPassword,
+        SendingSasl,
+        SendingAuthExchange,
+        ReadingAuthExchange
+    ))]
     ReadingAuth {
         stream: StartupStream<S, T::Stream>,
         config: Config,
@@ -112,6 +126,20 @@ where
         config: Config,
         idx: Option<usize>,
     },
+    #[state_machine_future(transitions(ReadingAuthExchange))]
+    SendingAuthExchange {
+        future: sink::Send<StartupStream<S, T::Stream>>,
+        exchange: Box<dyn AuthenticationExchange>,
+        config: Config,
+        idx: Option<usize>,
+    },
+    #[state_machine_future(transitions(SendingAuthExchange, ReadingInfo))]
+    ReadingAuthExchange {
+        stream: StartupStream<S, T::Stream>,
+        exchange: Box<dyn AuthenticationExchange>,
+        config: Config,
+        idx: Option<usize>,
+    },
     #[state_machine_future(transitions(ReadingInfo))]
     ReadingAuthCompletion {
         stream: StartupStream<S, T::Stream>,
@@ -142,7 +170,20 @@ where
         let (stream, channel_binding) = try_ready!(state.future.poll());
         let state = state.take();
 
-        let mut params = vec![("client_encoding", "UTF8"), ("timezone", "GMT")];
+        let extra_float_digits = state.config.0.extra_float_digits.map(|d| d.to_string());
+        let mut params = vec![("client_encoding", &*state.config.0.client_encoding)];
+        if let Some(timezone) = &state.config.0.timezone {
+            params.push(("timezone", &**timezone));
+        }
+        if let Some(lc_numeric) = &state.config.0.lc_numeric {
+            params.push(("lc_numeric", &**lc_numeric));
+        }
+        if let Some(lc_monetary) = &state.config.0.lc_monetary {
+            params.push(("lc_monetary", &**lc_monetary));
+        }
+        if let Some(extra_float_digits) = &extra_float_digits {
+            params.push(("extra_float_digits", &**extra_float_digits));
+        }
         if let Some(user) = &state.config.0.user {
             params.push(("user", &**user));
         }
@@ -155,11 +196,20 @@ where
         if let Some(application_name) = &state.config.0.application_name {
             params.push(("application_name", &**application_name));
         }
+        match state.config.0.replication_mode {
+            Some(ReplicationMode::Physical) => params.push(("replication", "true")),
+            Some(ReplicationMode::Logical) => params.push(("replication", "database")),
+            None => {}
+        }
 
         let mut buf = vec![];
         frontend::startup_message(params, &mut buf).map_err(Error::encode)?;
 
-        let stream = Framed::new(stream, PostgresCodec);
+        let mut parts =
+            FramedParts::new(stream, PostgresCodec::new(state.config.0.max_message_size));
+        parts.read_buf = BytesMut::with_capacity(state.config.0.read_buffer_size);
+        parts.write_buf = BytesMut::with_capacity(state.config.0.write_buffer_size);
+        let stream = Framed::from_parts(parts);
         let stream = StartupStream {
             inner: stream,
             buf: BackendMessages::empty(),
@@ -296,14 +346,50 @@ where
                     idx: state.idx,
                 })
             }
+            Some(Message::AuthenticationGss) | Some(Message::AuthenticationSspi) => {
+                let (method, name) = match message {
+                    Some(Message::AuthenticationGss) => (AuthenticationMethod::Gss, "GSSAPI"),
+                    _ => (AuthenticationMethod::Sspi, "SSPI"),
+                };
+                let authenticator = match &state.config.0.authenticator {
+                    Some(authenticator) => authenticator.0.clone(),
+                    None => {
+                        return Err(Error::authentication(
+                            format!(
+                                "{} authentication requires a configured authenticator",
+                                name
+                            )
+                            .into(),
+                        ));
+                    }
+                };
+
+                let mut exchange = authenticator.start(method).map_err(Error::authentication)?;
+                match exchange.step(None).map_err(Error::authentication)? {
+                    Some(token) => {
+                        let mut buf = vec![];
+                        frontend::gss_response(&token, &mut buf).map_err(Error::encode)?;
+                        transition!(SendingAuthExchange {
+                            future: state.stream.send(FrontendMessage::Raw(buf)),
+                            exchange,
+                            config: state.config,
+                            idx: state.idx,
+                        })
+                    }
+                    None => transition!(ReadingAuthExchange {
+                        stream: state.stream,
+                        exchange,
+                        config: state.config,
+                        idx: state.idx,
+                    }),
+                }
+            }
             Some(Message::AuthenticationKerberosV5)
-            | Some(Message::AuthenticationScmCredential)
-            | Some(Message::AuthenticationGss)
-            | Some(Message::AuthenticationSspi) => Err(Error::authentication(
+            | Some(Message::AuthenticationScmCredential) => Err(Error::authentication(
                 "unsupported authentication method".into(),
             )),
             Some(Message::ErrorResponse(body)) => Err(Error::db(body)),
-            Some(_) => Err(Error::unexpected_message()),
+            Some(m) => Err(Error::unexpected("an authentication request", &m)),
             None => Err(Error::closed()),
         }
     }
@@ -366,11 +452,65 @@ where
                 })
             }
             Some(Message::ErrorResponse(body)) => Err(Error::db(body)),
-            Some(_) => Err(Error::unexpected_message()),
+            Some(m) => Err(Error::unexpected("a SASL challenge", &m)),
             None => Err(Error::closed()),
         }
     }
 
+    fn poll_sending_auth_exchange<'a>(
+        state: &'a mut RentToOwn<'a, SendingAuthExchange<S, T>>,
+    ) -> Poll<AfterSendingAuthExchange<S, T>, Error> {
+        let stream = try_ready!(state.future.poll().map_err(Error::io));
+        let state = state.take();
+        transition!(ReadingAuthExchange {
+            stream,
+            exchange: state.exchange,
+            config: state.config,
+            idx: state.idx,
+        })
+    }
+
+    fn poll_reading_auth_exchange<'a>(
+        state: &'a mut RentToOwn<'a, ReadingAuthExchange<S, T>>,
+    ) -> Poll<AfterReadingAuthExchange<S, T>, Error> {
+        loop {
+            let message = try_ready!(state.stream.poll().map_err(Error::io));
+            match message {
+                Some(Message::AuthenticationGssContinue(body)) => {
+                    let token = state
+                        .exchange
+                        .step(Some(body.data()))
+                        .map_err(Error::authentication)?;
+                    if let Some(token) = token {
+                        let state = state.take();
+                        let mut buf = vec![];
+                        frontend::gss_response(&token, &mut buf).map_err(Error::encode)?;
+                        transition!(SendingAuthExchange {
+                            future: state.stream.send(FrontendMessage::Raw(buf)),
+                            exchange: state.exchange,
+                            config: state.config,
+                            idx: state.idx,
+                        })
+                    }
+                }
+                Some(Message::AuthenticationOk) => {
+                    let state = state.take();
+                    transition!(ReadingInfo {
+                        stream: state.stream,
+                        process_id: 0,
+                        secret_key: 0,
+                        parameters: HashMap::new(),
+                        config: state.config,
+                        idx: state.idx,
+                    })
+                }
+                Some(Message::ErrorResponse(body)) => return Err(Error::db(body)),
+                Some(m) => return Err(Error::unexpected("an authentication exchange message", &m)),
+                None => return Err(Error::closed()),
+            }
+        }
+    }
+
     fn poll_reading_auth_completion<'a>(
         state: &'a mut RentToOwn<'a, ReadingAuthCompletion<S, T>>,
     ) -> Poll<AfterReadingAuthCompletion<S, T>, Error> {
@@ -387,7 +527,7 @@ where
                 idx: state.idx,
             }),
             Some(Message::ErrorResponse(body)) => Err(Error::db(body)),
-            Some(_) => Err(Error::unexpected_message()),
+            Some(m) => Err(Error::unexpected("AuthenticationOk", &m)),
             None => Err(Error::closed()),
         }
     }
@@ -410,21 +550,49 @@ where
                 }
                 Some(Message::ReadyForQuery(_)) => {
                     let state = state.take();
+                    if let Some(actual) = state.parameters.get("client_encoding") {
+                        check_client_encoding(&state.config.0.client_encoding, actual)?;
+                    }
+                    if let Some(required) = state.config.0.min_server_version {
+                        let found = state
+                            .parameters
+                            .get("server_version")
+                            .and_then(|v| v.parse::<Version>().ok());
+                        match found {
+                            Some(found) if found >= required => {}
+                            _ => return Err(Error::server_version(found, required)),
+                        }
+                    }
+                    let client_encoding = state.config.0.client_encoding.clone();
                     let (sender, receiver) = mpsc::unbounded();
+                    let transaction = state.stream.inner.codec().transaction_state();
+                    let error = Arc::new(Mutex::new(None));
                     let client = Client::new(
                         sender,
                         state.process_id,
                         state.secret_key,
                         state.config,
                         state.idx,
+                        transaction,
+                        error.clone(),
+                    );
+                    let connection = Connection::new(
+                        state.stream.inner,
+                        state.parameters,
+                        receiver,
+                        client_encoding,
+                        error,
                     );
-                    let connection =
-                        Connection::new(state.stream.inner, state.parameters, receiver);
                     transition!(Finished((client, connection)))
                 }
                 Some(Message::ErrorResponse(body)) => return Err(Error::db(body)),
                 Some(Message::NoticeResponse(_)) => {}
-                Some(_) => return Err(Error::unexpected_message()),
+                Some(m) => {
+                    return Err(Error::unexpected(
+                        "BackendKeyData, ParameterStatus or ReadyForQuery",
+                        &m,
+                    ))
+                }
                 None => return Err(Error::closed()),
             }
         }
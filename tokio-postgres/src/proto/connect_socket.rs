@@ -12,6 +12,7 @@ use tokio_timer::Delay;
 use tokio_uds::UnixStream;
 
 use crate::config::Host;
+use crate::proto::Socks5Future;
 use crate::{Config, Error, Socket};
 
 lazy_static! {
@@ -43,13 +44,20 @@ pub enum ConnectSocket {
     ResolvingDns {
         future: CpuFuture<vec::IntoIter<SocketAddr>, io::Error>,
         config: Config,
+        idx: usize,
     },
-    #[state_machine_future(transitions(Finished))]
+    #[state_machine_future(transitions(Finished, ConnectingProxy))]
     ConnectingTcp {
         future: tokio_tcp::ConnectFuture,
         timeout: Option<Delay>,
         addrs: vec::IntoIter<SocketAddr>,
         config: Config,
+        idx: usize,
+    },
+    #[state_machine_future(transitions(Finished))]
+    ConnectingProxy {
+        future: Socks5Future,
+        timeout: Option<Delay>,
     },
     #[state_machine_future(ready)]
     Finished(Socket),
@@ -61,34 +69,34 @@ impl PollConnectSocket for ConnectSocket {
     fn poll_start<'a>(state: &'a mut RentToOwn<'a, Start>) -> Poll<AfterStart, Error> {
         let state = state.take();
 
-        let port = *state
-            .config
-            .0
-            .port
-            .get(state.idx)
-            .or_else(|| state.config.0.port.get(0))
-            .unwrap_or(&5432);
+        let port = port(&state.config, state.idx);
 
         match &state.config.0.host[state.idx] {
-            Host::Tcp(host) => match host.parse::<IpAddr>() {
-                Ok(addr) => transition!(ConnectingTcp {
-                    future: TcpStream::connect(&SocketAddr::new(addr, port)),
-                    timeout: state
-                        .config
-                        .0
-                        .connect_timeout
-                        .map(|d| Delay::new(Instant::now() + d)),
-                    addrs: vec![].into_iter(),
-                    config: state.config,
-                }),
-                Err(_) => transition!(ResolvingDns {
-                    future: DNS_POOL.spawn_fn({
-                        let host = host.clone();
-                        move || (&*host, port).to_socket_addrs()
+            Host::Tcp(host) => {
+                // with a proxy, the TCP connection is made to the proxy rather than the host
+                let (host, port) = match &state.config.0.proxy {
+                    Some((proxy_host, proxy_port)) => (proxy_host.clone(), *proxy_port),
+                    None => (host.clone(), port),
+                };
+                match host.parse::<IpAddr>() {
+                    Ok(addr) => transition!(ConnectingTcp {
+                        future: TcpStream::connect(&SocketAddr::new(addr, port)),
+                        timeout: state
+                            .config
+                            .0
+                            .connect_timeout
+                            .map(|d| Delay::new(Instant::now() + d)),
+                        addrs: vec![].into_iter(),
+                        config: state.config,
+                        idx: state.idx,
                     }),
-                    config: state.config,
-                }),
-            },
+                    Err(_) => transition!(ResolvingDns {
+                        future: DNS_POOL.spawn_fn(move || (&*host, port).to_socket_addrs()),
+                        config: state.config,
+                        idx: state.idx,
+                    }),
+                }
+            }
             #[cfg(unix)]
             Host::Unix(host) => {
                 let path = host.join(format!(".s.PGSQL.{}", port));
@@ -150,6 +158,7 @@ impl PollConnectSocket for ConnectSocket {
             addrs,
             timeout: timeout,
             config: state.config,
+            idx: state.idx,
         })
     }
 
@@ -187,10 +196,59 @@ impl PollConnectSocket for ConnectSocket {
                 .map_err(Error::connect)?;
         }
 
-        transition!(Finished(Socket::new_tcp(stream)));
+        let state = state.take();
+        let config = &state.config.0;
+        if config.proxy.is_none() {
+            transition!(Finished(Socket::new_tcp(stream)));
+        }
+
+        let host = match &config.host[state.idx] {
+            Host::Tcp(host) => host,
+            #[cfg(unix)]
+            Host::Unix(_) => unreachable!("proxies are only used for TCP hosts"),
+        };
+        let future = Socks5Future::new(
+            stream,
+            host,
+            port(&state.config, state.idx),
+            config.proxy_user.as_ref().map(|s| &**s),
+            config.proxy_password.as_ref().map(|s| &**s),
+        )?;
+
+        // the timeout covers the whole attempt, including the connection to the proxy
+        transition!(ConnectingProxy {
+            future,
+            timeout: state.timeout,
+        })
+    }
+
+    fn poll_connecting_proxy<'a>(
+        state: &'a mut RentToOwn<'a, ConnectingProxy>,
+    ) -> Poll<AfterConnectingProxy, Error> {
+        if let Some(timeout) = &mut state.timeout {
+            match timeout.poll() {
+                Ok(Async::Ready(())) => {
+                    return Err(Error::connect(io::Error::from(io::ErrorKind::TimedOut)));
+                }
+                Ok(Async::NotReady) => {}
+                Err(e) => return Err(Error::connect(io::Error::new(io::ErrorKind::Other, e))),
+            }
+        }
+        let stream = try_ready!(state.future.poll());
+
+        transition!(Finished(Socket::new_tcp(stream)))
     }
 }
 
+fn port(config: &Config, idx: usize) -> u16 {
+    *config
+        .0
+        .port
+        .get(idx)
+        .or_else(|| config.0.port.get(0))
+        .unwrap_or(&5432)
+}
+
 impl ConnectSocketFuture {
     pub fn new(config: Config, idx: usize) -> ConnectSocketFuture {
         ConnectSocket::start(config, idx)
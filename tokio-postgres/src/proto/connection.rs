@@ -1,3 +1,4 @@
+use antidote::Mutex;
 use fallible_iterator::FallibleIterator;
 use futures::sync::mpsc;
 use futures::{try_ready, Async, AsyncSink, Future, Poll, Sink, Stream};
@@ -6,10 +7,14 @@ use postgres_protocol::message::backend::Message;
 use postgres_protocol::message::frontend;
 use std::collections::{HashMap, VecDeque};
 use std::io;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 use tokio_codec::Framed;
 use tokio_io::{AsyncRead, AsyncWrite};
 
-use crate::proto::codec::{BackendMessage, BackendMessages, FrontendMessage, PostgresCodec};
+use crate::proto::codec::{
+    BackendMessage, BackendMessages, FrontendMessage, MessageTrace, PostgresCodec,
+};
 use crate::proto::copy_in::CopyInReceiver;
 use crate::proto::idle::IdleGuard;
 use crate::{AsyncMessage, Notification};
@@ -26,12 +31,15 @@ pub enum RequestMessages {
 pub struct Request {
     pub messages: RequestMessages,
     pub sender: mpsc::Sender<BackendMessages>,
+    pub sent: Arc<AtomicBool>,
     pub idle: Option<IdleGuard>,
+    pub stream_fields: bool,
 }
 
 struct Response {
     sender: mpsc::Sender<BackendMessages>,
     _idle: Option<IdleGuard>,
+    stream_fields: bool,
 }
 
 #[derive(PartialEq, Debug)]
@@ -44,11 +52,13 @@ enum State {
 pub struct Connection<S> {
     stream: Framed<S, PostgresCodec>,
     parameters: HashMap<String, String>,
+    client_encoding: String,
     receiver: mpsc::UnboundedReceiver<Request>,
     pending_request: Option<RequestMessages>,
     pending_response: Option<BackendMessage>,
     responses: VecDeque<Response>,
     state: State,
+    error: Arc<Mutex<Option<DbError>>>,
 }
 
 impl<S> Connection<S>
@@ -59,15 +69,19 @@ where
         stream: Framed<S, PostgresCodec>,
         parameters: HashMap<String, String>,
         receiver: mpsc::UnboundedReceiver<Request>,
+        client_encoding: String,
+        error: Arc<Mutex<Option<DbError>>>,
     ) -> Connection<S> {
         Connection {
             stream,
             parameters,
+            client_encoding,
             receiver,
             pending_request: None,
             pending_response: None,
             responses: VecDeque::new(),
             state: State::Active,
+            error,
         }
     }
 
@@ -75,12 +89,22 @@ where
         self.parameters.get(name).map(|s| &**s)
     }
 
+    pub fn set_message_trace(&mut self, trace: MessageTrace) {
+        self.stream.codec_mut().set_trace(trace);
+    }
+
     fn poll_response(&mut self) -> Poll<Option<BackendMessage>, io::Error> {
         if let Some(message) = self.pending_response.take() {
             trace!("retrying pending response");
             return Ok(Async::Ready(Some(message)));
         }
 
+        // everything decoded from here on belongs to the oldest outstanding request
+        let stream_fields = match self.responses.front() {
+            Some(response) => response.stream_fields,
+            None => false,
+        };
+        self.stream.codec_mut().set_stream_fields(stream_fields);
         self.stream.poll()
     }
 
@@ -116,10 +140,12 @@ where
                     return Ok(Some(AsyncMessage::Notification(notification)));
                 }
                 BackendMessage::Async(Message::ParameterStatus(body)) => {
-                    self.parameters.insert(
-                        body.name().map_err(Error::parse)?.to_string(),
-                        body.value().map_err(Error::parse)?.to_string(),
-                    );
+                    let name = body.name().map_err(Error::parse)?;
+                    let value = body.value().map_err(Error::parse)?;
+                    if name == "client_encoding" {
+                        check_client_encoding(&self.client_encoding, value)?;
+                    }
+                    self.parameters.insert(name.to_string(), value.to_string());
                     continue;
                 }
                 BackendMessage::Async(_) => unreachable!(),
@@ -132,8 +158,21 @@ where
             let mut response = match self.responses.pop_front() {
                 Some(response) => response,
                 None => match messages.next().map_err(Error::parse)? {
-                    Some(Message::ErrorResponse(error)) => return Err(Error::db(error)),
-                    _ => return Err(Error::unexpected_message()),
+                    Some(Message::ErrorResponse(error)) => {
+                        // the server is closing the connection, so make the reason available to the client
+                        if let Ok(e) = DbError::parse(&mut error.fields()) {
+                            *self.error.lock() = Some(e);
+                        }
+                        return Err(Error::db(error));
+                    }
+                    Some(m) => {
+                        self.stream.codec().transaction_state().set_desynchronized();
+                        return Err(Error::unexpected("a response to a request", &m));
+                    }
+                    None => {
+                        self.stream.codec().transaction_state().set_desynchronized();
+                        return Err(Error::unexpected_message());
+                    }
                 },
             };
 
@@ -167,9 +206,12 @@ where
         match self.receiver.poll() {
             Ok(Async::Ready(Some(request))) => {
                 trace!("polled new request");
+                // from here on the request may have reached the server
+                request.sent.store(true, Ordering::SeqCst);
                 self.responses.push_back(Response {
                     sender: request.sender,
                     _idle: request.idle,
+                    stream_fields: request.stream_fields,
                 });
                 Ok(Async::Ready(Some(request.messages)))
             }
@@ -319,3 +361,36 @@ where
         Ok(Async::Ready(()))
     }
 }
+
+/// Checks that the `client_encoding` reported by the server matches the one the connection was configured with.
+///
+/// Text is decoded assuming the configured encoding, so a mismatch, such as one caused by a `SET client_encoding`
+/// command, would otherwise surface later as an opaque UTF-8 decoding error.
+pub fn check_client_encoding(expected: &str, actual: &str) -> Result<(), Error> {
+    if normalize_encoding(expected) == normalize_encoding(actual) {
+        return Ok(());
+    }
+
+    Err(Error::encoding(
+        format!(
+            "the server's client_encoding is {} but the connection was configured for {}; \
+             use `Config::client_encoding` rather than changing it with `SET`",
+            actual, expected
+        )
+        .into(),
+    ))
+}
+
+// mirrors the server's lenient matching of encoding names, where case and punctuation are ignored
+fn normalize_encoding(name: &str) -> String {
+    let name = name
+        .chars()
+        .filter(char::is_ascii_alphanumeric)
+        .map(|c| c.to_ascii_lowercase())
+        .collect::<String>();
+    if name == "unicode" {
+        "utf8".to_string()
+    } else {
+        name
+    }
+}
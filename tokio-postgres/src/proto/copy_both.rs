@@ -0,0 +1,181 @@
+use bytes::{Buf, Bytes, IntoBuf};
+use futures::sync::mpsc;
+use futures::{try_ready, Async, AsyncSink, Poll, Sink, StartSend, Stream};
+use postgres_protocol::message::backend::Message;
+use postgres_protocol::message::frontend::CopyData;
+use std::mem;
+
+use crate::proto::client::{Client, PendingRequest};
+use crate::proto::codec::FrontendMessage;
+use crate::proto::copy_in::CopyMessage;
+use crate::proto::responses::Responses;
+use crate::Error;
+
+enum State {
+    Start {
+        client: Client,
+        request: PendingRequest,
+    },
+    ReadingCopyBothResponse {
+        receiver: Responses,
+    },
+    ReadingCopyData {
+        receiver: Responses,
+    },
+    Done,
+}
+
+pub struct CopyBothDuplex {
+    state: State,
+    sender: mpsc::Sender<CopyMessage>,
+    pending_message: Option<CopyMessage>,
+    done_sent: bool,
+}
+
+impl CopyBothDuplex {
+    pub fn new(
+        client: Client,
+        request: PendingRequest,
+        sender: mpsc::Sender<CopyMessage>,
+    ) -> CopyBothDuplex {
+        CopyBothDuplex {
+            state: State::Start { client, request },
+            sender,
+            pending_message: None,
+            done_sent: false,
+        }
+    }
+
+    fn start(&mut self) -> Result<(), Error> {
+        if let State::Start { .. } = self.state {
+            match mem::replace(&mut self.state, State::Done) {
+                State::Start { client, request } => {
+                    let receiver = client.send(request)?;
+                    self.state = State::ReadingCopyBothResponse { receiver };
+                }
+                _ => unreachable!(),
+            }
+        }
+
+        Ok(())
+    }
+
+    fn poll_pending(&mut self) -> Poll<(), Error> {
+        if let Some(message) = self.pending_message.take() {
+            match self
+                .sender
+                .start_send(message)
+                .map_err(|_| Error::closed())?
+            {
+                AsyncSink::Ready => {}
+                AsyncSink::NotReady(message) => {
+                    self.pending_message = Some(message);
+                    return Ok(Async::NotReady);
+                }
+            }
+        }
+
+        Ok(Async::Ready(()))
+    }
+}
+
+impl Stream for CopyBothDuplex {
+    type Item = Bytes;
+    type Error = Error;
+
+    fn poll(&mut self) -> Poll<Option<Bytes>, Error> {
+        self.start()?;
+
+        loop {
+            match mem::replace(&mut self.state, State::Done) {
+                State::Start { .. } => unreachable!(),
+                State::ReadingCopyBothResponse { mut receiver } => {
+                    let message = match receiver.poll() {
+                        Ok(Async::Ready(message)) => message,
+                        Ok(Async::NotReady) => {
+                            self.state = State::ReadingCopyBothResponse { receiver };
+                            break Ok(Async::NotReady);
+                        }
+                        Err(e) => return Err(e),
+                    };
+
+                    match message {
+                        Some(Message::CopyBothResponse(_)) => {
+                            self.state = State::ReadingCopyData { receiver };
+                        }
+                        Some(Message::ErrorResponse(body)) => break Err(Error::db(body)),
+                        Some(m) => break Err(Error::unexpected("CopyBothResponse", &m)),
+                        None => break Err(Error::closed()),
+                    }
+                }
+                State::ReadingCopyData { mut receiver } => {
+                    let message = match receiver.poll() {
+                        Ok(Async::Ready(message)) => message,
+                        Ok(Async::NotReady) => {
+                            self.state = State::ReadingCopyData { receiver };
+                            break Ok(Async::NotReady);
+                        }
+                        Err(e) => return Err(e),
+                    };
+
+                    match message {
+                        Some(Message::CopyData(body)) => {
+                            self.state = State::ReadingCopyData { receiver };
+                            break Ok(Async::Ready(Some(body.into_bytes())));
+                        }
+                        // the server can follow the end of the stream with the results of the command
+                        Some(Message::CopyDone)
+                        | Some(Message::RowDescription(_))
+                        | Some(Message::DataRow(_))
+                        | Some(Message::CommandComplete(_)) => {
+                            self.state = State::ReadingCopyData { receiver };
+                        }
+                        Some(Message::ReadyForQuery(_)) => break Ok(Async::Ready(None)),
+                        Some(Message::ErrorResponse(body)) => break Err(Error::db(body)),
+                        Some(m) => {
+                            break Err(Error::unexpected("CopyData or the end of the copy", &m))
+                        }
+                        None => break Err(Error::closed()),
+                    }
+                }
+                State::Done => break Ok(Async::Ready(None)),
+            }
+        }
+    }
+}
+
+impl Sink for CopyBothDuplex {
+    type SinkItem = Bytes;
+    type SinkError = Error;
+
+    fn start_send(&mut self, item: Bytes) -> StartSend<Bytes, Error> {
+        self.start()?;
+
+        if self.poll_pending()?.is_not_ready() {
+            return Ok(AsyncSink::NotReady(item));
+        }
+
+        let buf: Box<dyn Buf + Send> = Box::new(item.into_buf());
+        let data = CopyData::new(buf).map_err(Error::encode)?;
+        self.pending_message = Some(CopyMessage::Message(FrontendMessage::CopyData(data)));
+        self.poll_pending()?;
+
+        Ok(AsyncSink::Ready)
+    }
+
+    fn poll_complete(&mut self) -> Poll<(), Error> {
+        self.start()?;
+        try_ready!(self.poll_pending());
+        self.sender.poll_complete().map_err(|_| Error::closed())
+    }
+
+    fn close(&mut self) -> Poll<(), Error> {
+        if !self.done_sent {
+            try_ready!(self.poll_complete());
+            self.pending_message = Some(CopyMessage::Done);
+            self.done_sent = true;
+        }
+
+        self.poll_complete()
+    }
+}
@@ -22,6 +22,7 @@ pub enum CopyMessage {
 pub struct CopyInReceiver {
     receiver: mpsc::Receiver<CopyMessage>,
     done: bool,
+    copy_both: bool,
 }
 
 impl CopyInReceiver {
@@ -29,6 +30,17 @@ impl CopyInReceiver {
         CopyInReceiver {
             receiver,
             done: false,
+            copy_both: false,
+        }
+    }
+
+    // copy-both mode is started by a simple query, so there's no Sync to send, and it's ended with CopyDone even if
+    // the sender hangs up
+    pub fn copy_both(receiver: mpsc::Receiver<CopyMessage>) -> CopyInReceiver {
+        CopyInReceiver {
+            receiver,
+            done: false,
+            copy_both: true,
         }
     }
 }
@@ -48,7 +60,15 @@ impl Stream for CopyInReceiver {
                 self.done = true;
                 let mut buf = vec![];
                 frontend::copy_done(&mut buf);
-                frontend::sync(&mut buf);
+                if !self.copy_both {
+                    frontend::sync(&mut buf);
+                }
+                Ok(Async::Ready(Some(FrontendMessage::Raw(buf))))
+            }
+            Async::Ready(None) if self.copy_both => {
+                self.done = true;
+                let mut buf = vec![];
+                frontend::copy_done(&mut buf);
                 Ok(Async::Ready(Some(FrontendMessage::Raw(buf))))
             }
             Async::Ready(None) => {
@@ -144,7 +164,7 @@ where
                     })
                 }
                 Some(Message::ErrorResponse(body)) => return Err(Error::db(body)),
-                Some(_) => return Err(Error::unexpected_message()),
+                Some(m) => return Err(Error::unexpected("CopyInResponse", &m)),
                 None => return Err(Error::closed()),
             }
         }
@@ -245,7 +265,7 @@ where
                 transition!(Finished(rows))
             }
             Some(Message::ErrorResponse(body)) => Err(Error::db(body)),
-            Some(_) => Err(Error::unexpected_message()),
+            Some(m) => Err(Error::unexpected("CommandComplete", &m)),
             None => Err(Error::closed()),
         }
     }
@@ -60,7 +60,7 @@ impl Stream for CopyOutStream {
                             self.0 = State::ReadingCopyData { receiver };
                         }
                         Some(Message::ErrorResponse(body)) => break Err(Error::db(body)),
-                        Some(_) => break Err(Error::unexpected_message()),
+                        Some(m) => break Err(Error::unexpected("CopyOutResponse", &m)),
                         None => break Err(Error::closed()),
                     }
                 }
@@ -84,7 +84,9 @@ impl Stream for CopyOutStream {
                         }
                         Some(Message::ReadyForQuery(_)) => break Ok(Async::Ready(None)),
                         Some(Message::ErrorResponse(body)) => break Err(Error::db(body)),
-                        Some(_) => break Err(Error::unexpected_message()),
+                        Some(m) => {
+                            break Err(Error::unexpected("CopyData or the end of the copy", &m))
+                        }
                         None => break Err(Error::closed()),
                     }
                 }
@@ -54,7 +54,7 @@ impl PollExecute for Execute {
                     transition!(Finished(rows))
                 }
                 Some(Message::EmptyQueryResponse) => transition!(Finished(0)),
-                Some(_) => return Err(Error::unexpected_message()),
+                Some(m) => return Err(Error::unexpected("CommandComplete", &m)),
                 None => return Err(Error::closed()),
             }
         }
@@ -0,0 +1,92 @@
+use bytes::Bytes;
+use futures::{Async, Poll, Stream};
+use postgres_protocol::message::backend::Message;
+use std::mem;
+
+use crate::proto::client::{Client, PendingRequest};
+use crate::proto::responses::Responses;
+use crate::proto::statement::Statement;
+use crate::types::WasNull;
+use crate::Error;
+
+enum State {
+    Start {
+        client: Client,
+        request: PendingRequest,
+        statement: Statement,
+    },
+    ReadingResponse {
+        receiver: Responses,
+        column: String,
+    },
+    Done,
+}
+
+pub struct FieldStream(State);
+
+impl Stream for FieldStream {
+    type Item = Bytes;
+    type Error = Error;
+
+    fn poll(&mut self) -> Poll<Option<Bytes>, Error> {
+        loop {
+            match mem::replace(&mut self.0, State::Done) {
+                State::Start {
+                    client,
+                    request,
+                    statement,
+                } => {
+                    let receiver = client.send_streaming_fields(request)?;
+                    let column = statement.columns()[0].name().to_string();
+                    // it's ok for the statement to close now that we've queued the query
+                    drop(statement);
+                    self.0 = State::ReadingResponse { receiver, column };
+                }
+                State::ReadingResponse {
+                    mut receiver,
+                    column,
+                } => {
+                    let message = match receiver.poll() {
+                        Ok(Async::Ready(message)) => message,
+                        Ok(Async::NotReady) => {
+                            self.0 = State::ReadingResponse { receiver, column };
+                            break Ok(Async::NotReady);
+                        }
+                        Err(e) => return Err(e),
+                    };
+
+                    match message {
+                        Some(Message::CopyData(body)) => {
+                            self.0 = State::ReadingResponse { receiver, column };
+                            break Ok(Async::Ready(Some(body.into_bytes())));
+                        }
+                        Some(Message::BindComplete)
+                        | Some(Message::CommandComplete(_))
+                        | Some(Message::EmptyQueryResponse) => {
+                            self.0 = State::ReadingResponse { receiver, column };
+                        }
+                        // fields are only passed on as whole `DataRow`s when they're `NULL`
+                        Some(Message::DataRow(_)) => {
+                            break Err(Error::from_sql(Box::new(WasNull), 0, &column))
+                        }
+                        Some(Message::ReadyForQuery(_)) => break Ok(Async::Ready(None)),
+                        Some(Message::ErrorResponse(body)) => break Err(Error::db(body)),
+                        Some(m) => break Err(Error::unexpected("a row of the query", &m)),
+                        None => break Err(Error::closed()),
+                    }
+                }
+                State::Done => break Ok(Async::Ready(None)),
+            }
+        }
+    }
+}
+
+impl FieldStream {
+    pub fn new(client: Client, request: PendingRequest, statement: Statement) -> FieldStream {
+        FieldStream(State::Start {
+            client,
+            request,
+            statement,
+        })
+    }
+}
@@ -13,6 +13,7 @@ mod bind;
 mod cancel_query;
 mod cancel_query_raw;
 mod client;
+mod close;
 mod codec;
 #[cfg(feature = "runtime")]
 mod connect;
@@ -22,16 +23,23 @@ mod connect_raw;
 #[cfg(feature = "runtime")]
 mod connect_socket;
 mod connection;
+mod copy_both;
 mod copy_in;
 mod copy_out;
 mod execute;
+mod field;
 mod idle;
 mod maybe_tls_stream;
 mod portal;
 mod prepare;
+mod prepare_and_query;
 mod query;
+#[cfg(feature = "raw-protocol")]
+mod raw;
 mod responses;
 mod simple_query;
+#[cfg(feature = "runtime")]
+mod socks5;
 mod statement;
 mod tls;
 mod transaction;
@@ -44,6 +52,7 @@ pub use crate::proto::bind::BindFuture;
 pub use crate::proto::cancel_query::CancelQueryFuture;
 pub use crate::proto::cancel_query_raw::CancelQueryRawFuture;
 pub use crate::proto::client::Client;
+pub use crate::proto::close::CloseFuture;
 pub use crate::proto::codec::{FrontendMessage, PostgresCodec};
 #[cfg(feature = "runtime")]
 pub use crate::proto::connect::ConnectFuture;
@@ -52,15 +61,22 @@ pub use crate::proto::connect_once::ConnectOnceFuture;
 pub use crate::proto::connect_raw::ConnectRawFuture;
 #[cfg(feature = "runtime")]
 pub use crate::proto::connect_socket::ConnectSocketFuture;
-pub use crate::proto::connection::Connection;
+pub use crate::proto::connection::{check_client_encoding, Connection};
+pub use crate::proto::copy_both::CopyBothDuplex;
 pub use crate::proto::copy_in::CopyInFuture;
 pub use crate::proto::copy_out::CopyOutStream;
 pub use crate::proto::execute::ExecuteFuture;
+pub use crate::proto::field::FieldStream;
 pub use crate::proto::maybe_tls_stream::MaybeTlsStream;
 pub use crate::proto::portal::Portal;
 pub use crate::proto::prepare::PrepareFuture;
-pub use crate::proto::query::QueryStream;
+pub use crate::proto::prepare_and_query::PrepareAndQueryFuture;
+pub use crate::proto::query::{QueryOneFuture, QueryStream};
+#[cfg(feature = "raw-protocol")]
+pub use crate::proto::raw::RawStream;
 pub use crate::proto::simple_query::SimpleQueryStream;
+#[cfg(feature = "runtime")]
+pub use crate::proto::socks5::Socks5Future;
 pub use crate::proto::statement::Statement;
 pub use crate::proto::tls::TlsFuture;
 pub use crate::proto::transaction::TransactionFuture;
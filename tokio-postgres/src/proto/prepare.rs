@@ -11,7 +11,7 @@ use crate::proto::client::{Client, PendingRequest};
 use crate::proto::responses::Responses;
 use crate::proto::statement::Statement;
 use crate::proto::typeinfo::TypeinfoFuture;
-use crate::types::{Oid, Type};
+use crate::types::{self, Format, Oid, Type};
 use crate::{Column, Error};
 
 #[derive(StateMachineFuture)]
@@ -21,24 +21,28 @@ pub enum Prepare {
         client: Client,
         request: PendingRequest,
         name: String,
+        result_formats: Vec<Format>,
     },
     #[state_machine_future(transitions(ReadParameterDescription))]
     ReadParseComplete {
         client: Client,
         receiver: Responses,
         name: String,
+        result_formats: Vec<Format>,
     },
     #[state_machine_future(transitions(ReadRowDescription))]
     ReadParameterDescription {
         client: Client,
         receiver: Responses,
         name: String,
+        result_formats: Vec<Format>,
     },
     #[state_machine_future(transitions(GetParameterTypes, GetColumnTypes, Finished))]
     ReadRowDescription {
         client: Client,
         receiver: Responses,
         name: String,
+        result_formats: Vec<Format>,
         parameters: Vec<Oid>,
     },
     #[state_machine_future(transitions(GetColumnTypes, Finished))]
@@ -47,13 +51,13 @@ pub enum Prepare {
         remaining_parameters: vec::IntoIter<Oid>,
         name: String,
         parameters: Vec<Type>,
-        columns: Vec<(String, Oid)>,
+        columns: Vec<(String, Oid, Oid, i16, i32, Option<Format>)>,
     },
     #[state_machine_future(transitions(Finished))]
     GetColumnTypes {
         future: TypeinfoFuture,
-        cur_column_name: String,
-        remaining_columns: vec::IntoIter<(String, Oid)>,
+        cur_column: (String, Oid, i16, i32, Option<Format>),
+        remaining_columns: vec::IntoIter<(String, Oid, Oid, i16, i32, Option<Format>)>,
         name: String,
         parameters: Vec<Type>,
         columns: Vec<Column>,
@@ -72,6 +76,7 @@ impl PollPrepare for Prepare {
         transition!(ReadParseComplete {
             receiver,
             name: state.name,
+            result_formats: state.result_formats,
             client: state.client,
         })
     }
@@ -86,10 +91,11 @@ impl PollPrepare for Prepare {
             Some(Message::ParseComplete) => transition!(ReadParameterDescription {
                 receiver: state.receiver,
                 name: state.name,
+                result_formats: state.result_formats,
                 client: state.client,
             }),
             Some(Message::ErrorResponse(body)) => Err(Error::db(body)),
-            Some(_) => Err(Error::unexpected_message()),
+            Some(m) => Err(Error::unexpected("ParseComplete", &m)),
             None => Err(Error::closed()),
         }
     }
@@ -104,10 +110,11 @@ impl PollPrepare for Prepare {
             Some(Message::ParameterDescription(body)) => transition!(ReadRowDescription {
                 receiver: state.receiver,
                 name: state.name,
+                result_formats: state.result_formats,
                 parameters: body.parameters().collect().map_err(Error::parse)?,
                 client: state.client,
             }),
-            Some(_) => Err(Error::unexpected_message()),
+            Some(m) => Err(Error::unexpected("ParameterDescription", &m)),
             None => Err(Error::closed()),
         }
     }
@@ -118,14 +125,24 @@ impl PollPrepare for Prepare {
         let message = try_ready!(state.receiver.poll());
         let state = state.take();
 
+        let mut result_formats = state.result_formats.into_iter();
         let columns = match message {
             Some(Message::RowDescription(body)) => body
                 .fields()
-                .map(|f| Ok((f.name().to_string(), f.type_oid())))
+                .map(|f| {
+                    Ok((
+                        f.name().to_string(),
+                        f.type_oid(),
+                        f.table_oid(),
+                        f.column_id(),
+                        f.type_modifier(),
+                        result_formats.next(),
+                    ))
+                })
                 .collect()
                 .map_err(Error::parse)?,
             Some(Message::NoData) => vec![],
-            Some(_) => return Err(Error::unexpected_message()),
+            Some(m) => return Err(Error::unexpected("RowDescription or NoData", &m)),
             None => return Err(Error::closed()),
         };
 
@@ -141,10 +158,10 @@ impl PollPrepare for Prepare {
         }
 
         let mut columns = columns.into_iter();
-        if let Some((name, oid)) = columns.next() {
+        if let Some((name, oid, table_oid, column_id, type_modifier, format)) = columns.next() {
             transition!(GetColumnTypes {
                 future: TypeinfoFuture::new(oid, state.client),
-                cur_column_name: name,
+                cur_column: (name, table_oid, column_id, type_modifier, format),
                 remaining_columns: columns,
                 name: state.name,
                 parameters: vec![],
@@ -153,7 +170,7 @@ impl PollPrepare for Prepare {
         }
 
         transition!(Finished(Statement::new(
-            state.client.downgrade(),
+            &state.client,
             state.name,
             vec![],
             vec![]
@@ -175,10 +192,10 @@ impl PollPrepare for Prepare {
         let state = state.take();
 
         let mut columns = state.columns.into_iter();
-        if let Some((name, oid)) = columns.next() {
+        if let Some((name, oid, table_oid, column_id, type_modifier, format)) = columns.next() {
             transition!(GetColumnTypes {
                 future: TypeinfoFuture::new(oid, client),
-                cur_column_name: name,
+                cur_column: (name, table_oid, column_id, type_modifier, format),
                 remaining_columns: columns,
                 name: state.name,
                 parameters: state.parameters,
@@ -187,7 +204,7 @@ impl PollPrepare for Prepare {
         }
 
         transition!(Finished(Statement::new(
-            client.downgrade(),
+            &client,
             state.name,
             state.parameters,
             vec![],
@@ -199,12 +216,21 @@ impl PollPrepare for Prepare {
     ) -> Poll<AfterGetColumnTypes, Error> {
         let client = loop {
             let (ty, client) = try_ready!(state.future.poll());
-            let name = mem::replace(&mut state.cur_column_name, String::new());
-            state.columns.push(Column::new(name, ty));
+            let (name, table_oid, column_id, type_modifier, format) =
+                mem::replace(&mut state.cur_column, (String::new(), 0, 0, -1, None));
+            let format = format.unwrap_or_else(|| types::result_format(&ty));
+            state.columns.push(Column::new(
+                name,
+                ty,
+                table_oid,
+                column_id,
+                type_modifier,
+                format,
+            ));
 
             match state.remaining_columns.next() {
-                Some((name, oid)) => {
-                    state.cur_column_name = name;
+                Some((name, oid, table_oid, column_id, type_modifier, format)) => {
+                    state.cur_column = (name, table_oid, column_id, type_modifier, format);
                     state.future = TypeinfoFuture::new(oid, client);
                 }
                 None => break client,
@@ -213,7 +239,7 @@ impl PollPrepare for Prepare {
         let state = state.take();
 
         transition!(Finished(Statement::new(
-            client.downgrade(),
+            &client,
             state.name,
             state.parameters,
             state.columns,
@@ -222,7 +248,12 @@ impl PollPrepare for Prepare {
 }
 
 impl PrepareFuture {
-    pub fn new(client: Client, request: PendingRequest, name: String) -> PrepareFuture {
-        Prepare::start(client, request, name)
+    pub fn new(
+        client: Client,
+        request: PendingRequest,
+        name: String,
+        result_formats: Vec<Format>,
+    ) -> PrepareFuture {
+        Prepare::start(client, request, name, result_formats)
     }
 }
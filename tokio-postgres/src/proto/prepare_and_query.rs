@@ -0,0 +1,185 @@
+#![allow(clippy::large_enum_variant)]
+
+use fallible_iterator::FallibleIterator;
+use futures::{try_ready, Async, Future, Poll, Stream};
+use postgres_protocol::message::backend::{DataRowBody, Message};
+use state_machine_future::{transition, RentToOwn, StateMachineFuture};
+use std::mem;
+use std::vec;
+
+use crate::proto::client::{Client, PendingRequest};
+use crate::proto::responses::Responses;
+use crate::proto::statement::Statement;
+use crate::proto::typeinfo::TypeinfoFuture;
+use crate::types::{Format, Oid, Type};
+use crate::{Column, Error, Row};
+
+// The statement is parsed, described, bound and executed in a single request. Since the result columns aren't known
+// when the request is sent, every column is requested in the binary format, and the whole response is read before
+// looking up the columns' types so that the lookups aren't queued behind unread rows.
+#[derive(StateMachineFuture)]
+pub enum PrepareAndQuery {
+    #[state_machine_future(start, transitions(ReadResponse))]
+    Start {
+        client: Client,
+        request: PendingRequest,
+        name: String,
+        param_types: Vec<Type>,
+    },
+    #[state_machine_future(transitions(GetColumnTypes, Finished))]
+    ReadResponse {
+        client: Client,
+        receiver: Responses,
+        name: String,
+        param_types: Vec<Type>,
+        parsed: bool,
+        columns: Vec<(String, Oid, Oid, i16, i32)>,
+        rows: Vec<DataRowBody>,
+    },
+    #[state_machine_future(transitions(Finished))]
+    GetColumnTypes {
+        client: Client,
+        future: TypeinfoFuture,
+        cur_column: (String, Oid, i16, i32),
+        remaining_columns: vec::IntoIter<(String, Oid, Oid, i16, i32)>,
+        name: String,
+        param_types: Vec<Type>,
+        columns: Vec<Column>,
+        rows: Vec<DataRowBody>,
+    },
+    #[state_machine_future(ready)]
+    Finished((Statement, Vec<Row>)),
+    #[state_machine_future(error)]
+    Failed(Error),
+}
+
+impl PollPrepareAndQuery for PrepareAndQuery {
+    fn poll_start<'a>(state: &'a mut RentToOwn<'a, Start>) -> Poll<AfterStart, Error> {
+        let state = state.take();
+        let receiver = state.client.send(state.request)?;
+
+        transition!(ReadResponse {
+            receiver,
+            name: state.name,
+            param_types: state.param_types,
+            parsed: false,
+            columns: vec![],
+            rows: vec![],
+            client: state.client,
+        })
+    }
+
+    fn poll_read_response<'a>(
+        state: &'a mut RentToOwn<'a, ReadResponse>,
+    ) -> Poll<AfterReadResponse, Error> {
+        loop {
+            let message = try_ready!(state.receiver.poll());
+
+            match message {
+                Some(Message::ParseComplete) => state.parsed = true,
+                Some(Message::ParameterDescription(_))
+                | Some(Message::NoData)
+                | Some(Message::BindComplete) => {}
+                Some(Message::RowDescription(body)) => {
+                    state.columns = body
+                        .fields()
+                        .map(|f| {
+                            Ok((
+                                f.name().to_string(),
+                                f.type_oid(),
+                                f.table_oid(),
+                                f.column_id(),
+                                f.type_modifier(),
+                            ))
+                        })
+                        .collect()
+                        .map_err(Error::parse)?;
+                }
+                Some(Message::DataRow(body)) => state.rows.push(body),
+                Some(Message::CommandComplete(_)) | Some(Message::EmptyQueryResponse) => break,
+                Some(Message::ErrorResponse(body)) => {
+                    if state.parsed {
+                        state.client.close_statement(&state.name);
+                    }
+                    return Err(Error::db(body));
+                }
+                Some(m) => return Err(Error::unexpected("a query response", &m)),
+                None => return Err(Error::closed()),
+            }
+        }
+        let state = state.take();
+
+        let mut columns = state.columns.into_iter();
+        if let Some((name, oid, table_oid, column_id, type_modifier)) = columns.next() {
+            transition!(GetColumnTypes {
+                future: TypeinfoFuture::new(oid, state.client.clone()),
+                client: state.client,
+                cur_column: (name, table_oid, column_id, type_modifier),
+                remaining_columns: columns,
+                name: state.name,
+                param_types: state.param_types,
+                columns: vec![],
+                rows: state.rows,
+            });
+        }
+
+        let statement = Statement::new(&state.client, state.name, state.param_types, vec![]);
+        let rows = finish_rows(&statement, state.rows)?;
+        transition!(Finished((statement, rows)))
+    }
+
+    fn poll_get_column_types<'a>(
+        state: &'a mut RentToOwn<'a, GetColumnTypes>,
+    ) -> Poll<AfterGetColumnTypes, Error> {
+        loop {
+            let ty = match state.future.poll() {
+                Ok(Async::Ready((ty, _))) => ty,
+                Ok(Async::NotReady) => return Ok(Async::NotReady),
+                Err(e) => {
+                    state.client.close_statement(&state.name);
+                    return Err(e);
+                }
+            };
+            let (name, table_oid, column_id, type_modifier) =
+                mem::replace(&mut state.cur_column, (String::new(), 0, 0, -1));
+            state.columns.push(Column::new(
+                name,
+                ty,
+                table_oid,
+                column_id,
+                type_modifier,
+                Format::Binary,
+            ));
+
+            match state.remaining_columns.next() {
+                Some((name, oid, table_oid, column_id, type_modifier)) => {
+                    state.cur_column = (name, table_oid, column_id, type_modifier);
+                    state.future = TypeinfoFuture::new(oid, state.client.clone());
+                }
+                None => break,
+            }
+        }
+        let state = state.take();
+
+        let statement = Statement::new(&state.client, state.name, state.param_types, state.columns);
+        let rows = finish_rows(&statement, state.rows)?;
+        transition!(Finished((statement, rows)))
+    }
+}
+
+fn finish_rows(statement: &Statement, rows: Vec<DataRowBody>) -> Result<Vec<Row>, Error> {
+    rows.into_iter()
+        .map(|body| Row::new(statement.clone(), body))
+        .collect()
+}
+
+impl PrepareAndQueryFuture {
+    pub fn new(
+        client: Client,
+        request: PendingRequest,
+        name: String,
+        param_types: Vec<Type>,
+    ) -> PrepareAndQueryFuture {
+        PrepareAndQuery::start(client, request, name, param_types)
+    }
+}
@@ -1,4 +1,4 @@
-use futures::{Async, Poll, Stream};
+use futures::{try_ready, Async, Future, Poll, Stream};
 use postgres_protocol::message::backend::Message;
 use std::mem;
 
@@ -97,7 +97,7 @@ where
                         | Some(Message::CommandComplete(_)) => {
                             break Ok(Async::Ready(None));
                         }
-                        Some(_) => break Err(Error::unexpected_message()),
+                        Some(m) => break Err(Error::unexpected("a query response", &m)),
                         None => break Err(Error::closed()),
                     }
                 }
@@ -119,3 +119,36 @@ where
         })
     }
 }
+
+pub struct QueryOneFuture {
+    stream: QueryStream<Statement>,
+    row: Option<Row>,
+}
+
+impl Future for QueryOneFuture {
+    type Item = Row;
+    type Error = Error;
+
+    fn poll(&mut self) -> Poll<Row, Error> {
+        loop {
+            match try_ready!(self.stream.poll()) {
+                Some(row) => {
+                    if self.row.is_some() {
+                        return Err(Error::row_count());
+                    }
+                    self.row = Some(row);
+                }
+                None => match self.row.take() {
+                    Some(row) => return Ok(Async::Ready(row)),
+                    None => return Err(Error::row_count()),
+                },
+            }
+        }
+    }
+}
+
+impl QueryOneFuture {
+    pub fn new(stream: QueryStream<Statement>) -> QueryOneFuture {
+        QueryOneFuture { stream, row: None }
+    }
+}
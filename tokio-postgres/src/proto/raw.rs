@@ -0,0 +1,64 @@
+use futures::{Async, Poll, Stream};
+use postgres_protocol::message::backend;
+use std::mem;
+
+use crate::proto::client::{Client, PendingRequest};
+use crate::proto::responses::Responses;
+use crate::Error;
+
+pub enum State {
+    Start {
+        client: Client,
+        request: PendingRequest,
+    },
+    ReadResponse {
+        receiver: Responses,
+    },
+    Done,
+}
+
+pub struct RawStream(State);
+
+impl Stream for RawStream {
+    type Item = (u8, Vec<u8>);
+    type Error = Error;
+
+    fn poll(&mut self) -> Poll<Option<(u8, Vec<u8>)>, Error> {
+        loop {
+            match mem::replace(&mut self.0, State::Done) {
+                State::Start { client, request } => {
+                    let receiver = client.send(request)?;
+                    self.0 = State::ReadResponse { receiver };
+                }
+                State::ReadResponse { mut receiver } => {
+                    let message = match receiver.poll_raw() {
+                        Ok(Async::Ready(message)) => message,
+                        Ok(Async::NotReady) => {
+                            self.0 = State::ReadResponse { receiver };
+                            return Ok(Async::NotReady);
+                        }
+                        Err(e) => return Err(e),
+                    };
+
+                    match message {
+                        Some((backend::READY_FOR_QUERY_TAG, body)) => {
+                            return Ok(Async::Ready(Some((backend::READY_FOR_QUERY_TAG, body))));
+                        }
+                        Some(message) => {
+                            self.0 = State::ReadResponse { receiver };
+                            return Ok(Async::Ready(Some(message)));
+                        }
+                        None => return Err(Error::closed()),
+                    }
+                }
+                State::Done => return Ok(Async::Ready(None)),
+            }
+        }
+    }
+}
+
+impl RawStream {
+    pub fn new(client: Client, request: PendingRequest) -> RawStream {
+        RawStream(State::Start { client, request })
+    }
+}
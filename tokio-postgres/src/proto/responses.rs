@@ -2,18 +2,24 @@ use fallible_iterator::FallibleIterator;
 use futures::sync::mpsc;
 use futures::{try_ready, Async, Poll, Stream};
 use postgres_protocol::message::backend;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 
 use crate::proto::codec::BackendMessages;
 use crate::Error;
 
-pub fn channel() -> (mpsc::Sender<BackendMessages>, Responses) {
+pub fn channel() -> (mpsc::Sender<BackendMessages>, Arc<AtomicBool>, Responses) {
     let (sender, receiver) = mpsc::channel(1);
+    let sent = Arc::new(AtomicBool::new(false));
 
     (
         sender,
+        sent.clone(),
         Responses {
             receiver,
             cur: BackendMessages::empty(),
+            sent,
+            complete: false,
         },
     )
 }
@@ -21,6 +27,8 @@ pub fn channel() -> (mpsc::Sender<BackendMessages>, Responses) {
 pub struct Responses {
     receiver: mpsc::Receiver<BackendMessages>,
     cur: BackendMessages,
+    sent: Arc<AtomicBool>,
+    complete: bool,
 }
 
 impl Stream for Responses {
@@ -30,13 +38,50 @@ impl Stream for Responses {
     fn poll(&mut self) -> Poll<Option<backend::Message>, Error> {
         loop {
             if let Some(message) = self.cur.next().map_err(Error::parse)? {
+                if let backend::Message::ReadyForQuery(_) = message {
+                    self.complete = true;
+                }
                 return Ok(Async::Ready(Some(message)));
             }
 
-            match try_ready!(self.receiver.poll().map_err(|()| Error::closed())) {
+            match try_ready!(self.poll_receiver()) {
                 Some(messages) => self.cur = messages,
                 None => return Ok(Async::Ready(None)),
             }
         }
     }
 }
+
+impl Responses {
+    #[cfg(feature = "raw-protocol")]
+    pub fn poll_raw(&mut self) -> Poll<Option<(u8, Vec<u8>)>, Error> {
+        loop {
+            if let Some(message) = self.cur.next_raw().map_err(Error::parse)? {
+                if message.0 == backend::READY_FOR_QUERY_TAG {
+                    self.complete = true;
+                }
+                return Ok(Async::Ready(Some(message)));
+            }
+
+            match try_ready!(self.poll_receiver()) {
+                Some(messages) => self.cur = messages,
+                None => return Ok(Async::Ready(None)),
+            }
+        }
+    }
+
+    // The connection only hangs up before the end of the response if it has closed.
+    fn poll_receiver(&mut self) -> Poll<Option<BackendMessages>, Error> {
+        match self.receiver.poll() {
+            Ok(Async::Ready(None)) | Err(()) if !self.complete => {
+                if self.sent.load(Ordering::SeqCst) {
+                    Err(Error::closed_after_send())
+                } else {
+                    Err(Error::closed())
+                }
+            }
+            Ok(r) => Ok(r),
+            Err(()) => Ok(Async::Ready(None)),
+        }
+    }
+}
@@ -14,6 +14,7 @@ pub enum State {
         request: PendingRequest,
     },
     ReadResponse {
+        client: Client,
         columns: Option<Arc<[String]>>,
         receiver: Responses,
     },
@@ -32,18 +33,24 @@ impl Stream for SimpleQueryStream {
                 State::Start { client, request } => {
                     let receiver = client.send(request)?;
                     self.0 = State::ReadResponse {
+                        client,
                         columns: None,
                         receiver,
                     };
                 }
                 State::ReadResponse {
+                    client,
                     columns,
                     mut receiver,
                 } => {
                     let message = match receiver.poll() {
                         Ok(Async::Ready(message)) => message,
                         Ok(Async::NotReady) => {
-                            self.0 = State::ReadResponse { columns, receiver };
+                            self.0 = State::ReadResponse {
+                                client,
+                                columns,
+                                receiver,
+                            };
                             return Ok(Async::NotReady);
                         }
                         Err(e) => return Err(e),
@@ -51,15 +58,14 @@ impl Stream for SimpleQueryStream {
 
                     match message {
                         Some(Message::CommandComplete(body)) => {
-                            let rows = body
-                                .tag()
-                                .map_err(Error::parse)?
-                                .rsplit(' ')
-                                .next()
-                                .unwrap()
-                                .parse()
-                                .unwrap_or(0);
+                            let tag = body.tag().map_err(Error::parse)?;
+                            // the server has dropped every prepared statement, including those cached by the client
+                            if tag == "DEALLOCATE ALL" || tag == "DISCARD ALL" {
+                                client.statements_deallocated();
+                            }
+                            let rows = tag.rsplit(' ').next().unwrap().parse().unwrap_or(0);
                             self.0 = State::ReadResponse {
+                                client,
                                 columns: None,
                                 receiver,
                             };
@@ -69,34 +75,43 @@ impl Stream for SimpleQueryStream {
                         }
                         Some(Message::EmptyQueryResponse) => {
                             self.0 = State::ReadResponse {
+                                client,
                                 columns: None,
                                 receiver,
                             };
                             return Ok(Async::Ready(Some(SimpleQueryMessage::CommandComplete(0))));
                         }
                         Some(Message::RowDescription(body)) => {
-                            let columns = body
+                            let columns: Arc<[String]> = body
                                 .fields()
                                 .map(|f| Ok(f.name().to_string()))
                                 .collect::<Vec<_>>()
                                 .map_err(Error::parse)?
                                 .into();
                             self.0 = State::ReadResponse {
-                                columns: Some(columns),
+                                client,
+                                columns: Some(columns.clone()),
                                 receiver,
                             };
+                            return Ok(Async::Ready(Some(SimpleQueryMessage::RowDescription(
+                                columns,
+                            ))));
                         }
                         Some(Message::DataRow(body)) => {
                             let row = match &columns {
                                 Some(columns) => SimpleQueryRow::new(columns.clone(), body)?,
                                 None => return Err(Error::unexpected_message()),
                             };
-                            self.0 = State::ReadResponse { columns, receiver };
+                            self.0 = State::ReadResponse {
+                                client,
+                                columns,
+                                receiver,
+                            };
                             return Ok(Async::Ready(Some(SimpleQueryMessage::Row(row))));
                         }
                         Some(Message::ErrorResponse(body)) => return Err(Error::db(body)),
                         Some(Message::ReadyForQuery(_)) => return Ok(Async::Ready(None)),
-                        Some(_) => return Err(Error::unexpected_message()),
+                        Some(m) => return Err(Error::unexpected("a simple query response", &m)),
                         None => return Err(Error::closed()),
                     }
                 }
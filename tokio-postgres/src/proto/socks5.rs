@@ -0,0 +1,241 @@
+use futures::{try_ready, Future, Poll};
+use state_machine_future::{transition, RentToOwn, StateMachineFuture};
+use std::io;
+use std::net::IpAddr;
+use tokio_io::io::{read_exact, write_all, ReadExact, WriteAll};
+use tokio_tcp::TcpStream;
+
+use crate::Error;
+
+const VERSION: u8 = 5;
+const NO_AUTHENTICATION: u8 = 0;
+const USERNAME_PASSWORD: u8 = 2;
+const NO_ACCEPTABLE_METHODS: u8 = 0xff;
+const CONNECT: u8 = 1;
+const IPV4: u8 = 1;
+const DOMAIN_NAME: u8 = 3;
+const IPV6: u8 = 4;
+
+#[derive(StateMachineFuture)]
+pub enum Socks5 {
+    #[state_machine_future(start, transitions(ReadingMethod))]
+    Start {
+        future: WriteAll<TcpStream, Vec<u8>>,
+        authentication: Option<Vec<u8>>,
+        request: Vec<u8>,
+    },
+    #[state_machine_future(transitions(SendingAuthentication, SendingRequest))]
+    ReadingMethod {
+        future: ReadExact<TcpStream, [u8; 2]>,
+        authentication: Option<Vec<u8>>,
+        request: Vec<u8>,
+    },
+    #[state_machine_future(transitions(ReadingAuthentication))]
+    SendingAuthentication {
+        future: WriteAll<TcpStream, Vec<u8>>,
+        request: Vec<u8>,
+    },
+    #[state_machine_future(transitions(SendingRequest))]
+    ReadingAuthentication {
+        future: ReadExact<TcpStream, [u8; 2]>,
+        request: Vec<u8>,
+    },
+    #[state_machine_future(transitions(ReadingReply))]
+    SendingRequest {
+        future: WriteAll<TcpStream, Vec<u8>>,
+    },
+    #[state_machine_future(transitions(ReadingAddress))]
+    ReadingReply {
+        future: ReadExact<TcpStream, [u8; 5]>,
+    },
+    #[state_machine_future(transitions(Finished))]
+    ReadingAddress {
+        future: ReadExact<TcpStream, Vec<u8>>,
+    },
+    #[state_machine_future(ready)]
+    Finished(TcpStream),
+    #[state_machine_future(error)]
+    Failed(Error),
+}
+
+impl PollSocks5 for Socks5 {
+    fn poll_start<'a>(state: &'a mut RentToOwn<'a, Start>) -> Poll<AfterStart, Error> {
+        let (stream, _) = try_ready!(state.future.poll().map_err(Error::connect));
+        let state = state.take();
+
+        transition!(ReadingMethod {
+            future: read_exact(stream, [0; 2]),
+            authentication: state.authentication,
+            request: state.request,
+        })
+    }
+
+    fn poll_reading_method<'a>(
+        state: &'a mut RentToOwn<'a, ReadingMethod>,
+    ) -> Poll<AfterReadingMethod, Error> {
+        let (stream, reply) = try_ready!(state.future.poll().map_err(Error::connect));
+        let state = state.take();
+
+        if reply[0] != VERSION {
+            return Err(error("invalid response from SOCKS5 proxy"));
+        }
+        match (reply[1], state.authentication) {
+            (NO_AUTHENTICATION, _) => transition!(SendingRequest {
+                future: write_all(stream, state.request),
+            }),
+            (USERNAME_PASSWORD, Some(authentication)) => transition!(SendingAuthentication {
+                future: write_all(stream, authentication),
+                request: state.request,
+            }),
+            (NO_ACCEPTABLE_METHODS, _) => Err(error(
+                "SOCKS5 proxy rejected the offered authentication methods",
+            )),
+            _ => Err(error("invalid response from SOCKS5 proxy")),
+        }
+    }
+
+    fn poll_sending_authentication<'a>(
+        state: &'a mut RentToOwn<'a, SendingAuthentication>,
+    ) -> Poll<AfterSendingAuthentication, Error> {
+        let (stream, _) = try_ready!(state.future.poll().map_err(Error::connect));
+        let state = state.take();
+
+        transition!(ReadingAuthentication {
+            future: read_exact(stream, [0; 2]),
+            request: state.request,
+        })
+    }
+
+    fn poll_reading_authentication<'a>(
+        state: &'a mut RentToOwn<'a, ReadingAuthentication>,
+    ) -> Poll<AfterReadingAuthentication, Error> {
+        let (stream, reply) = try_ready!(state.future.poll().map_err(Error::connect));
+        let state = state.take();
+
+        if reply[1] != 0 {
+            return Err(error("SOCKS5 proxy authentication failed"));
+        }
+
+        transition!(SendingRequest {
+            future: write_all(stream, state.request),
+        })
+    }
+
+    fn poll_sending_request<'a>(
+        state: &'a mut RentToOwn<'a, SendingRequest>,
+    ) -> Poll<AfterSendingRequest, Error> {
+        let (stream, _) = try_ready!(state.future.poll().map_err(Error::connect));
+
+        transition!(ReadingReply {
+            future: read_exact(stream, [0; 5]),
+        })
+    }
+
+    fn poll_reading_reply<'a>(
+        state: &'a mut RentToOwn<'a, ReadingReply>,
+    ) -> Poll<AfterReadingReply, Error> {
+        let (stream, reply) = try_ready!(state.future.poll().map_err(Error::connect));
+
+        if reply[0] != VERSION {
+            return Err(error("invalid response from SOCKS5 proxy"));
+        }
+        let message = match reply[1] {
+            0 => None,
+            1 => Some("general SOCKS server failure"),
+            2 => Some("connection not allowed by ruleset"),
+            3 => Some("network unreachable"),
+            4 => Some("host unreachable"),
+            5 => Some("connection refused"),
+            6 => Some("TTL expired"),
+            7 => Some("command not supported"),
+            8 => Some("address type not supported"),
+            _ => Some("unknown error"),
+        };
+        if let Some(message) = message {
+            return Err(error(&format!("SOCKS5 proxy error: {}", message)));
+        }
+
+        // the reply ends with the address bound by the proxy, whose first byte has already been read
+        let remaining = match reply[3] {
+            IPV4 => 4 - 1 + 2,
+            IPV6 => 16 - 1 + 2,
+            DOMAIN_NAME => reply[4] as usize + 2,
+            _ => return Err(error("invalid response from SOCKS5 proxy")),
+        };
+
+        transition!(ReadingAddress {
+            future: read_exact(stream, vec![0; remaining]),
+        })
+    }
+
+    fn poll_reading_address<'a>(
+        state: &'a mut RentToOwn<'a, ReadingAddress>,
+    ) -> Poll<AfterReadingAddress, Error> {
+        let (stream, _) = try_ready!(state.future.poll().map_err(Error::connect));
+        transition!(Finished(stream))
+    }
+}
+
+impl Socks5Future {
+    pub fn new(
+        stream: TcpStream,
+        host: &str,
+        port: u16,
+        user: Option<&str>,
+        password: Option<&[u8]>,
+    ) -> Result<Socks5Future, Error> {
+        let authentication = match user {
+            Some(user) => {
+                let password = password.unwrap_or(&[]);
+                if user.len() > 255 || password.len() > 255 {
+                    return Err(error(
+                        "SOCKS5 proxy username and password must be at most 255 bytes",
+                    ));
+                }
+                let mut buf = vec![1, user.len() as u8];
+                buf.extend_from_slice(user.as_bytes());
+                buf.push(password.len() as u8);
+                buf.extend_from_slice(password);
+                Some(buf)
+            }
+            None => None,
+        };
+
+        let greeting = if authentication.is_some() {
+            vec![VERSION, 2, NO_AUTHENTICATION, USERNAME_PASSWORD]
+        } else {
+            vec![VERSION, 1, NO_AUTHENTICATION]
+        };
+
+        let mut request = vec![VERSION, CONNECT, 0];
+        match host.parse::<IpAddr>() {
+            Ok(IpAddr::V4(addr)) => {
+                request.push(IPV4);
+                request.extend_from_slice(&addr.octets());
+            }
+            Ok(IpAddr::V6(addr)) => {
+                request.push(IPV6);
+                request.extend_from_slice(&addr.octets());
+            }
+            Err(_) => {
+                if host.len() > 255 {
+                    return Err(error("hostname too long for a SOCKS5 proxy"));
+                }
+                request.push(DOMAIN_NAME);
+                request.push(host.len() as u8);
+                request.extend_from_slice(host.as_bytes());
+            }
+        }
+        request.extend_from_slice(&port.to_be_bytes());
+
+        Ok(Socks5::start(
+            write_all(stream, greeting),
+            authentication,
+            request,
+        ))
+    }
+}
+
+fn error(message: &str) -> Error {
+    Error::connect(io::Error::new(io::ErrorKind::Other, message))
+}
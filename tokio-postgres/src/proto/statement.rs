@@ -1,6 +1,7 @@
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 
-use crate::proto::client::WeakClient;
+use crate::proto::client::{Client, WeakClient};
 use crate::types::Type;
 use crate::Column;
 
@@ -9,10 +10,16 @@ pub struct StatementInner {
     name: String,
     params: Vec<Type>,
     columns: Vec<Column>,
+    closed: AtomicBool,
+    deallocations: usize,
 }
 
 impl Drop for StatementInner {
     fn drop(&mut self) {
+        if self.closed.load(Ordering::SeqCst) {
+            return;
+        }
+
         if let Some(client) = self.client.upgrade() {
             client.close_statement(&self.name);
         }
@@ -24,16 +31,18 @@ pub struct Statement(Arc<StatementInner>);
 
 impl Statement {
     pub fn new(
-        client: WeakClient,
+        client: &Client,
         name: String,
         params: Vec<Type>,
         columns: Vec<Column>,
     ) -> Statement {
         Statement(Arc::new(StatementInner {
-            client,
+            client: client.downgrade(),
             name,
             params,
             columns,
+            closed: AtomicBool::new(false),
+            deallocations: client.deallocations(),
         }))
     }
 
@@ -48,4 +57,14 @@ impl Statement {
     pub fn columns(&self) -> &[Column] {
         &self.0.columns
     }
+
+    // The client's deallocation count when the statement was prepared. It no longer exists on the server if they
+    // differ.
+    pub fn deallocations(&self) -> usize {
+        self.0.deallocations
+    }
+
+    pub fn set_closed(&self) {
+        self.0.closed.store(true, Ordering::SeqCst);
+    }
 }
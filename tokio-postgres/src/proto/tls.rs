@@ -44,10 +44,17 @@ where
     S: AsyncRead + AsyncWrite,
 {
     fn poll_start<'a>(state: &'a mut RentToOwn<'a, Start<S, T>>) -> Poll<AfterStart<S, T>, Error> {
-        let state = state.take();
+        let mut state = state.take();
+
+        if let SslMode::VerifyCa | SslMode::VerifyFull = state.mode {
+            state
+                .tls
+                .require_verification(state.mode)
+                .map_err(Error::tls)?;
+        }
 
         match state.mode {
-            SslMode::Disable => transition!(Ready((
+            SslMode::Disable | SslMode::Allow => transition!(Ready((
                 MaybeTlsStream::Raw(state.stream),
                 ChannelBinding::none()
             ))),
@@ -55,7 +62,7 @@ where
                 MaybeTlsStream::Raw(state.stream),
                 ChannelBinding::none()
             ))),
-            SslMode::Prefer | SslMode::Require => {
+            SslMode::Prefer | SslMode::Require | SslMode::VerifyCa | SslMode::VerifyFull => {
                 let mut buf = vec![];
                 frontend::ssl_request(&mut buf);
 
@@ -91,7 +98,7 @@ where
             transition!(ConnectingTls {
                 future: state.tls.connect(stream),
             })
-        } else if state.mode == SslMode::Require {
+        } else if state.mode != SslMode::Prefer {
             Err(Error::tls("server does not support TLS".into()))
         } else {
             transition!(Ready((MaybeTlsStream::Raw(stream), ChannelBinding::none())))
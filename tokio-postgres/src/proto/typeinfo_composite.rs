@@ -5,7 +5,6 @@ use std::mem;
 use std::vec;
 
 use crate::error::Error;
-use crate::next_statement;
 use crate::proto::client::Client;
 use crate::proto::prepare::PrepareFuture;
 use crate::proto::query::QueryStream;
@@ -67,8 +66,9 @@ impl PollTypeinfoComposite for TypeinfoComposite {
             }),
             None => transition!(PreparingTypeinfoComposite {
                 future: Box::new(state.client.prepare(
-                    next_statement(),
+                    state.client.next_statement(),
                     TYPEINFO_COMPOSITE_QUERY,
+                    &[],
                     &[]
                 )),
                 oid: state.oid,
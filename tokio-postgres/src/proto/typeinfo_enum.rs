@@ -3,7 +3,6 @@ use futures::{try_ready, Async, Future, Poll};
 use state_machine_future::{transition, RentToOwn, StateMachineFuture};
 
 use crate::error::{Error, SqlState};
-use crate::next_statement;
 use crate::proto::client::Client;
 use crate::proto::prepare::PrepareFuture;
 use crate::proto::query::QueryStream;
@@ -65,11 +64,12 @@ impl PollTypeinfoEnum for TypeinfoEnum {
                 client: state.client,
             }),
             None => transition!(PreparingTypeinfoEnum {
-                future: Box::new(
-                    state
-                        .client
-                        .prepare(next_statement(), TYPEINFO_ENUM_QUERY, &[])
-                ),
+                future: Box::new(state.client.prepare(
+                    state.client.next_statement(),
+                    TYPEINFO_ENUM_QUERY,
+                    &[],
+                    &[]
+                )),
                 oid: state.oid,
                 client: state.client,
             }),
@@ -87,8 +87,9 @@ impl PollTypeinfoEnum for TypeinfoEnum {
 
                 transition!(PreparingTypeinfoEnumFallback {
                     future: Box::new(state.client.prepare(
-                        next_statement(),
+                        state.client.next_statement(),
                         TYPEINFO_ENUM_FALLBACK_QUERY,
+                        &[],
                         &[]
                     )),
                     oid: state.oid,
@@ -0,0 +1,135 @@
+//! Streaming replication support.
+//!
+//! Connections configured with a replication mode accept the commands of Postgres's streaming replication protocol.
+//! Commands such as `IDENTIFY_SYSTEM` and `CREATE_REPLICATION_SLOT` return normal results and can be run with
+//! `Client::simple_query`, while `START_REPLICATION` switches the connection into `COPY BOTH` mode and is run with
+//! `Client::copy_both_simple`. The server then streams WAL data and keepalives to the client, which should be parsed
+//! with `ReplicationMessage::parse`, and the client periodically reports its progress back to the server with
+//! `standby_status_update`.
+use bytes::Bytes;
+use postgres_protocol::message::backend;
+use postgres_protocol::message::frontend;
+use std::time::SystemTime;
+
+use crate::types::{system_time_from_micros, system_time_to_micros, Lsn};
+use crate::Error;
+
+/// A message sent by the server during streaming replication.
+#[derive(Debug)]
+pub enum ReplicationMessage {
+    /// A chunk of WAL data.
+    XLogData(XLogData),
+    /// A keepalive sent periodically by the server.
+    PrimaryKeepAlive(PrimaryKeepAlive),
+}
+
+impl ReplicationMessage {
+    /// Parses a message from the contents of a `CopyData` message sent by the server.
+    pub fn parse(buf: &Bytes) -> Result<ReplicationMessage, Error> {
+        match backend::ReplicationMessage::parse(buf).map_err(Error::parse)? {
+            backend::ReplicationMessage::XLogData(body) => {
+                Ok(ReplicationMessage::XLogData(XLogData {
+                    wal_start: Lsn::from(body.wal_start()),
+                    wal_end: Lsn::from(body.wal_end()),
+                    timestamp: system_time_from_micros(body.timestamp()),
+                    data: body.into_bytes(),
+                }))
+            }
+            backend::ReplicationMessage::PrimaryKeepAlive(body) => {
+                Ok(ReplicationMessage::PrimaryKeepAlive(PrimaryKeepAlive {
+                    wal_end: Lsn::from(body.wal_end()),
+                    timestamp: system_time_from_micros(body.timestamp()),
+                    reply: body.reply() != 0,
+                }))
+            }
+        }
+    }
+}
+
+/// A chunk of WAL data sent by the server.
+#[derive(Debug)]
+pub struct XLogData {
+    wal_start: Lsn,
+    wal_end: Lsn,
+    timestamp: SystemTime,
+    data: Bytes,
+}
+
+impl XLogData {
+    /// Returns the position in the WAL of the start of the data.
+    pub fn wal_start(&self) -> Lsn {
+        self.wal_start
+    }
+
+    /// Returns the current end of the WAL on the server.
+    pub fn wal_end(&self) -> Lsn {
+        self.wal_end
+    }
+
+    /// Returns the server's clock at the time the message was sent.
+    pub fn timestamp(&self) -> SystemTime {
+        self.timestamp
+    }
+
+    /// Returns the WAL data.
+    ///
+    /// For logical replication, this is the output of the slot's output plugin.
+    pub fn data(&self) -> &Bytes {
+        &self.data
+    }
+
+    /// Consumes the message, returning the WAL data.
+    pub fn into_data(self) -> Bytes {
+        self.data
+    }
+}
+
+/// A keepalive sent periodically by the server.
+#[derive(Debug)]
+pub struct PrimaryKeepAlive {
+    wal_end: Lsn,
+    timestamp: SystemTime,
+    reply: bool,
+}
+
+impl PrimaryKeepAlive {
+    /// Returns the current end of the WAL on the server.
+    pub fn wal_end(&self) -> Lsn {
+        self.wal_end
+    }
+
+    /// Returns the server's clock at the time the message was sent.
+    pub fn timestamp(&self) -> SystemTime {
+        self.timestamp
+    }
+
+    /// Determines if the server has requested an immediate status update, to avoid a timeout disconnect.
+    pub fn reply_requested(&self) -> bool {
+        self.reply
+    }
+}
+
+/// Creates a standby status update reporting the client's replication progress, to be sent to the server through the
+/// sink returned by `Client::copy_both_simple`.
+///
+/// `write_lsn`, `flush_lsn` and `apply_lsn` are the positions following the last WAL data written to disk, flushed to
+/// disk, and applied by the client, respectively. WAL before `flush_lsn` may be removed by the server. If `reply` is
+/// set, the server will immediately respond with a keepalive.
+pub fn standby_status_update(
+    write_lsn: Lsn,
+    flush_lsn: Lsn,
+    apply_lsn: Lsn,
+    timestamp: SystemTime,
+    reply: bool,
+) -> Bytes {
+    let mut buf = vec![];
+    frontend::standby_status_update(
+        write_lsn.into(),
+        flush_lsn.into(),
+        apply_lsn.into(),
+        system_time_to_micros(timestamp),
+        reply as u8,
+        &mut buf,
+    );
+    buf.into()
+}
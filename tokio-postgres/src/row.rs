@@ -1,7 +1,11 @@
 //! Rows.
 
+#[cfg(feature = "with-bytes-0_4")]
+use bytes::Bytes;
 use fallible_iterator::FallibleIterator;
 use postgres_protocol::message::backend::DataRowBody;
+#[cfg(feature = "with-serde_json-1")]
+use serde_1::Deserialize;
 use std::fmt;
 use std::ops::Range;
 use std::str;
@@ -10,8 +14,12 @@ use std::sync::Arc;
 use crate::proto;
 use crate::row::sealed::{AsName, Sealed};
 use crate::stmt::Column;
-use crate::types::{FromSql, Type, WrongType};
+#[cfg(feature = "with-serde_json-1")]
+use crate::types::Json;
+use crate::types::{Format, FromSql, Type, WrongType};
 use crate::Error;
+#[cfg(feature = "derive")]
+pub use postgres_derive::FromRow;
 
 mod sealed {
     pub trait Sealed {}
@@ -130,7 +138,8 @@ impl Row {
 
     /// Deserializes a value from the row.
     ///
-    /// The value can be specified either by its numeric index in the row, or by its column name.
+    /// The value can be specified either by its numeric index in the row, or by its column name. Types like `&str`
+    /// and `&[u8]` borrow from the row's buffer rather than copying the value out of it.
     ///
     /// # Panics
     ///
@@ -155,6 +164,97 @@ impl Row {
         self.get_inner(&idx)
     }
 
+    /// Deserializes a `JSON` or `JSONB` value from the row directly into a `Deserialize` type.
+    ///
+    /// This is equivalent to retrieving a `Json<T>`, and avoids building an intermediate `serde_json::Value`. Errors
+    /// from deserialization are returned with the `serde_json` error as their source.
+    ///
+    /// Requires the `with-serde_json-1` Cargo feature.
+    #[cfg(feature = "with-serde_json-1")]
+    pub fn get_json<'a, I, T>(&'a self, idx: I) -> Result<T, Error>
+    where
+        I: RowIndex,
+        T: Deserialize<'a>,
+    {
+        self.get_inner::<_, Json<T>>(&idx).map(|json| json.0)
+    }
+
+    /// Retrieves a `BYTEA` value from the row as a `Bytes` which shares the row's buffer rather than copying it.
+    ///
+    /// The returned value keeps the whole row's buffer alive. `None` is returned if the value is `NULL`.
+    ///
+    /// The bytes are the column's raw value in the format it was retrieved in, so only columns retrieved in the binary
+    /// format (the default) are supported. An error is returned for a column requested in the text format with
+    /// `Client::prepare_with_result_formats`, whose raw value would be the hex-encoded text rather than the bytes
+    /// themselves; use `Row::get` to decode it instead.
+    ///
+    /// Requires the `with-bytes-0_4` Cargo feature.
+    #[cfg(feature = "with-bytes-0_4")]
+    pub fn get_bytes<I>(&self, idx: I) -> Result<Option<Bytes>, Error>
+    where
+        I: RowIndex,
+    {
+        let idx = match idx.__idx(self.columns()) {
+            Some(idx) => idx,
+            None => return Err(Error::column()),
+        };
+
+        let column = &self.columns()[idx];
+        if *column.type_() != Type::BYTEA {
+            return Err(Error::from_sql(
+                Box::new(WrongType::new(column.type_().clone())),
+                idx,
+                column.name(),
+            ));
+        }
+        if column.format() == Format::Text {
+            return Err(Error::from_sql(
+                "the text format of BYTEA values must be decoded".into(),
+                idx,
+                column.name(),
+            ));
+        }
+
+        let buf = self.body.buffer_bytes();
+        Ok(self.ranges[idx].clone().map(|r| buf.slice(r.start, r.end)))
+    }
+
+    /// Deserializes every value in the row into a tuple, in order.
+    ///
+    /// Each element of the tuple is retrieved as with `Row::try_get`, and an error is returned if the row does not
+    /// contain exactly as many values as the tuple has elements. This is convenient for queries returning a few
+    /// columns, such as `INSERT ... RETURNING`:
+    ///
+    /// ```no_run
+    /// # fn f(rows: Vec<tokio_postgres::Row>) -> Result<(), tokio_postgres::Error> {
+    /// use std::time::SystemTime;
+    ///
+    /// let ids = rows
+    ///     .iter()
+    ///     .map(|row| row.get_tuple::<(i32, SystemTime)>())
+    ///     .collect::<Result<Vec<_>, _>>()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn get_tuple<'a, T>(&'a self) -> Result<T, Error>
+    where
+        T: FromRow<'a>,
+    {
+        T::from_row(self)
+    }
+
+    #[doc(hidden)]
+    pub fn __get_field<'a, T>(&'a self, column: &str, field: &str) -> Result<T, Error>
+    where
+        T: FromSql<'a>,
+    {
+        if column.__idx(self.columns()).is_none() {
+            return Err(Error::missing_field(column, field));
+        }
+
+        self.get_inner(&column)
+    }
+
     fn get_inner<'a, I, T>(&'a self, idx: &I) -> Result<T, Error>
     where
         I: RowIndex,
@@ -165,16 +265,80 @@ impl Row {
             None => return Err(Error::column()),
         };
 
-        let ty = self.columns()[idx].type_();
+        let column = &self.columns()[idx];
+        let mut ty = column.type_();
+        if column.format() == Format::Text && !T::accepts(ty) && T::accepts(&Type::TEXT) {
+            // the text format of any value can be read as a string
+            ty = &Type::TEXT;
+        }
         if !T::accepts(ty) {
-            return Err(Error::from_sql(Box::new(WrongType::new(ty.clone())), idx));
+            return Err(Error::from_sql(
+                Box::new(WrongType::new(ty.clone())),
+                idx,
+                column.name(),
+            ));
         }
 
         let buf = self.ranges[idx].clone().map(|r| &self.body.buffer()[r]);
-        FromSql::from_sql_nullable(ty, buf).map_err(|e| Error::from_sql(e, idx))
+        let value = match column.format() {
+            Format::Binary => T::from_sql_nullable(ty, buf),
+            Format::Text => T::from_sql_text_nullable(ty, buf),
+        };
+        value.map_err(|e| Error::from_sql(e, idx, column.name()))
     }
 }
 
+/// A trait for types that can be created from all of the values in a `Row`.
+///
+/// This is implemented for tuples of up to 8 `FromSql` types, and is used by `Row::get_tuple`.
+///
+/// With the `derive` Cargo feature enabled, it can also be derived for structs with named fields. Each field is
+/// retrieved from the column with the same name, or from the column named by a `#[postgres(column = "...")]`
+/// attribute. Nullable columns should be retrieved into `Option` fields. As with the `ToSql` and `FromSql` derives,
+/// a `#[postgres(crate = "postgres")]` attribute is required when deriving through the `postgres` crate.
+///
+/// ```ignore
+/// use tokio_postgres::row::FromRow;
+///
+/// #[derive(FromRow)]
+/// struct User {
+///     id: i32,
+///     #[postgres(column = "user_name")]
+///     name: String,
+///     email: Option<String>,
+/// }
+/// ```
+pub trait FromRow<'a>: Sized {
+    /// Creates a new value from the values of a row.
+    fn from_row(row: &'a Row) -> Result<Self, Error>;
+}
+
+macro_rules! from_row_tuple {
+    ($n:expr, $($t:ident $idx:tt),+) => {
+        impl<'a, $($t),+> FromRow<'a> for ($($t,)+)
+        where
+            $($t: FromSql<'a>),+
+        {
+            fn from_row(row: &'a Row) -> Result<Self, Error> {
+                if row.len() != $n {
+                    return Err(Error::column_count($n, row.len()));
+                }
+
+                Ok(($(row.get_inner(&$idx)?,)+))
+            }
+        }
+    };
+}
+
+from_row_tuple!(1, A 0);
+from_row_tuple!(2, A 0, B 1);
+from_row_tuple!(3, A 0, B 1, C 2);
+from_row_tuple!(4, A 0, B 1, C 2, D 3);
+from_row_tuple!(5, A 0, B 1, C 2, D 3, E 4);
+from_row_tuple!(6, A 0, B 1, C 2, D 3, E 4, F 5);
+from_row_tuple!(7, A 0, B 1, C 2, D 3, E 4, F 5, G 6);
+from_row_tuple!(8, A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7);
+
 /// A row of data returned from the database by a simple query.
 pub struct SimpleQueryRow {
     columns: Arc<[String]>,
@@ -193,6 +357,11 @@ impl SimpleQueryRow {
         })
     }
 
+    /// Returns the names of the columns of data in the row.
+    pub fn columns(&self) -> &[String] {
+        &self.columns
+    }
+
     /// Determines if the row contains no values.
     pub fn is_empty(&self) -> bool {
         self.len() == 0
@@ -238,6 +407,7 @@ impl SimpleQueryRow {
         };
 
         let buf = self.ranges[idx].clone().map(|r| &self.body.buffer()[r]);
-        FromSql::from_sql_nullable(&Type::TEXT, buf).map_err(|e| Error::from_sql(e, idx))
+        FromSql::from_sql_nullable(&Type::TEXT, buf)
+            .map_err(|e| Error::from_sql(e, idx, &self.columns[idx]))
     }
 }
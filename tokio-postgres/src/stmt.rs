@@ -1,15 +1,41 @@
-use crate::types::Type;
+use crate::types::{Format, Oid, Type};
 
 /// Information about a column of a Postgres query.
 #[derive(Debug)]
 pub struct Column {
     name: String,
     type_: Type,
+    table_oid: Option<Oid>,
+    column_id: Option<i16>,
+    type_modifier: i32,
+    format: Format,
 }
 
 impl Column {
-    pub(crate) fn new(name: String, type_: Type) -> Column {
-        Column { name, type_ }
+    pub(crate) fn new(
+        name: String,
+        type_: Type,
+        table_oid: Oid,
+        column_id: i16,
+        type_modifier: i32,
+        format: Format,
+    ) -> Column {
+        Column {
+            name,
+            type_,
+            table_oid: if table_oid == 0 {
+                None
+            } else {
+                Some(table_oid)
+            },
+            column_id: if column_id == 0 {
+                None
+            } else {
+                Some(column_id)
+            },
+            type_modifier,
+            format,
+        }
     }
 
     /// Returns the name of the column.
@@ -21,4 +47,55 @@ impl Column {
     pub fn type_(&self) -> &Type {
         &self.type_
     }
+
+    /// Returns the format the column's values are requested in.
+    ///
+    /// Values are requested in the binary format unless the column's type has no binary format, or the text format
+    /// was requested with `Client::prepare_with_result_formats`.
+    pub fn format(&self) -> Format {
+        self.format
+    }
+
+    /// Returns the OID of the table the column was taken from, if it maps directly to a table column.
+    pub fn table_oid(&self) -> Option<Oid> {
+        self.table_oid
+    }
+
+    /// Returns the attribute number of the column within its table, if it maps directly to a table column.
+    pub fn column_id(&self) -> Option<i16> {
+        self.column_id
+    }
+
+    /// Returns the type modifier of the column, such as the declared length of a `VARCHAR(n)`.
+    ///
+    /// The interpretation of the value depends on the type, and -1 indicates that the column has no modifier.
+    /// `max_length` and `numeric_precision_scale` decode it for common types.
+    pub fn type_modifier(&self) -> i32 {
+        self.type_modifier
+    }
+
+    /// Returns the declared maximum length in characters of a `VARCHAR(n)` or `CHAR(n)` column.
+    ///
+    /// `None` is returned for other types, and for columns without a declared length.
+    pub fn max_length(&self) -> Option<i32> {
+        match self.type_ {
+            Type::VARCHAR | Type::BPCHAR if self.type_modifier >= 4 => Some(self.type_modifier - 4),
+            _ => None,
+        }
+    }
+
+    /// Returns the declared precision and scale of a `NUMERIC(p, s)` column.
+    ///
+    /// `None` is returned for other types, and for columns without a declared precision.
+    pub fn numeric_precision_scale(&self) -> Option<(i32, i32)> {
+        if self.type_ != Type::NUMERIC || self.type_modifier < 4 {
+            return None;
+        }
+
+        let typmod = self.type_modifier - 4;
+        let precision = (typmod >> 16) & 0xffff;
+        // the scale is stored as an 11 bit signed integer, since it can be negative as of Postgres 15
+        let scale = ((typmod & 0x7ff) ^ 1024) - 1024;
+        Some((precision, scale))
+    }
 }
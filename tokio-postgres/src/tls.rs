@@ -1,12 +1,14 @@
 //! TLS support.
 
 use futures::future::{self, FutureResult};
-use futures::{Future, Poll};
+use futures::{try_ready, Async, Future, Poll};
 use std::error::Error;
 use std::fmt;
 use std::io::{self, Read, Write};
 use tokio_io::{AsyncRead, AsyncWrite};
 
+use crate::config::SslMode;
+
 pub(crate) mod private {
     pub struct ForcePrivateApi;
 }
@@ -62,6 +64,21 @@ pub trait TlsConnect<S> {
     /// Returns a future performing a TLS handshake over the stream.
     fn connect(self, stream: S) -> Self::Future;
 
+    /// Configures the connector to perform the certificate verification required by an SSL mode.
+    ///
+    /// This is called before the handshake of connections using `SslMode::VerifyCa`, which requires the server's
+    /// certificate chain to be verified, or `SslMode::VerifyFull`, which additionally requires the certificate to match
+    /// the host name. Implementations which can't guarantee that verification must return an error, which fails the
+    /// connection.
+    ///
+    /// The default implementation returns an error for both modes.
+    fn require_verification(&mut self, mode: SslMode) -> Result<(), Box<dyn Error + Sync + Send>> {
+        match mode {
+            SslMode::VerifyCa | SslMode::VerifyFull => Err(VerificationUnsupported(mode).into()),
+            _ => Ok(()),
+        }
+    }
+
     #[doc(hidden)]
     fn can_connect(&self, _: private::ForcePrivateApi) -> bool {
         true
@@ -139,3 +156,195 @@ impl fmt::Display for NoTlsError {
 }
 
 impl Error for NoTlsError {}
+
+// The error returned by the default `TlsConnect::require_verification` implementation.
+#[derive(Debug)]
+struct VerificationUnsupported(SslMode);
+
+impl fmt::Display for VerificationUnsupported {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mode = match self.0 {
+            SslMode::VerifyCa => "verify-ca",
+            _ => "verify-full",
+        };
+        write!(
+            fmt,
+            "the TLS connector does not support the certificate verification required by sslmode={}",
+            mode
+        )
+    }
+}
+
+impl Error for VerificationUnsupported {}
+
+/// A `MakeTlsConnect` and `TlsConnect` implementation which delegates to one of two implementations.
+///
+/// This allows the TLS implementation to be chosen at runtime, for example to use a different backend depending on
+/// the platform.
+#[derive(Debug, Copy, Clone)]
+pub enum EitherTls<L, R> {
+    /// The first implementation.
+    Left(L),
+    /// The second implementation.
+    Right(R),
+}
+
+#[cfg(feature = "runtime")]
+impl<S, L, R> MakeTlsConnect<S> for EitherTls<L, R>
+where
+    L: MakeTlsConnect<S>,
+    R: MakeTlsConnect<S>,
+{
+    type Stream = EitherTlsStream<L::Stream, R::Stream>;
+    type TlsConnect = EitherTls<L::TlsConnect, R::TlsConnect>;
+    type Error = Box<dyn Error + Sync + Send>;
+
+    fn make_tls_connect(&mut self, domain: &str) -> Result<Self::TlsConnect, Self::Error> {
+        match self {
+            EitherTls::Left(l) => l
+                .make_tls_connect(domain)
+                .map(EitherTls::Left)
+                .map_err(Into::into),
+            EitherTls::Right(r) => r
+                .make_tls_connect(domain)
+                .map(EitherTls::Right)
+                .map_err(Into::into),
+        }
+    }
+}
+
+impl<S, L, R> TlsConnect<S> for EitherTls<L, R>
+where
+    L: TlsConnect<S>,
+    R: TlsConnect<S>,
+{
+    type Stream = EitherTlsStream<L::Stream, R::Stream>;
+    type Error = Box<dyn Error + Sync + Send>;
+    type Future = EitherTlsFuture<L::Future, R::Future>;
+
+    fn connect(self, stream: S) -> EitherTlsFuture<L::Future, R::Future> {
+        match self {
+            EitherTls::Left(l) => EitherTlsFuture::Left(l.connect(stream)),
+            EitherTls::Right(r) => EitherTlsFuture::Right(r.connect(stream)),
+        }
+    }
+
+    fn require_verification(&mut self, mode: SslMode) -> Result<(), Box<dyn Error + Sync + Send>> {
+        match self {
+            EitherTls::Left(l) => l.require_verification(mode),
+            EitherTls::Right(r) => r.require_verification(mode),
+        }
+    }
+
+    fn can_connect(&self, _: private::ForcePrivateApi) -> bool {
+        match self {
+            EitherTls::Left(l) => l.can_connect(private::ForcePrivateApi),
+            EitherTls::Right(r) => r.can_connect(private::ForcePrivateApi),
+        }
+    }
+}
+
+/// The future returned by the `EitherTls` connector.
+pub enum EitherTlsFuture<L, R> {
+    /// The first implementation's future.
+    Left(L),
+    /// The second implementation's future.
+    Right(R),
+}
+
+impl<L, R, LS, RS> Future for EitherTlsFuture<L, R>
+where
+    L: Future<Item = (LS, ChannelBinding)>,
+    L::Error: Into<Box<dyn Error + Sync + Send>>,
+    R: Future<Item = (RS, ChannelBinding)>,
+    R::Error: Into<Box<dyn Error + Sync + Send>>,
+{
+    type Item = (EitherTlsStream<LS, RS>, ChannelBinding);
+    type Error = Box<dyn Error + Sync + Send>;
+
+    fn poll(&mut self) -> Poll<(EitherTlsStream<LS, RS>, ChannelBinding), Self::Error> {
+        match self {
+            EitherTlsFuture::Left(l) => {
+                let (stream, channel_binding) = try_ready!(l.poll().map_err(Into::into));
+                Ok(Async::Ready((
+                    EitherTlsStream::Left(stream),
+                    channel_binding,
+                )))
+            }
+            EitherTlsFuture::Right(r) => {
+                let (stream, channel_binding) = try_ready!(r.poll().map_err(Into::into));
+                Ok(Async::Ready((
+                    EitherTlsStream::Right(stream),
+                    channel_binding,
+                )))
+            }
+        }
+    }
+}
+
+/// The TLS stream type produced by the `EitherTls` connector.
+pub enum EitherTlsStream<L, R> {
+    /// The first implementation's stream.
+    Left(L),
+    /// The second implementation's stream.
+    Right(R),
+}
+
+impl<L, R> Read for EitherTlsStream<L, R>
+where
+    L: Read,
+    R: Read,
+{
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            EitherTlsStream::Left(l) => l.read(buf),
+            EitherTlsStream::Right(r) => r.read(buf),
+        }
+    }
+}
+
+impl<L, R> AsyncRead for EitherTlsStream<L, R>
+where
+    L: AsyncRead,
+    R: AsyncRead,
+{
+    unsafe fn prepare_uninitialized_buffer(&self, buf: &mut [u8]) -> bool {
+        match self {
+            EitherTlsStream::Left(l) => l.prepare_uninitialized_buffer(buf),
+            EitherTlsStream::Right(r) => r.prepare_uninitialized_buffer(buf),
+        }
+    }
+}
+
+impl<L, R> Write for EitherTlsStream<L, R>
+where
+    L: Write,
+    R: Write,
+{
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            EitherTlsStream::Left(l) => l.write(buf),
+            EitherTlsStream::Right(r) => r.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            EitherTlsStream::Left(l) => l.flush(),
+            EitherTlsStream::Right(r) => r.flush(),
+        }
+    }
+}
+
+impl<L, R> AsyncWrite for EitherTlsStream<L, R>
+where
+    L: AsyncWrite,
+    R: AsyncWrite,
+{
+    fn shutdown(&mut self) -> Poll<(), io::Error> {
+        match self {
+            EitherTlsStream::Left(l) => l.shutdown(),
+            EitherTlsStream::Right(r) => r.shutdown(),
+        }
+    }
+}
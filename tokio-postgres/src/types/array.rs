@@ -0,0 +1,234 @@
+use fallible_iterator::FallibleIterator;
+use postgres_protocol;
+use postgres_protocol::types::{self, ArrayDimension};
+use std::error::Error;
+use std::fmt;
+
+use crate::types::{default_lower_bound, downcast, FromSql, IsNull, Kind, ToSql, Type};
+
+/// Information about a dimension of an array.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Dimension {
+    /// The length of the dimension.
+    pub len: i32,
+    /// The index of the first element of the dimension.
+    pub lower_bound: i32,
+}
+
+/// A multi-dimensional Postgres array.
+///
+/// Unlike `Vec<T>`, this type supports arrays with any number of dimensions and preserves the lower bound of each
+/// dimension. Elements are indexed the same way as they are on the server, so the first element of an array declared
+/// as `[2:4]` has the index 2.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Array<T> {
+    dimensions: Vec<Dimension>,
+    data: Vec<T>,
+}
+
+impl<T> Array<T> {
+    /// Creates a new one-dimensional array from a vector of elements.
+    pub fn from_vec(data: Vec<T>, lower_bound: i32) -> Array<T> {
+        let dimensions = if data.is_empty() {
+            vec![]
+        } else {
+            vec![Dimension {
+                len: data.len() as i32,
+                lower_bound,
+            }]
+        };
+        Array { dimensions, data }
+    }
+
+    /// Creates a new array from its elements, in row-major order, and its dimensions.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of elements does not match the size of the dimensions, or if any dimension has a
+    /// negative length.
+    pub fn from_parts(data: Vec<T>, dimensions: Vec<Dimension>) -> Array<T> {
+        let mut size = if dimensions.is_empty() { 0 } else { 1 };
+        for dimension in &dimensions {
+            assert!(dimension.len >= 0, "negative dimension length");
+            size *= dimension.len as usize;
+        }
+        assert!(
+            size == data.len(),
+            "expected {} elements but got {}",
+            size,
+            data.len()
+        );
+
+        Array { dimensions, data }
+    }
+
+    /// Returns the dimensions of the array.
+    pub fn dimensions(&self) -> &[Dimension] {
+        &self.dimensions
+    }
+
+    /// Returns the element at the specified indices, one per dimension.
+    ///
+    /// Indices are relative to the lower bound of each dimension. `None` is returned if the number of indices does
+    /// not match the number of dimensions, or if any index is out of bounds.
+    pub fn get(&self, indices: &[i32]) -> Option<&T> {
+        if indices.len() != self.dimensions.len() {
+            return None;
+        }
+
+        let mut idx = 0;
+        for (&index, dimension) in indices.iter().zip(&self.dimensions) {
+            let offset = i64::from(index) - i64::from(dimension.lower_bound);
+            if offset < 0 || offset >= i64::from(dimension.len) {
+                return None;
+            }
+            idx = idx * dimension.len as usize + offset as usize;
+        }
+
+        self.data.get(idx)
+    }
+
+    /// Returns the elements of the array in row-major order.
+    pub fn as_slice(&self) -> &[T] {
+        &self.data
+    }
+
+    /// Consumes the array, returning its elements in row-major order.
+    ///
+    /// The dimensions of the array are discarded, so this can be used to retrieve every element of an array without
+    /// caring about its shape. Arrays which may contain `NULL` elements should be retrieved as `Array<Option<T>>`.
+    pub fn into_inner(self) -> Vec<T> {
+        self.data
+    }
+}
+
+impl<'a, T: FromSql<'a>> FromSql<'a> for Array<T> {
+    fn from_sql(ty: &Type, raw: &'a [u8]) -> Result<Array<T>, Box<dyn Error + Sync + Send>> {
+        let member_type = match *ty.kind() {
+            Kind::Array(ref member) => member,
+            _ => panic!("expected array type"),
+        };
+
+        let array = types::array_from_sql(raw)?;
+        let dimensions = array
+            .dimensions()
+            .map(|d| {
+                Ok(Dimension {
+                    len: d.len,
+                    lower_bound: d.lower_bound,
+                })
+            })
+            .collect()?;
+        let data = array
+            .values()
+            .map(|v| T::from_sql_nullable(member_type, v))
+            .collect()?;
+
+        Ok(Array { dimensions, data })
+    }
+
+    fn from_sql_text(_: &Type, _: &'a [u8]) -> Result<Array<T>, Box<dyn Error + Sync + Send>> {
+        Err("arrays in the text format can only be decoded as TextArray".into())
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        match *ty.kind() {
+            Kind::Array(ref inner) => T::accepts(inner),
+            _ => false,
+        }
+    }
+}
+
+impl<T: ToSql> ToSql for Array<T> {
+    fn to_sql(&self, ty: &Type, w: &mut Vec<u8>) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        let member_type = match *ty.kind() {
+            Kind::Array(ref member) => member,
+            _ => panic!("expected array type"),
+        };
+
+        types::array_to_sql(
+            self.dimensions.iter().map(|d| ArrayDimension {
+                len: d.len,
+                lower_bound: d.lower_bound,
+            }),
+            member_type.oid(),
+            self.data.iter(),
+            |e, w| match e.to_sql(member_type, w)? {
+                IsNull::No => Ok(postgres_protocol::IsNull::No),
+                IsNull::Yes => Ok(postgres_protocol::IsNull::Yes),
+            },
+            w,
+        )?;
+        Ok(IsNull::No)
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        match *ty.kind() {
+            Kind::Array(ref member) => T::accepts(member),
+            _ => false,
+        }
+    }
+
+    to_sql_checked!();
+}
+
+/// A wrapper which encodes the elements of an iterator as a one-dimensional Postgres array.
+///
+/// This avoids collecting the elements into a `Vec` before encoding them. The iterator is cloned each time the value
+/// is encoded, and its length must be known in advance since it is written before the elements.
+#[derive(Clone)]
+pub struct ArrayIter<I>(pub I);
+
+impl<I> fmt::Debug for ArrayIter<I> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("ArrayIter").finish()
+    }
+}
+
+impl<I> ToSql for ArrayIter<I>
+where
+    I: ExactSizeIterator + Clone,
+    I::Item: ToSql,
+{
+    fn to_sql(&self, ty: &Type, w: &mut Vec<u8>) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        let member_type = match *ty.kind() {
+            Kind::Array(ref member) => member,
+            _ => panic!("expected array type"),
+        };
+
+        let elements = self.0.clone();
+        let len = downcast(elements.len())?;
+        let mut count = 0;
+
+        types::array_to_sql(
+            Some(ArrayDimension {
+                len,
+                lower_bound: default_lower_bound(ty),
+            }),
+            member_type.oid(),
+            elements.inspect(|_| count += 1),
+            |e, w| match e.to_sql(member_type, w)? {
+                IsNull::No => Ok(postgres_protocol::IsNull::No),
+                IsNull::Yes => Ok(postgres_protocol::IsNull::Yes),
+            },
+            w,
+        )?;
+
+        if count != len {
+            return Err(
+                "iterator produced a different number of elements than its reported length".into(),
+            );
+        }
+
+        Ok(IsNull::No)
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        match *ty.kind() {
+            Kind::Array(ref member) => I::Item::accepts(member),
+            _ => false,
+        }
+    }
+
+    to_sql_checked!();
+}
@@ -0,0 +1,41 @@
+use postgres_protocol::types;
+use std::error::Error;
+
+use crate::types::{FromSql, IsNull, ToSql, Type};
+
+/// A wrapper which stores a `Vec<bool>` as a `BIT` or `VARBIT` value.
+///
+/// The booleans are packed 8 to a byte, making this much more compact than `BOOL[]` for large sets of flags. The
+/// first element corresponds to the leftmost bit of the value. Decoding respects the length of the value, so the
+/// padding bits of the last byte are not returned.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Bits(pub Vec<bool>);
+
+impl<'a> FromSql<'a> for Bits {
+    fn from_sql(_: &Type, raw: &'a [u8]) -> Result<Bits, Box<dyn Error + Sync + Send>> {
+        let varbit = types::varbit_from_sql(raw)?;
+        let bits = (0..varbit.len())
+            .map(|i| varbit.bytes()[i / 8] & (0x80 >> (i % 8)) != 0)
+            .collect();
+        Ok(Bits(bits))
+    }
+
+    accepts!(BIT, VARBIT);
+}
+
+impl ToSql for Bits {
+    fn to_sql(&self, _: &Type, out: &mut Vec<u8>) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        let bytes = self.0.chunks(8).map(|chunk| {
+            chunk
+                .iter()
+                .enumerate()
+                .filter(|(_, &bit)| bit)
+                .fold(0, |byte, (i, _)| byte | (0x80 >> i))
+        });
+        types::varbit_to_sql(self.0.len(), bytes, out)?;
+        Ok(IsNull::No)
+    }
+
+    accepts!(BIT, VARBIT);
+    to_sql_checked!();
+}
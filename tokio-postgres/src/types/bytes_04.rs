@@ -0,0 +1,23 @@
+use bytes::Bytes;
+use postgres_protocol::types;
+use std::error::Error;
+
+use crate::types::{FromSql, IsNull, ToSql, Type};
+
+impl<'a> FromSql<'a> for Bytes {
+    fn from_sql(_: &Type, raw: &[u8]) -> Result<Bytes, Box<dyn Error + Sync + Send>> {
+        Ok(Bytes::from(types::bytea_from_sql(raw)))
+    }
+
+    accepts!(BYTEA);
+}
+
+impl ToSql for Bytes {
+    fn to_sql(&self, _: &Type, w: &mut Vec<u8>) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        types::bytea_to_sql(self, w);
+        Ok(IsNull::No)
+    }
+
+    accepts!(BYTEA);
+    to_sql_checked!();
+}
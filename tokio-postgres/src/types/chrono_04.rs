@@ -103,7 +103,13 @@ impl ToSql for DateTime<FixedOffset> {
 impl<'a> FromSql<'a> for NaiveDate {
     fn from_sql(_: &Type, raw: &[u8]) -> Result<NaiveDate, Box<dyn Error + Sync + Send>> {
         let jd = types::date_from_sql(raw)?;
-        Ok(base().date() + Duration::days(i64::from(jd)))
+        if jd == i32::max_value() || jd == i32::min_value() {
+            return Err("infinite date; use `Date<NaiveDate>` to represent infinity".into());
+        }
+        base()
+            .date()
+            .checked_add_signed(Duration::days(i64::from(jd)))
+            .ok_or_else(|| "value out of range".into())
     }
 
     accepts!(DATE);
@@ -0,0 +1,29 @@
+use std::error::Error;
+
+use crate::types::{FromSql, Type};
+
+/// The undecoded bytes of a text value.
+///
+/// Strings are always decoded as UTF-8, which matches the `UTF8` `client_encoding` the connection uses by default. If
+/// a different encoding is configured with `Config::client_encoding`, text values can be retrieved as this type
+/// instead and decoded by the caller.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EncodedText(pub Vec<u8>);
+
+impl<'a> FromSql<'a> for EncodedText {
+    fn from_sql(_: &Type, raw: &'a [u8]) -> Result<EncodedText, Box<dyn Error + Sync + Send>> {
+        Ok(EncodedText(raw.to_vec()))
+    }
+
+    fn from_sql_text(_: &Type, raw: &'a [u8]) -> Result<EncodedText, Box<dyn Error + Sync + Send>> {
+        Ok(EncodedText(raw.to_vec()))
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        match *ty {
+            Type::VARCHAR | Type::TEXT | Type::BPCHAR | Type::NAME | Type::UNKNOWN => true,
+            ref ty if ty.name() == "citext" => true,
+            _ => false,
+        }
+    }
+}
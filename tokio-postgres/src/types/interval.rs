@@ -0,0 +1,111 @@
+use postgres_protocol::types;
+use std::error::Error;
+use std::fmt;
+
+use crate::types::{FromSql, IsNull, ToSql, Type};
+
+const MICROS_PER_SECOND: u64 = 1_000_000;
+const MICROS_PER_MINUTE: u64 = 60 * MICROS_PER_SECOND;
+const MICROS_PER_HOUR: u64 = 60 * MICROS_PER_MINUTE;
+
+/// An `INTERVAL` value.
+///
+/// Postgres stores intervals as separate numbers of months, days and microseconds, since the lengths of months and
+/// days vary. The components are kept separate here as well, and may have different signs.
+///
+/// The `Display` implementation formats the interval as Postgres does in its default `postgres` interval style, for
+/// example `1 year 2 mons 3 days 04:05:06.5` or `-1 days +02:00:00`.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Interval {
+    /// The number of months.
+    pub months: i32,
+    /// The number of days.
+    pub days: i32,
+    /// The number of microseconds.
+    pub microseconds: i64,
+}
+
+impl fmt::Display for Interval {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut is_zero = true;
+        // a component following a negative one is explicitly signed
+        let mut is_before = false;
+
+        let parts = [
+            (self.months / 12, "year"),
+            (self.months % 12, "mon"),
+            (self.days, "day"),
+        ];
+        for &(value, unit) in &parts {
+            if value == 0 {
+                continue;
+            }
+            write!(
+                fmt,
+                "{}{}{} {}{}",
+                if is_zero { "" } else { " " },
+                if is_before && value > 0 { "+" } else { "" },
+                value,
+                unit,
+                if value == 1 { "" } else { "s" },
+            )?;
+            is_before = value < 0;
+            is_zero = false;
+        }
+
+        if is_zero || self.microseconds != 0 {
+            let sign = if self.microseconds < 0 {
+                "-"
+            } else if is_before {
+                "+"
+            } else {
+                ""
+            };
+            // negating in u64 handles i64::min_value(), whose magnitude doesn't fit in an i64
+            let time = if self.microseconds < 0 {
+                (self.microseconds as u64).wrapping_neg()
+            } else {
+                self.microseconds as u64
+            };
+            write!(
+                fmt,
+                "{}{}{:02}:{:02}:{:02}",
+                if is_zero { "" } else { " " },
+                sign,
+                time / MICROS_PER_HOUR,
+                time % MICROS_PER_HOUR / MICROS_PER_MINUTE,
+                time % MICROS_PER_MINUTE / MICROS_PER_SECOND,
+            )?;
+            let fraction = time % MICROS_PER_SECOND;
+            if fraction != 0 {
+                let fraction = format!("{:06}", fraction);
+                write!(fmt, ".{}", fraction.trim_end_matches('0'))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> FromSql<'a> for Interval {
+    fn from_sql(_: &Type, raw: &'a [u8]) -> Result<Interval, Box<dyn Error + Sync + Send>> {
+        let (microseconds, days, months) = types::interval_from_sql(raw)?;
+        Ok(Interval {
+            months,
+            days,
+            microseconds,
+        })
+    }
+
+    accepts!(INTERVAL);
+}
+
+impl ToSql for Interval {
+    fn to_sql(&self, _: &Type, out: &mut Vec<u8>) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        types::interval_to_sql(self.microseconds, self.days, self.months, out);
+        Ok(IsNull::No)
+    }
+
+    accepts!(INTERVAL);
+    to_sql_checked!();
+}
@@ -0,0 +1,432 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::{Debug, Write};
+use std::str;
+
+use crate::types::json_object::sealed::{JsonValue, Sealed};
+use crate::types::{FromSql, IsNull, ToSql, Type};
+
+/// A flat JSON object with values of a single type, converted to and from a `HashMap` without `serde`.
+///
+/// The values can be `String`s, `bool`s, `i32`s, `i64`s, `f64`s, or an `Option` of one of those to allow `null`
+/// values. Decoding fails if the value is not an object, or if any of its values are not of the expected type.
+///
+/// Requires the `json-object` Cargo feature.
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonObject<T>(pub HashMap<String, T>);
+
+/// A type which can be used as the values of a `JsonObject`.
+///
+/// This trait is sealed and cannot be implemented outside of this crate.
+pub trait JsonObjectValue: Sealed {}
+
+mod sealed {
+    use std::error::Error;
+
+    // A parsed JSON value. Arrays and objects are validated, but their contents are not kept since they can't be
+    // converted to any supported value type.
+    pub enum JsonValue<'a> {
+        Null,
+        Bool(bool),
+        Number(&'a str),
+        String(String),
+        Nested,
+    }
+
+    pub trait Sealed: Sized {
+        fn write(&self, out: &mut String) -> Result<(), Box<dyn Error + Sync + Send>>;
+
+        fn read(value: JsonValue<'_>) -> Option<Self>;
+    }
+}
+
+impl Sealed for String {
+    fn write(&self, out: &mut String) -> Result<(), Box<dyn Error + Sync + Send>> {
+        write_string(self, out);
+        Ok(())
+    }
+
+    fn read(value: JsonValue<'_>) -> Option<String> {
+        match value {
+            JsonValue::String(s) => Some(s),
+            _ => None,
+        }
+    }
+}
+
+impl JsonObjectValue for String {}
+
+impl Sealed for bool {
+    fn write(&self, out: &mut String) -> Result<(), Box<dyn Error + Sync + Send>> {
+        out.push_str(if *self { "true" } else { "false" });
+        Ok(())
+    }
+
+    fn read(value: JsonValue<'_>) -> Option<bool> {
+        match value {
+            JsonValue::Bool(b) => Some(b),
+            _ => None,
+        }
+    }
+}
+
+impl JsonObjectValue for bool {}
+
+macro_rules! integer_value {
+    ($($t:ty),+) => {
+        $(
+            impl Sealed for $t {
+                fn write(&self, out: &mut String) -> Result<(), Box<dyn Error + Sync + Send>> {
+                    write!(out, "{}", self)?;
+                    Ok(())
+                }
+
+                fn read(value: JsonValue<'_>) -> Option<$t> {
+                    match value {
+                        JsonValue::Number(n) => n.parse().ok(),
+                        _ => None,
+                    }
+                }
+            }
+
+            impl JsonObjectValue for $t {}
+        )+
+    }
+}
+
+integer_value!(i32, i64);
+
+impl Sealed for f64 {
+    fn write(&self, out: &mut String) -> Result<(), Box<dyn Error + Sync + Send>> {
+        if !self.is_finite() {
+            return Err("JSON cannot represent NaN or infinite numbers".into());
+        }
+        write!(out, "{}", self)?;
+        Ok(())
+    }
+
+    fn read(value: JsonValue<'_>) -> Option<f64> {
+        match value {
+            JsonValue::Number(n) => n.parse().ok(),
+            _ => None,
+        }
+    }
+}
+
+impl JsonObjectValue for f64 {}
+
+impl<T> Sealed for Option<T>
+where
+    T: Sealed,
+{
+    fn write(&self, out: &mut String) -> Result<(), Box<dyn Error + Sync + Send>> {
+        match *self {
+            Some(ref value) => value.write(out),
+            None => {
+                out.push_str("null");
+                Ok(())
+            }
+        }
+    }
+
+    fn read(value: JsonValue<'_>) -> Option<Option<T>> {
+        match value {
+            JsonValue::Null => Some(None),
+            value => T::read(value).map(Some),
+        }
+    }
+}
+
+impl<T> JsonObjectValue for Option<T> where T: JsonObjectValue {}
+
+impl<'a, T> FromSql<'a> for JsonObject<T>
+where
+    T: JsonObjectValue,
+{
+    fn from_sql(ty: &Type, raw: &'a [u8]) -> Result<JsonObject<T>, Box<dyn Error + Sync + Send>> {
+        let raw = if *ty == Type::JSONB {
+            // We only support version 1 of the jsonb binary format
+            match raw.split_first() {
+                Some((&1, rest)) => rest,
+                _ => return Err("unsupported JSONB encoding version".into()),
+            }
+        } else {
+            raw
+        };
+        JsonObject::from_sql_text(ty, raw)
+    }
+
+    fn from_sql_text(
+        _: &Type,
+        raw: &'a [u8],
+    ) -> Result<JsonObject<T>, Box<dyn Error + Sync + Send>> {
+        let mut parser = Parser {
+            s: str::from_utf8(raw)?,
+            pos: 0,
+        };
+        let map = parser.object()?;
+        parser.end()?;
+        Ok(JsonObject(map))
+    }
+
+    accepts!(JSON, JSONB);
+}
+
+impl<T> ToSql for JsonObject<T>
+where
+    T: JsonObjectValue + Debug,
+{
+    fn to_sql(&self, ty: &Type, out: &mut Vec<u8>) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        if *ty == Type::JSONB {
+            out.push(1);
+        }
+
+        let mut json = String::from("{");
+        for (i, (key, value)) in self.0.iter().enumerate() {
+            if i > 0 {
+                json.push(',');
+            }
+            write_string(key, &mut json);
+            json.push(':');
+            value.write(&mut json)?;
+        }
+        json.push('}');
+        out.extend_from_slice(json.as_bytes());
+
+        Ok(IsNull::No)
+    }
+
+    accepts!(JSON, JSONB);
+    to_sql_checked!();
+}
+
+fn write_string(s: &str, out: &mut String) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+struct Parser<'a> {
+    s: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<u8> {
+        self.s.as_bytes().get(self.pos).cloned()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(b' ') | Some(b'\t') | Some(b'\n') | Some(b'\r') = self.peek() {
+            self.pos += 1;
+        }
+    }
+
+    fn expect(&mut self, b: u8) -> Result<(), Box<dyn Error + Sync + Send>> {
+        self.skip_whitespace();
+        if self.peek() == Some(b) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(format!("invalid JSON: expected `{}`", b as char).into())
+        }
+    }
+
+    fn end(&mut self) -> Result<(), Box<dyn Error + Sync + Send>> {
+        self.skip_whitespace();
+        if self.pos == self.s.len() {
+            Ok(())
+        } else {
+            Err("invalid JSON: trailing characters".into())
+        }
+    }
+
+    fn object<T>(&mut self) -> Result<HashMap<String, T>, Box<dyn Error + Sync + Send>>
+    where
+        T: Sealed,
+    {
+        self.skip_whitespace();
+        if self.peek() != Some(b'{') {
+            return Err("JSON value is not an object".into());
+        }
+
+        let mut map = HashMap::new();
+        self.members(|key, value| {
+            let value = T::read(value).ok_or_else(|| {
+                format!("JSON object value for key `{}` has an unexpected type", key)
+            })?;
+            map.insert(key, value);
+            Ok(())
+        })?;
+        Ok(map)
+    }
+
+    // Parses an object, passing each of its members to the callback.
+    fn members<F>(&mut self, mut f: F) -> Result<(), Box<dyn Error + Sync + Send>>
+    where
+        F: FnMut(String, JsonValue<'a>) -> Result<(), Box<dyn Error + Sync + Send>>,
+    {
+        self.expect(b'{')?;
+        self.skip_whitespace();
+        if self.peek() == Some(b'}') {
+            self.pos += 1;
+            return Ok(());
+        }
+
+        loop {
+            self.expect(b'"')?;
+            let key = self.string()?;
+            self.expect(b':')?;
+            let value = self.value()?;
+            f(key, value)?;
+
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(b'}') => {
+                    self.pos += 1;
+                    return Ok(());
+                }
+                _ => return Err("invalid JSON: expected `,` or `}`".into()),
+            }
+        }
+    }
+
+    fn value(&mut self) -> Result<JsonValue<'a>, Box<dyn Error + Sync + Send>> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(b'"') => {
+                self.pos += 1;
+                self.string().map(JsonValue::String)
+            }
+            Some(b'{') => {
+                self.members(|_, _| Ok(()))?;
+                Ok(JsonValue::Nested)
+            }
+            Some(b'[') => {
+                self.array()?;
+                Ok(JsonValue::Nested)
+            }
+            Some(b't') => self.literal("true", JsonValue::Bool(true)),
+            Some(b'f') => self.literal("false", JsonValue::Bool(false)),
+            Some(b'n') => self.literal("null", JsonValue::Null),
+            Some(b'-') | Some(b'0'..=b'9') => self.number(),
+            _ => Err("invalid JSON: expected a value".into()),
+        }
+    }
+
+    fn array(&mut self) -> Result<(), Box<dyn Error + Sync + Send>> {
+        self.expect(b'[')?;
+        self.skip_whitespace();
+        if self.peek() == Some(b']') {
+            self.pos += 1;
+            return Ok(());
+        }
+
+        loop {
+            self.value()?;
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(b']') => {
+                    self.pos += 1;
+                    return Ok(());
+                }
+                _ => return Err("invalid JSON: expected `,` or `]`".into()),
+            }
+        }
+    }
+
+    fn literal(
+        &mut self,
+        literal: &str,
+        value: JsonValue<'a>,
+    ) -> Result<JsonValue<'a>, Box<dyn Error + Sync + Send>> {
+        if self.s[self.pos..].starts_with(literal) {
+            self.pos += literal.len();
+            Ok(value)
+        } else {
+            Err("invalid JSON: unknown literal".into())
+        }
+    }
+
+    fn number(&mut self) -> Result<JsonValue<'a>, Box<dyn Error + Sync + Send>> {
+        let start = self.pos;
+        while let Some(b'-') | Some(b'+') | Some(b'.') | Some(b'e') | Some(b'E')
+        | Some(b'0'..=b'9') = self.peek()
+        {
+            self.pos += 1;
+        }
+        let number = &self.s[start..self.pos];
+        if number.parse::<f64>().is_err() {
+            return Err("invalid JSON: malformed number".into());
+        }
+        Ok(JsonValue::Number(number))
+    }
+
+    // Parses the remainder of a string whose opening quote has already been consumed.
+    fn string(&mut self) -> Result<String, Box<dyn Error + Sync + Send>> {
+        let mut out = String::new();
+        loop {
+            let rest = &self.s[self.pos..];
+            let end = match rest.find(&['"', '\\'][..]) {
+                Some(end) => end,
+                None => return Err("invalid JSON: unterminated string".into()),
+            };
+            out.push_str(&rest[..end]);
+            self.pos += end + 1;
+            if rest.as_bytes()[end] == b'"' {
+                return Ok(out);
+            }
+
+            let escape = self.peek().ok_or("invalid JSON: unterminated string")?;
+            self.pos += 1;
+            match escape {
+                b'"' => out.push('"'),
+                b'\\' => out.push('\\'),
+                b'/' => out.push('/'),
+                b'b' => out.push('\u{8}'),
+                b'f' => out.push('\u{c}'),
+                b'n' => out.push('\n'),
+                b'r' => out.push('\r'),
+                b't' => out.push('\t'),
+                b'u' => {
+                    let mut c = self.hex4()?;
+                    if (0xd800..0xdc00).contains(&c) && self.s[self.pos..].starts_with("\\u") {
+                        self.pos += 2;
+                        let low = self.hex4()?;
+                        if !(0xdc00..0xe000).contains(&low) {
+                            return Err("invalid JSON: invalid unicode escape".into());
+                        }
+                        c = 0x10000 + ((c - 0xd800) << 10) + (low - 0xdc00);
+                    }
+                    out.push(std::char::from_u32(c).ok_or("invalid JSON: invalid unicode escape")?);
+                }
+                _ => return Err("invalid JSON: invalid escape".into()),
+            }
+        }
+    }
+
+    fn hex4(&mut self) -> Result<u32, Box<dyn Error + Sync + Send>> {
+        let digits = self
+            .s
+            .get(self.pos..self.pos + 4)
+            .ok_or("invalid JSON: invalid unicode escape")?;
+        let c =
+            u32::from_str_radix(digits, 16).map_err(|_| "invalid JSON: invalid unicode escape")?;
+        self.pos += 4;
+        Ok(c)
+    }
+}
@@ -0,0 +1,74 @@
+use postgres_protocol::types;
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::types::{FromSql, IsNull, ToSql, Type};
+
+/// A Postgres log sequence number, as stored by the `PG_LSN` type.
+///
+/// LSNs are ordered by their position in the write-ahead log, so they can be compared directly to measure
+/// replication progress. The `Display` and `FromStr` implementations use Postgres's `XXXXXXXX/XXXXXXXX` format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Lsn(u64);
+
+impl From<u64> for Lsn {
+    fn from(v: u64) -> Lsn {
+        Lsn(v)
+    }
+}
+
+impl From<Lsn> for u64 {
+    fn from(lsn: Lsn) -> u64 {
+        lsn.0
+    }
+}
+
+impl fmt::Display for Lsn {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(fmt, "{:X}/{:X}", self.0 >> 32, self.0 & 0xffff_ffff)
+    }
+}
+
+/// An error parsing an `Lsn` from a string.
+#[derive(Debug)]
+pub struct ParseLsnError(());
+
+impl fmt::Display for ParseLsnError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.write_str("invalid LSN")
+    }
+}
+
+impl Error for ParseLsnError {}
+
+impl FromStr for Lsn {
+    type Err = ParseLsnError;
+
+    fn from_str(s: &str) -> Result<Lsn, ParseLsnError> {
+        let mut it = s.splitn(2, '/');
+        let hi = it.next().ok_or(ParseLsnError(()))?;
+        let lo = it.next().ok_or(ParseLsnError(()))?;
+        let hi = u32::from_str_radix(hi, 16).map_err(|_| ParseLsnError(()))?;
+        let lo = u32::from_str_radix(lo, 16).map_err(|_| ParseLsnError(()))?;
+        Ok(Lsn((u64::from(hi) << 32) | u64::from(lo)))
+    }
+}
+
+impl<'a> FromSql<'a> for Lsn {
+    fn from_sql(_: &Type, raw: &'a [u8]) -> Result<Lsn, Box<dyn Error + Sync + Send>> {
+        types::lsn_from_sql(raw).map(Lsn)
+    }
+
+    accepts!(PG_LSN);
+}
+
+impl ToSql for Lsn {
+    fn to_sql(&self, _: &Type, out: &mut Vec<u8>) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        types::lsn_to_sql(self.0, out);
+        Ok(IsNull::No)
+    }
+
+    accepts!(PG_LSN);
+    to_sql_checked!();
+}
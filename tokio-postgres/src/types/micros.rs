@@ -0,0 +1,36 @@
+use postgres_protocol::types;
+use std::convert::TryFrom;
+use std::error::Error;
+use std::time::Duration;
+
+use crate::types::{FromSql, IsNull, ToSql, Type};
+
+/// A wrapper which stores a `Duration` as a `BIGINT` number of microseconds.
+///
+/// This is intended for schemas which store durations as plain integers rather than with the `INTERVAL` type.
+/// Precision below a microsecond is truncated when encoding. Durations too long to be represented by an `i64` number
+/// of microseconds cannot be encoded, and negative values cannot be decoded.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Micros(pub Duration);
+
+impl<'a> FromSql<'a> for Micros {
+    fn from_sql(_: &Type, raw: &'a [u8]) -> Result<Micros, Box<dyn Error + Sync + Send>> {
+        let micros = types::int8_from_sql(raw)?;
+        let micros = u64::try_from(micros).map_err(|_| "negative duration")?;
+        Ok(Micros(Duration::from_micros(micros)))
+    }
+
+    accepts!(INT8);
+}
+
+impl ToSql for Micros {
+    fn to_sql(&self, _: &Type, out: &mut Vec<u8>) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        let micros =
+            i64::try_from(self.0.as_micros()).map_err(|_| "value too large to transmit")?;
+        types::int8_to_sql(micros, out);
+        Ok(IsNull::No)
+    }
+
+    accepts!(INT8);
+    to_sql_checked!();
+}
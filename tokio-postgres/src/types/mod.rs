@@ -1,14 +1,33 @@
 //! Types.
+//!
+//! # Deriving
+//!
+//! With the `derive` Cargo feature enabled, `ToSql` and `FromSql` can be derived for newtypes wrapping a single
+//! value, which are then converted exactly like the wrapped type:
+//!
+//! ```ignore
+//! use tokio_postgres::types::{FromSql, ToSql};
+//!
+//! #[derive(Debug, ToSql, FromSql)]
+//! #[postgres(transparent)]
+//! struct UserId(i32);
+//! ```
+//!
+//! Only tuple structs with exactly one field are supported.
+//!
+//! The generated implementations refer to the `tokio_postgres` crate. When the derives are used through the `postgres`
+//! crate instead, add a `#[postgres(crate = "postgres")]` attribute to the type.
 
 use fallible_iterator::FallibleIterator;
 use postgres_protocol;
 use postgres_protocol::types::{self, ArrayDimension};
 use std::borrow::Cow;
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::error::Error;
 use std::fmt;
-use std::hash::BuildHasher;
+use std::hash::{BuildHasher, Hash};
 use std::net::IpAddr;
+use std::rc::Rc;
 use std::sync::Arc;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
@@ -17,13 +36,73 @@ use crate::types::type_gen::{Inner, Other};
 #[doc(inline)]
 pub use postgres_protocol::Oid;
 
+pub use crate::types::array::{Array, ArrayIter, Dimension};
+pub use crate::types::bits::Bits;
+pub use crate::types::encoded_text::EncodedText;
+pub use crate::types::interval::Interval;
+#[cfg(feature = "json-object")]
+pub use crate::types::json_object::{JsonObject, JsonObjectValue};
+pub use crate::types::lsn::{Lsn, ParseLsnError};
+pub use crate::types::micros::Micros;
+pub use crate::types::numeric_raw::NumericRaw;
+pub use crate::types::pg_timestamp::PgTimestamp;
+pub use crate::types::range::{Range, RangeBound};
+pub use crate::types::raw::Raw;
+pub use crate::types::record::Record;
 pub use crate::types::special::{Date, Timestamp};
+pub use crate::types::text_array::TextArray;
+pub use crate::types::timestamp_millis::TimestampMillis;
+pub use crate::types::txid_snapshot::TxidSnapshot;
+#[cfg(feature = "derive")]
+pub use postgres_derive::{FromSql, ToSql};
 
 // Number of seconds from 1970-01-01 to 2000-01-01
 const TIME_SEC_CONVERSION: u64 = 946_684_800;
 const USEC_PER_SEC: u64 = 1_000_000;
 const NSEC_PER_USEC: u64 = 1_000;
 
+// Postgres timestamps are microseconds since midnight, January 1st 2000
+pub(crate) fn system_time_from_micros(time: i64) -> SystemTime {
+    let epoch = UNIX_EPOCH + Duration::from_secs(TIME_SEC_CONVERSION);
+
+    let negative = time < 0;
+    let time = time.abs() as u64;
+
+    let secs = time / USEC_PER_SEC;
+    let nsec = (time % USEC_PER_SEC) * NSEC_PER_USEC;
+    let offset = Duration::new(secs, nsec as u32);
+
+    if negative {
+        epoch - offset
+    } else {
+        epoch + offset
+    }
+}
+
+pub(crate) fn system_time_to_micros(time: SystemTime) -> i64 {
+    let epoch = UNIX_EPOCH + Duration::from_secs(TIME_SEC_CONVERSION);
+
+    let to_usec =
+        |d: Duration| d.as_secs() * USEC_PER_SEC + u64::from(d.subsec_nanos()) / NSEC_PER_USEC;
+
+    match time.duration_since(epoch) {
+        Ok(duration) => to_usec(duration) as i64,
+        Err(e) => -(to_usec(e.duration()) as i64),
+    }
+}
+
+// Returns the format values of a type are requested in when it is returned from a query and no format was
+// explicitly requested. Values are requested in the binary format unless the type has no binary output function.
+pub(crate) fn result_format(ty: &Type) -> Format {
+    match *ty.kind() {
+        Kind::Array(ref member) | Kind::Domain(ref member) => result_format(member),
+        _ => match *ty {
+            Type::ACLITEM => Format::Text,
+            _ => Format::Binary,
+        },
+    }
+}
+
 /// Generates a simple implementation of `ToSql::accepts` which accepts the
 /// types passed to it.
 #[macro_export]
@@ -75,18 +154,42 @@ where
 
 #[cfg(feature = "with-bit-vec-0_5")]
 mod bit_vec_05;
+#[cfg(feature = "with-bytes-0_4")]
+mod bytes_04;
 #[cfg(feature = "with-chrono-0_4")]
 mod chrono_04;
 #[cfg(feature = "with-eui48-0_4")]
 mod eui48_04;
 #[cfg(feature = "with-geo-types-0_4")]
 mod geo_types_04;
+#[cfg(feature = "with-ndarray-0_12")]
+mod ndarray_012;
 #[cfg(feature = "with-serde_json-1")]
 mod serde_json_1;
+#[cfg(feature = "with-time-0_1")]
+mod time_01;
 #[cfg(feature = "with-uuid-0_7")]
 mod uuid_07;
 
+mod array;
+mod bits;
+mod encoded_text;
+mod interval;
+#[cfg(feature = "json-object")]
+mod json_object;
+mod lsn;
+mod micros;
+mod numeric_raw;
+mod pg_timestamp;
+mod range;
+mod raw;
+mod record;
 mod special;
+#[cfg(all(test, feature = "runtime"))]
+mod test_util;
+mod text_array;
+mod timestamp_millis;
+mod txid_snapshot;
 mod type_gen;
 
 #[cfg(feature = "with-serde_json-1")]
@@ -238,18 +341,34 @@ impl WrongType {
 /// | Rust type                         | Postgres type(s)                              |
 /// |-----------------------------------|-----------------------------------------------|
 /// | `bool`                            | BOOL                                          |
+/// | `()`                              | VOID                                          |
 /// | `i8`                              | "char"                                        |
 /// | `i16`                             | SMALLINT, SMALLSERIAL                         |
 /// | `i32`                             | INT, SERIAL                                   |
-/// | `u32`                             | OID                                           |
+/// | `u32`                             | OID, REGCLASS, REGPROC, and other OID aliases |
 /// | `i64`                             | BIGINT, BIGSERIAL                             |
 /// | `f32`                             | REAL                                          |
 /// | `f64`                             | DOUBLE PRECISION                              |
 /// | `&str`/`String`                   | VARCHAR, CHAR(n), TEXT, CITEXT, NAME, UNKNOWN |
+/// | `&str`/`String`                   | REFCURSOR, ACLITEM, JSONPATH                  |
+/// | `&str`/`String`                   | Enums, decoded as the label of the value      |
 /// | `&[u8]`/`Vec<u8>`                 | BYTEA                                         |
+/// | `[u8; N]` (N up to 32, or 64)     | BYTEA                                         |
 /// | `HashMap<String, Option<String>>` | HSTORE                                        |
 /// | `SystemTime`                      | TIMESTAMP, TIMESTAMP WITH TIME ZONE           |
 /// | `IpAddr`                          | INET                                          |
+/// | `Lsn`                             | PG_LSN                                        |
+/// | `Micros`                          | BIGINT                                        |
+/// | `Interval`                        | INTERVAL                                      |
+/// | `TimestampMillis`                 | TIMESTAMP WITH TIME ZONE                      |
+/// | `PgTimestamp`                     | TIMESTAMP, TIMESTAMP WITH TIME ZONE           |
+/// | `NumericRaw`                      | NUMERIC                                       |
+/// | `Bits`                            | BIT, VARBIT                                   |
+/// | `Record`                          | RECORD                                        |
+/// | `Range<T>`                        | Ranges of T, such as TSTZRANGE                |
+/// | `TxidSnapshot`                    | TXID_SNAPSHOT                                 |
+/// | `TextArray`                       | Arrays in the text format, such as ACLITEM[]  |
+/// | `EncodedText`                     | VARCHAR, CHAR(n), TEXT, CITEXT, NAME, UNKNOWN |
 ///
 /// In addition, some implementations are provided for types in third party
 /// crates. These are disabled by default; to opt into one of these
@@ -270,9 +389,39 @@ impl WrongType {
 /// | `geo_types::Rect<f64>`          | BOX                                 |
 /// | `geo_types::LineString<f64>`    | PATH                                |
 /// | `serde_json::Value`             | JSON, JSONB                         |
+/// | `&serde_json::value::RawValue`  | JSON, JSONB                         |
+/// | `time::Duration`                | INTERVAL                            |
 /// | `uuid::Uuid`                    | UUID                                |
 /// | `bit_vec::BitVec`               | BIT, VARBIT                         |
 /// | `eui48::MacAddress`             | MACADDR                             |
+/// | `bytes::Bytes`                  | BYTEA                               |
+/// | `ndarray::Array1<T>`            | T[] with one dimension              |
+/// | `ndarray::Array2<T>`            | T[] with two dimensions             |
+///
+/// `bytes::Bytes` values are copied out of the row. `Row::get_bytes` can be used to retrieve them without copying.
+///
+/// The `json-object` feature enables `JsonObject<T>`, which converts flat JSON and JSONB objects to and from a
+/// `HashMap<String, T>` without depending on `serde`.
+///
+/// # Padding
+///
+/// Values of type `CHAR(n)` are returned exactly as Postgres sends them,
+/// padded with trailing spaces to `n` characters. Use `str::trim_end` if the
+/// padding is not wanted. Values of other text types, such as `VARCHAR` and
+/// `TEXT`, are never padded or trimmed.
+///
+/// # Borrowing
+///
+/// The `&str` and `&[u8]` implementations borrow directly from the buffer
+/// backing the row rather than allocating. The UTF-8 validity of text values
+/// is still checked, but no copy is made, so the resulting references are
+/// valid for as long as the row they were retrieved from.
+///
+/// # Encoding
+///
+/// Strings are always decoded as UTF-8, which matches the `UTF8` `client_encoding` the connection uses by default.
+/// If a different encoding is configured with `Config::client_encoding`, the raw bytes of text values can be
+/// retrieved as `EncodedText` instead and decoded by the caller.
 ///
 /// # Nullability
 ///
@@ -284,6 +433,20 @@ impl WrongType {
 ///
 /// `FromSql` is implemented for `Vec<T>` where `T` implements `FromSql`, and
 /// corresponds to one-dimensional Postgres arrays.
+/// One-dimensional arrays can also be collected into a `HashSet<T>` or
+/// `BTreeSet<T>`, discarding duplicate elements. Arrays containing `NULL`
+/// elements can only be retrieved if `T` is an `Option`.
+/// Arrays with multiple dimensions or lower bounds other than 1 can be
+/// retrieved with the `Array<T>` type.
+/// The `INT2VECTOR` and `OIDVECTOR` catalog types can be retrieved as
+/// `Vec<i16>` and `Vec<u32>` respectively.
+///
+/// # Formats
+///
+/// Values are retrieved in the binary format unless the type has no binary format, like `ACLITEM`, or the text format
+/// was requested with `Client::prepare_with_result_formats`. Values of columns whose `Column::format` is
+/// `Format::Text` are decoded with `from_sql_text`, which only some implementations support. The text format of any
+/// value can be retrieved as a `&str` or `String`.
 pub trait FromSql<'a>: Sized {
     /// Creates a new value of this type from a buffer of data of the specified
     /// Postgres `Type` in its binary format.
@@ -315,9 +478,102 @@ pub trait FromSql<'a>: Sized {
         }
     }
 
+    /// Creates a new value of this type from a buffer of data of the specified
+    /// Postgres `Type` in its text format.
+    ///
+    /// The caller of this method is responsible for ensuring that this type
+    /// is compatible with the Postgres `Type`.
+    ///
+    /// The default implementation returns an error, as most types are only
+    /// decoded from the binary format.
+    fn from_sql_text(ty: &Type, raw: &'a [u8]) -> Result<Self, Box<dyn Error + Sync + Send>> {
+        let _ = raw;
+        Err(format!("the text format of {} values is not supported", ty).into())
+    }
+
+    /// A convenience function that delegates to `from_sql_text` and `from_sql_null` depending on the
+    /// value of `raw`.
+    fn from_sql_text_nullable(
+        ty: &Type,
+        raw: Option<&'a [u8]>,
+    ) -> Result<Self, Box<dyn Error + Sync + Send>> {
+        match raw {
+            Some(raw) => Self::from_sql_text(ty, raw),
+            None => Self::from_sql_null(ty),
+        }
+    }
+
     /// Determines if a value of this type can be created from the specified
     /// Postgres `Type`.
     fn accepts(ty: &Type) -> bool;
+
+    #[doc(hidden)]
+    fn __from_sql_array(
+        member_type: &Type,
+        array: types::Array<'a>,
+    ) -> Result<Vec<Self>, Box<dyn Error + Sync + Send>> {
+        array
+            .values()
+            .map(|v| Self::from_sql_nullable(member_type, v))
+            .collect()
+    }
+
+    #[doc(hidden)]
+    fn __from_sql_array_nullable(
+        member_type: &Type,
+        array: types::Array<'a>,
+    ) -> Result<Vec<Option<Self>>, Box<dyn Error + Sync + Send>> {
+        array
+            .values()
+            .map(|v| Option::<Self>::from_sql_nullable(member_type, v))
+            .collect()
+    }
+}
+
+// Decodes the elements of a one-dimensional array of a fixed-width type. Element lengths are checked against `width`
+// and the bytes decoded directly, instead of dispatching through `from_sql_nullable` for every element. `NULL`
+// elements are delegated to `null`, so they are handled exactly as in the generic path.
+fn fixed_width_array<'a, T, F, N>(
+    array: types::Array<'a>,
+    width: usize,
+    decode: F,
+    null: N,
+) -> Result<Vec<T>, Box<dyn Error + Sync + Send>>
+where
+    F: Fn(&'a [u8]) -> Result<T, Box<dyn Error + Sync + Send>>,
+    N: Fn() -> Result<T, Box<dyn Error + Sync + Send>>,
+{
+    let mut values = array.values();
+    let mut out = Vec::with_capacity(values.size_hint().0);
+    while let Some(value) = values.next()? {
+        let value = match value {
+            Some(raw) if raw.len() == width => decode(raw)?,
+            Some(_) => return Err("invalid buffer size".into()),
+            None => null()?,
+        };
+        out.push(value);
+    }
+    Ok(out)
+}
+
+macro_rules! fixed_width_array {
+    ($t:ty, $width:expr, $decode:expr) => {
+        fn __from_sql_array(
+            member_type: &Type,
+            array: types::Array<'a>,
+        ) -> Result<Vec<$t>, Box<dyn Error + Sync + Send>> {
+            fixed_width_array(array, $width, $decode, || {
+                <$t as FromSql>::from_sql_null(member_type)
+            })
+        }
+
+        fn __from_sql_array_nullable(
+            _: &Type,
+            array: types::Array<'a>,
+        ) -> Result<Vec<Option<$t>>, Box<dyn Error + Sync + Send>> {
+            fixed_width_array(array, $width, |raw| ($decode)(raw).map(Some), || Ok(None))
+        }
+    };
 }
 
 /// A trait for types which can be created from a Postgres value without borrowing any data.
@@ -332,6 +588,10 @@ impl<'a, T: FromSql<'a>> FromSql<'a> for Option<T> {
         <T as FromSql>::from_sql(ty, raw).map(Some)
     }
 
+    fn from_sql_text(ty: &Type, raw: &'a [u8]) -> Result<Option<T>, Box<dyn Error + Sync + Send>> {
+        <T as FromSql>::from_sql_text(ty, raw).map(Some)
+    }
+
     fn from_sql_null(_: &Type) -> Result<Option<T>, Box<dyn Error + Sync + Send>> {
         Ok(None)
     }
@@ -339,6 +599,13 @@ impl<'a, T: FromSql<'a>> FromSql<'a> for Option<T> {
     fn accepts(ty: &Type) -> bool {
         <T as FromSql>::accepts(ty)
     }
+
+    fn __from_sql_array(
+        member_type: &Type,
+        array: types::Array<'a>,
+    ) -> Result<Vec<Option<T>>, Box<dyn Error + Sync + Send>> {
+        T::__from_sql_array_nullable(member_type, array)
+    }
 }
 
 impl<'a, T: FromSql<'a>> FromSql<'a> for Vec<T> {
@@ -353,10 +620,11 @@ impl<'a, T: FromSql<'a>> FromSql<'a> for Vec<T> {
             return Err("array contains too many dimensions".into());
         }
 
-        array
-            .values()
-            .map(|v| T::from_sql_nullable(member_type, v))
-            .collect()
+        T::__from_sql_array(member_type, array)
+    }
+
+    fn from_sql_text(_: &Type, _: &'a [u8]) -> Result<Vec<T>, Box<dyn Error + Sync + Send>> {
+        Err("arrays in the text format can only be decoded as TextArray".into())
     }
 
     fn accepts(ty: &Type) -> bool {
@@ -367,12 +635,45 @@ impl<'a, T: FromSql<'a>> FromSql<'a> for Vec<T> {
     }
 }
 
+impl<'a, T, S> FromSql<'a> for HashSet<T, S>
+where
+    T: FromSql<'a> + Eq + Hash,
+    S: Default + BuildHasher,
+{
+    fn from_sql(ty: &Type, raw: &'a [u8]) -> Result<HashSet<T, S>, Box<dyn Error + Sync + Send>> {
+        Vec::<T>::from_sql(ty, raw).map(|v| v.into_iter().collect())
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        <Vec<T> as FromSql>::accepts(ty)
+    }
+}
+
+impl<'a, T> FromSql<'a> for BTreeSet<T>
+where
+    T: FromSql<'a> + Ord,
+{
+    fn from_sql(ty: &Type, raw: &'a [u8]) -> Result<BTreeSet<T>, Box<dyn Error + Sync + Send>> {
+        Vec::<T>::from_sql(ty, raw).map(|v| v.into_iter().collect())
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        <Vec<T> as FromSql>::accepts(ty)
+    }
+}
+
 impl<'a> FromSql<'a> for Vec<u8> {
-    fn from_sql(_: &Type, raw: &'a [u8]) -> Result<Vec<u8>, Box<dyn Error + Sync + Send>> {
-        Ok(types::bytea_from_sql(raw).to_owned())
+    fn from_sql(ty: &Type, raw: &'a [u8]) -> Result<Vec<u8>, Box<dyn Error + Sync + Send>> {
+        <&[u8] as FromSql>::from_sql(ty, raw).map(ToOwned::to_owned)
     }
 
-    accepts!(BYTEA);
+    fn from_sql_text(_: &Type, raw: &'a [u8]) -> Result<Vec<u8>, Box<dyn Error + Sync + Send>> {
+        types::bytea_from_text(raw)
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        <&[u8] as FromSql>::accepts(ty)
+    }
 }
 
 impl<'a> FromSql<'a> for &'a [u8] {
@@ -383,9 +684,40 @@ impl<'a> FromSql<'a> for &'a [u8] {
     accepts!(BYTEA);
 }
 
+// fixed size arrays are only supported up to a handful of lengths, since their trait implementations can't be generic
+// over the length
+macro_rules! byte_array_from {
+    ($($n:expr),+) => {
+        $(
+            impl<'a> FromSql<'a> for [u8; $n] {
+                fn from_sql(_: &Type, raw: &'a [u8]) -> Result<[u8; $n], Box<dyn Error + Sync + Send>> {
+                    let bytes = types::bytea_from_sql(raw);
+                    if bytes.len() != $n {
+                        return Err(format!("expected {} bytes but got {}", $n, bytes.len()).into());
+                    }
+                    let mut array = [0; $n];
+                    array.copy_from_slice(bytes);
+                    Ok(array)
+                }
+
+                accepts!(BYTEA);
+            }
+        )+
+    }
+}
+
+byte_array_from!(
+    1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 26,
+    27, 28, 29, 30, 31, 32, 64
+);
+
 impl<'a> FromSql<'a> for String {
-    fn from_sql(_: &Type, raw: &'a [u8]) -> Result<String, Box<dyn Error + Sync + Send>> {
-        types::text_from_sql(raw).map(ToString::to_string)
+    fn from_sql(ty: &Type, raw: &'a [u8]) -> Result<String, Box<dyn Error + Sync + Send>> {
+        <&str as FromSql>::from_sql(ty, raw).map(ToString::to_string)
+    }
+
+    fn from_sql_text(ty: &Type, raw: &'a [u8]) -> Result<String, Box<dyn Error + Sync + Send>> {
+        <&str as FromSql>::from_sql_text(ty, raw).map(ToString::to_string)
     }
 
     fn accepts(ty: &Type) -> bool {
@@ -394,39 +726,122 @@ impl<'a> FromSql<'a> for String {
 }
 
 impl<'a> FromSql<'a> for &'a str {
-    fn from_sql(_: &Type, raw: &'a [u8]) -> Result<&'a str, Box<dyn Error + Sync + Send>> {
+    fn from_sql(ty: &Type, mut raw: &'a [u8]) -> Result<&'a str, Box<dyn Error + Sync + Send>> {
+        if *ty == Type::JSONPATH {
+            // We only support version 1 of the jsonpath binary format
+            match raw.split_first() {
+                Some((&1, rest)) => raw = rest,
+                _ => return Err("unsupported JSONPATH encoding version".into()),
+            }
+        }
+        types::text_from_sql(raw)
+    }
+
+    fn from_sql_text(_: &Type, raw: &'a [u8]) -> Result<&'a str, Box<dyn Error + Sync + Send>> {
         types::text_from_sql(raw)
     }
 
     fn accepts(ty: &Type) -> bool {
         match *ty {
             Type::VARCHAR | Type::TEXT | Type::BPCHAR | Type::NAME | Type::UNKNOWN => true,
+            Type::REFCURSOR | Type::JSONPATH => true,
+            // ACLITEM has no binary format and is always retrieved in its text format
+            Type::ACLITEM => true,
             ref ty if ty.name() == "citext" => true,
-            _ => false,
+            // enum values are sent as their labels
+            ref ty => match *ty.kind() {
+                Kind::Enum(_) => true,
+                _ => false,
+            },
         }
     }
 }
 
+impl<'a> FromSql<'a> for bool {
+    fn from_sql(_: &Type, raw: &'a [u8]) -> Result<bool, Box<dyn Error + Sync + Send>> {
+        match raw {
+            [1] => Ok(true),
+            [0] => Ok(false),
+            [_] => Err("invalid BOOL value".into()),
+            _ => Err("invalid buffer size".into()),
+        }
+    }
+
+    fn from_sql_text(_: &Type, raw: &'a [u8]) -> Result<bool, Box<dyn Error + Sync + Send>> {
+        match raw {
+            b"t" => Ok(true),
+            b"f" => Ok(false),
+            _ => Err("invalid BOOL value".into()),
+        }
+    }
+
+    accepts!(BOOL);
+
+    fixed_width_array!(bool, 1, |raw| bool::from_sql(&Type::BOOL, raw));
+}
+
+impl<'a> FromSql<'a> for () {
+    fn from_sql(_: &Type, _: &'a [u8]) -> Result<(), Box<dyn Error + Sync + Send>> {
+        Ok(())
+    }
+
+    accepts!(VOID);
+}
+
 macro_rules! simple_from {
-    ($t:ty, $f:ident, $($expected:ident),+) => {
+    ($t:ty, $f:ident, $width:expr, $from_bytes:expr, $($expected:ident),+) => {
         impl<'a> FromSql<'a> for $t {
+            #[inline]
             fn from_sql(_: &Type, raw: &'a [u8]) -> Result<$t, Box<dyn Error + Sync + Send>> {
                 types::$f(raw)
             }
 
             accepts!($($expected),+);
+
+            fixed_width_array!($t, $width, |raw: &[u8]| {
+                let mut bytes = [0; $width];
+                bytes.copy_from_slice(raw);
+                Ok($from_bytes(bytes))
+            });
         }
     }
 }
 
-simple_from!(bool, bool_from_sql, BOOL);
-simple_from!(i8, char_from_sql, CHAR);
-simple_from!(i16, int2_from_sql, INT2);
-simple_from!(i32, int4_from_sql, INT4);
-simple_from!(u32, oid_from_sql, OID);
-simple_from!(i64, int8_from_sql, INT8);
-simple_from!(f32, float4_from_sql, FLOAT4);
-simple_from!(f64, float8_from_sql, FLOAT8);
+simple_from!(i8, char_from_sql, 1, i8::from_be_bytes, CHAR);
+simple_from!(i16, int2_from_sql, 2, i16::from_be_bytes, INT2);
+simple_from!(i32, int4_from_sql, 4, i32::from_be_bytes, INT4);
+simple_from!(
+    u32,
+    oid_from_sql,
+    4,
+    u32::from_be_bytes,
+    OID,
+    REGPROC,
+    REGPROCEDURE,
+    REGOPER,
+    REGOPERATOR,
+    REGCLASS,
+    REGTYPE,
+    REGCONFIG,
+    REGDICTIONARY,
+    REGNAMESPACE,
+    REGROLE
+);
+simple_from!(i64, int8_from_sql, 8, i64::from_be_bytes, INT8);
+simple_from!(
+    f32,
+    float4_from_sql,
+    4,
+    |bytes| f32::from_bits(u32::from_be_bytes(bytes)),
+    FLOAT4
+);
+simple_from!(
+    f64,
+    float8_from_sql,
+    8,
+    |bytes| f64::from_bits(u64::from_be_bytes(bytes)),
+    FLOAT8
+);
 
 impl<'a, S> FromSql<'a> for HashMap<String, Option<String>, S>
 where
@@ -449,22 +864,7 @@ where
 impl<'a> FromSql<'a> for SystemTime {
     fn from_sql(_: &Type, raw: &'a [u8]) -> Result<SystemTime, Box<dyn Error + Sync + Send>> {
         let time = types::timestamp_from_sql(raw)?;
-        let epoch = UNIX_EPOCH + Duration::from_secs(TIME_SEC_CONVERSION);
-
-        let negative = time < 0;
-        let time = time.abs() as u64;
-
-        let secs = time / USEC_PER_SEC;
-        let nsec = (time % USEC_PER_SEC) * NSEC_PER_USEC;
-        let offset = Duration::new(secs, nsec as u32);
-
-        let time = if negative {
-            epoch - offset
-        } else {
-            epoch + offset
-        };
-
-        Ok(time)
+        Ok(system_time_from_micros(time))
     }
 
     accepts!(TIMESTAMP, TIMESTAMPTZ);
@@ -487,6 +887,15 @@ pub enum IsNull {
     No,
 }
 
+/// The format of a value sent to or received from Postgres.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Format {
+    /// The text format.
+    Text,
+    /// The binary format.
+    Binary,
+}
+
 /// A trait for types that can be converted into Postgres values.
 ///
 /// # Types
@@ -500,15 +909,26 @@ pub enum IsNull {
 /// | `i8`                              | "char"                               |
 /// | `i16`                             | SMALLINT, SMALLSERIAL                |
 /// | `i32`                             | INT, SERIAL                          |
-/// | `u32`                             | OID                                  |
+/// | `u32`                             | OID, REGCLASS, REGPROC, etc.         |
 /// | `i64`                             | BIGINT, BIGSERIAL                    |
 /// | `f32`                             | REAL                                 |
 /// | `f64`                             | DOUBLE PRECISION                     |
 /// | `&str`/`String`                   | VARCHAR, CHAR(n), TEXT, CITEXT, NAME |
+/// | `&str`/`String`                   | REFCURSOR, JSONPATH                  |
 /// | `&[u8]`/Vec<u8>`                  | BYTEA                                |
+/// | `[u8; N]` (N up to 32, or 64)     | BYTEA                                |
 /// | `HashMap<String, Option<String>>` | HSTORE                               |
 /// | `SystemTime`                      | TIMESTAMP, TIMESTAMP WITH TIME ZONE  |
 /// | `IpAddr`                          | INET                                 |
+/// | `Lsn`                             | PG_LSN                               |
+/// | `Micros`                          | BIGINT                               |
+/// | `Interval`                        | INTERVAL                             |
+/// | `TimestampMillis`                 | TIMESTAMP WITH TIME ZONE             |
+/// | `PgTimestamp`                     | TIMESTAMP, TIMESTAMP WITH TIME ZONE  |
+/// | `NumericRaw`                      | NUMERIC                              |
+/// | `Bits`                            | BIT, VARBIT                          |
+/// | `Range<T>`                        | Ranges of T, such as TSTZRANGE       |
+/// | `TxidSnapshot`                    | TXID_SNAPSHOT                        |
 ///
 /// In addition, some implementations are provided for types in third party
 /// crates. These are disabled by default; to opt into one of these
@@ -529,9 +949,14 @@ pub enum IsNull {
 /// | `geo_types::Rect<f64>`          | BOX                                 |
 /// | `geo_types::LineString<f64>`    | PATH                                |
 /// | `serde_json::Value`             | JSON, JSONB                         |
+/// | `time::Duration`                | INTERVAL                            |
 /// | `uuid::Uuid`                    | UUID                                |
 /// | `bit_vec::BitVec`               | BIT, VARBIT                         |
 /// | `eui48::MacAddress`             | MACADDR                             |
+/// | `bytes::Bytes`                  | BYTEA                               |
+///
+/// The `json-object` feature enables `JsonObject<T>`, which converts flat JSON and JSONB objects to and from a
+/// `HashMap<String, T>` without depending on `serde`.
 ///
 /// # Nullability
 ///
@@ -539,10 +964,25 @@ pub enum IsNull {
 /// `Option<T>` where `T` implements `ToSql`. An `Option<T>` represents a
 /// nullable Postgres value.
 ///
+/// # Pointers
+///
+/// `ToSql` is implemented for `Box<T>`, `Rc<T>`, and `Arc<T>` where `T`
+/// implements `ToSql`, including trait objects like `Box<dyn ToSql>`. This
+/// allows lists of heterogeneous parameters to be built dynamically.
+///
 /// # Arrays
 ///
 /// `ToSql` is implemented for `Vec<T>` and `&[T]` where `T` implements `ToSql`,
 /// and corresponds to one-dimensional Postgres arrays with an index offset of 1.
+/// Arrays containing `NULL` elements can be sent with an element type of
+/// `Option<T>`, such as `Vec<Option<i32>>`.
+/// Arrays with multiple dimensions or lower bounds other than 1 can be sent
+/// with the `Array<T>` type.
+/// The `ArrayIter` wrapper can be used to send the elements of an iterator as
+/// an array without first collecting them.
+/// The `INT2VECTOR` and `OIDVECTOR` types are sent with an index offset of 0,
+/// as the server requires. Note that the server rejects empty vectors sent in
+/// the binary format.
 pub trait ToSql: fmt::Debug {
     /// Converts the value of `self` into the binary format of the specified
     /// Postgres `Type`, appending it to `out`.
@@ -572,6 +1012,13 @@ pub trait ToSql: fmt::Debug {
         ty: &Type,
         out: &mut Vec<u8>,
     ) -> Result<IsNull, Box<dyn Error + Sync + Send>>;
+
+    /// Returns the format in which `to_sql` encodes the value.
+    ///
+    /// Defaults to `Format::Binary`. Only values passed directly as query parameters may use `Format::Text`.
+    fn encode_format(&self) -> Format {
+        Format::Binary
+    }
 }
 
 impl<'a, T> ToSql for &'a T
@@ -586,9 +1033,51 @@ where
         T::accepts(ty)
     }
 
+    fn encode_format(&self) -> Format {
+        (*self).encode_format()
+    }
+
     to_sql_checked!();
 }
 
+macro_rules! deref_to {
+    ($($t:ident),+) => {
+        $(
+            impl<T> ToSql for $t<T>
+            where
+                T: ToSql + ?Sized,
+            {
+                fn to_sql(
+                    &self,
+                    ty: &Type,
+                    out: &mut Vec<u8>,
+                ) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+                    (**self).to_sql_checked(ty, out)
+                }
+
+                // the inner value may be unsized, so its type is checked in `to_sql_checked` instead
+                fn accepts(_: &Type) -> bool {
+                    true
+                }
+
+                fn to_sql_checked(
+                    &self,
+                    ty: &Type,
+                    out: &mut Vec<u8>,
+                ) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+                    (**self).to_sql_checked(ty, out)
+                }
+
+                fn encode_format(&self) -> Format {
+                    (**self).encode_format()
+                }
+            }
+        )+
+    };
+}
+
+deref_to!(Box, Rc, Arc);
+
 impl<T: ToSql> ToSql for Option<T> {
     fn to_sql(&self, ty: &Type, out: &mut Vec<u8>) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
         match *self {
@@ -601,6 +1090,13 @@ impl<T: ToSql> ToSql for Option<T> {
         <T as ToSql>::accepts(ty)
     }
 
+    fn encode_format(&self) -> Format {
+        match *self {
+            Some(ref val) => val.encode_format(),
+            None => Format::Binary,
+        }
+    }
+
     to_sql_checked!();
 }
 
@@ -613,7 +1109,7 @@ impl<'a, T: ToSql> ToSql for &'a [T] {
 
         let dimension = ArrayDimension {
             len: downcast(self.len())?,
-            lower_bound: 1,
+            lower_bound: default_lower_bound(ty),
         };
 
         types::array_to_sql(
@@ -674,8 +1170,34 @@ impl ToSql for Vec<u8> {
     to_sql_checked!();
 }
 
+macro_rules! byte_array_to {
+    ($($n:expr),+) => {
+        $(
+            impl ToSql for [u8; $n] {
+                fn to_sql(&self, ty: &Type, w: &mut Vec<u8>) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+                    <&[u8] as ToSql>::to_sql(&&self[..], ty, w)
+                }
+
+                fn accepts(ty: &Type) -> bool {
+                    <&[u8] as ToSql>::accepts(ty)
+                }
+
+                to_sql_checked!();
+            }
+        )+
+    }
+}
+
+byte_array_to!(
+    1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 26,
+    27, 28, 29, 30, 31, 32, 64
+);
+
 impl<'a> ToSql for &'a str {
-    fn to_sql(&self, _: &Type, w: &mut Vec<u8>) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+    fn to_sql(&self, ty: &Type, w: &mut Vec<u8>) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        if *ty == Type::JSONPATH {
+            w.push(1);
+        }
         types::text_to_sql(*self, w);
         Ok(IsNull::No)
     }
@@ -683,6 +1205,7 @@ impl<'a> ToSql for &'a str {
     fn accepts(ty: &Type) -> bool {
         match *ty {
             Type::VARCHAR | Type::TEXT | Type::BPCHAR | Type::NAME | Type::UNKNOWN => true,
+            Type::REFCURSOR | Type::JSONPATH => true,
             ref ty if ty.name() == "citext" => true,
             _ => false,
         }
@@ -737,7 +1260,21 @@ simple_to!(bool, bool_to_sql, BOOL);
 simple_to!(i8, char_to_sql, CHAR);
 simple_to!(i16, int2_to_sql, INT2);
 simple_to!(i32, int4_to_sql, INT4);
-simple_to!(u32, oid_to_sql, OID);
+simple_to!(
+    u32,
+    oid_to_sql,
+    OID,
+    REGPROC,
+    REGPROCEDURE,
+    REGOPER,
+    REGOPERATOR,
+    REGCLASS,
+    REGTYPE,
+    REGCONFIG,
+    REGDICTIONARY,
+    REGNAMESPACE,
+    REGROLE
+);
 simple_to!(i64, int8_to_sql, INT8);
 simple_to!(f32, float4_to_sql, FLOAT4);
 simple_to!(f64, float8_to_sql, FLOAT8);
@@ -763,17 +1300,7 @@ where
 
 impl ToSql for SystemTime {
     fn to_sql(&self, _: &Type, w: &mut Vec<u8>) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
-        let epoch = UNIX_EPOCH + Duration::from_secs(TIME_SEC_CONVERSION);
-
-        let to_usec =
-            |d: Duration| d.as_secs() * USEC_PER_SEC + u64::from(d.subsec_nanos()) / NSEC_PER_USEC;
-
-        let time = match self.duration_since(epoch) {
-            Ok(duration) => to_usec(duration) as i64,
-            Err(e) => -(to_usec(e.duration()) as i64),
-        };
-
-        types::timestamp_to_sql(time, w);
+        types::timestamp_to_sql(system_time_to_micros(*self), w);
         Ok(IsNull::No)
     }
 
@@ -797,6 +1324,14 @@ impl ToSql for IpAddr {
     to_sql_checked!();
 }
 
+// the vector types are zero-indexed, and the server rejects them otherwise
+fn default_lower_bound(ty: &Type) -> i32 {
+    match *ty {
+        Type::INT2_VECTOR | Type::OID_VECTOR => 0,
+        _ => 1,
+    }
+}
+
 fn downcast(len: usize) -> Result<i32, Box<dyn Error + Sync + Send>> {
     if len > i32::max_value() as usize {
         Err("value too large to transmit".into())
@@ -0,0 +1,76 @@
+use fallible_iterator::FallibleIterator;
+use ndarray_012::{Array, Array1, Array2, Dimension, ShapeBuilder};
+use postgres_protocol::types;
+use std::error::Error;
+
+use crate::types::{FromSql, Kind, Type, WrongType};
+
+impl<'a, T: FromSql<'a>> FromSql<'a> for Array1<T> {
+    fn from_sql(ty: &Type, raw: &'a [u8]) -> Result<Array1<T>, Box<dyn Error + Sync + Send>> {
+        array_from_sql(ty, raw, |shape| match *shape {
+            [] => Some(0),
+            [len] => Some(len),
+            _ => None,
+        })
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        accepts::<T>(ty)
+    }
+}
+
+impl<'a, T: FromSql<'a>> FromSql<'a> for Array2<T> {
+    fn from_sql(ty: &Type, raw: &'a [u8]) -> Result<Array2<T>, Box<dyn Error + Sync + Send>> {
+        array_from_sql(ty, raw, |shape| match *shape {
+            [] => Some((0, 0)),
+            [rows, cols] => Some((rows, cols)),
+            _ => None,
+        })
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        accepts::<T>(ty)
+    }
+}
+
+fn accepts<'a, T: FromSql<'a>>(ty: &Type) -> bool {
+    match *ty.kind() {
+        Kind::Array(ref inner) => T::accepts(inner),
+        _ => false,
+    }
+}
+
+// Postgres arrays are stored in row-major order, so the values can be collected directly into an array of the
+// matching shape. An empty array has no dimensions at all.
+fn array_from_sql<'a, T, D, S, F>(
+    ty: &Type,
+    raw: &'a [u8],
+    shape: F,
+) -> Result<Array<T, D>, Box<dyn Error + Sync + Send>>
+where
+    T: FromSql<'a>,
+    D: Dimension,
+    S: ShapeBuilder<Dim = D>,
+    F: FnOnce(&[usize]) -> Option<S>,
+{
+    let member_type = match *ty.kind() {
+        Kind::Array(ref member) => member,
+        _ => panic!("expected array type"),
+    };
+
+    let array = types::array_from_sql(raw)?;
+    let dimensions = array
+        .dimensions()
+        .map(|d| Ok(d.len as usize))
+        .collect::<Vec<_>>()?;
+    let shape = match shape(&dimensions) {
+        Some(shape) => shape,
+        None => return Err(Box::new(WrongType::new(ty.clone()))),
+    };
+
+    let values = array
+        .values()
+        .map(|v| T::from_sql_nullable(member_type, v))
+        .collect()?;
+    Ok(Array::from_shape_vec(shape, values)?)
+}
@@ -0,0 +1,221 @@
+use std::convert::TryFrom;
+use std::error::Error;
+
+use crate::types::{FromSql, IsNull, ToSql, Type};
+
+const NUMERIC_NEG: u16 = 0x4000;
+const NUMERIC_NAN: u16 = 0xC000;
+const NUMERIC_MAX_SCALE: u32 = 0x3FFF;
+
+/// A `NUMERIC` value decoded into an integer mantissa and a decimal scale, without a decimal crate.
+///
+/// The value is `mantissa × 10^-scale`, so `12.50` is represented with a mantissa of `1250` and a scale of `2`.
+/// Decoding preserves the scale of the value as sent by the server. Values whose mantissa does not fit in an `i128`,
+/// as well as `NaN` and infinities, cannot be decoded.
+///
+/// Values retrieved in the text format are parsed from their decimal representation (`[-]digits[.digits]`).
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct NumericRaw {
+    /// The digits of the value as an integer.
+    pub mantissa: i128,
+    /// The number of digits after the decimal point.
+    pub scale: u32,
+}
+
+impl<'a> FromSql<'a> for NumericRaw {
+    fn from_sql(_: &Type, raw: &'a [u8]) -> Result<NumericRaw, Box<dyn Error + Sync + Send>> {
+        if raw.len() < 8 {
+            return Err("invalid buffer size".into());
+        }
+        let ndigits = i16::from_be_bytes([raw[0], raw[1]]);
+        let weight = i16::from_be_bytes([raw[2], raw[3]]);
+        let sign = u16::from_be_bytes([raw[4], raw[5]]);
+        let scale = u16::from_be_bytes([raw[6], raw[7]]);
+
+        match sign {
+            0 | NUMERIC_NEG => {}
+            NUMERIC_NAN => return Err("NUMERIC value is NaN".into()),
+            _ => return Err("NUMERIC value is infinite or has an invalid sign".into()),
+        }
+        if ndigits < 0 || raw.len() != 8 + ndigits as usize * 2 {
+            return Err("invalid buffer size".into());
+        }
+
+        // the digits have this many decimal places, which may differ from the scale of the value
+        let places = 4 * (i32::from(ndigits) - 1 - i32::from(weight));
+        let shift = i32::from(scale) - places;
+        // the last digit may be padded with zeros past the scale, and those can't be allowed to overflow the mantissa
+        if shift < -3 {
+            return Err("invalid NUMERIC scale".into());
+        }
+
+        let out_of_range = || "NUMERIC value out of range for NumericRaw";
+        let mut mantissa = 0u128;
+        for (i, digit) in raw[8..].chunks(2).enumerate() {
+            let mut digit = i16::from_be_bytes([digit[0], digit[1]]);
+            if !(0..10000).contains(&digit) {
+                return Err("invalid NUMERIC digit".into());
+            }
+            let mut base = 10000;
+            if i == ndigits as usize - 1 && shift < 0 {
+                let p = 10i16.pow(-shift as u32);
+                if digit % p != 0 {
+                    return Err("invalid NUMERIC scale".into());
+                }
+                digit /= p;
+                base /= p as u128;
+            }
+            mantissa = mantissa
+                .checked_mul(base)
+                .and_then(|m| m.checked_add(digit as u128))
+                .ok_or_else(out_of_range)?;
+        }
+
+        if ndigits == 0 {
+            mantissa = 0;
+        } else if shift > 0 {
+            mantissa = 10u128
+                .checked_pow(shift as u32)
+                .and_then(|p| mantissa.checked_mul(p))
+                .ok_or_else(out_of_range)?;
+        }
+
+        Ok(NumericRaw {
+            mantissa: signed(mantissa, sign == NUMERIC_NEG)?,
+            scale: u32::from(scale),
+        })
+    }
+
+    fn from_sql_text(_: &Type, raw: &'a [u8]) -> Result<NumericRaw, Box<dyn Error + Sync + Send>> {
+        from_text(raw)
+    }
+
+    accepts!(NUMERIC);
+}
+
+fn from_text(raw: &[u8]) -> Result<NumericRaw, Box<dyn Error + Sync + Send>> {
+    let s = std::str::from_utf8(raw)?;
+    match s {
+        "NaN" => return Err("NUMERIC value is NaN".into()),
+        "Infinity" | "-Infinity" => return Err("NUMERIC value is infinite".into()),
+        _ => {}
+    }
+
+    let (negative, s) = if s.starts_with('-') {
+        (true, &s[1..])
+    } else {
+        (false, s)
+    };
+    let (int_part, frac_part) = match s.find('.') {
+        Some(i) => (&s[..i], &s[i + 1..]),
+        None => (s, ""),
+    };
+    if int_part.is_empty()
+        || !int_part.bytes().all(|b| b.is_ascii_digit())
+        || !frac_part.bytes().all(|b| b.is_ascii_digit())
+        || (frac_part.is_empty() && s.len() != int_part.len())
+    {
+        return Err("invalid NUMERIC text".into());
+    }
+
+    let scale = frac_part.len() as u32;
+    if scale > NUMERIC_MAX_SCALE {
+        return Err("invalid NUMERIC scale".into());
+    }
+
+    let mut mantissa = 0u128;
+    for b in int_part.bytes().chain(frac_part.bytes()) {
+        mantissa = mantissa
+            .checked_mul(10)
+            .and_then(|m| m.checked_add(u128::from(b - b'0')))
+            .ok_or("NUMERIC value out of range for NumericRaw")?;
+    }
+
+    Ok(NumericRaw {
+        mantissa: signed(mantissa, negative)?,
+        scale,
+    })
+}
+
+fn signed(magnitude: u128, negative: bool) -> Result<i128, Box<dyn Error + Sync + Send>> {
+    let out_of_range = "NUMERIC value out of range for NumericRaw";
+    // the magnitude of i128::min_value() is one larger than that of i128::max_value()
+    if negative {
+        if magnitude > i128::max_value() as u128 + 1 {
+            return Err(out_of_range.into());
+        }
+        Ok((magnitude as i128).wrapping_neg())
+    } else {
+        i128::try_from(magnitude).map_err(|_| out_of_range.into())
+    }
+}
+
+impl ToSql for NumericRaw {
+    fn to_sql(&self, _: &Type, out: &mut Vec<u8>) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        if self.scale > NUMERIC_MAX_SCALE {
+            return Err("NUMERIC scale too large".into());
+        }
+        let scale = self.scale as usize;
+
+        // split the decimal digits into base 10000 digits aligned on the decimal point
+        // negating in u128 handles i128::min_value(), whose magnitude doesn't fit in an i128
+        let magnitude = if self.mantissa < 0 {
+            (self.mantissa as u128).wrapping_neg()
+        } else {
+            self.mantissa as u128
+        };
+        let decimal = magnitude.to_string();
+        let (int_part, frac_part) = if decimal.len() > scale {
+            decimal.split_at(decimal.len() - scale)
+        } else {
+            ("", &*decimal)
+        };
+        let int_pad = (4 - int_part.len() % 4) % 4;
+        let frac_lead = scale - frac_part.len();
+        let frac_pad = (4 - scale % 4) % 4;
+
+        let mut padded = String::with_capacity(int_pad + decimal.len() + frac_lead + frac_pad);
+        padded.extend((0..int_pad).map(|_| '0'));
+        padded.push_str(int_part);
+        padded.extend((0..frac_lead).map(|_| '0'));
+        padded.push_str(frac_part);
+        padded.extend((0..frac_pad).map(|_| '0'));
+
+        let mut digits = padded
+            .as_bytes()
+            .chunks(4)
+            .map(|chunk| {
+                chunk
+                    .iter()
+                    .fold(0i16, |d, &b| d * 10 + i16::from(b - b'0'))
+            })
+            .collect::<Vec<_>>();
+        let mut weight = (int_pad + int_part.len()) as i32 / 4 - 1;
+
+        let leading = digits.iter().take_while(|&&d| d == 0).count();
+        digits.drain(..leading);
+        weight -= leading as i32;
+        while digits.last() == Some(&0) {
+            digits.pop();
+        }
+        if digits.is_empty() {
+            weight = 0;
+        }
+
+        let sign = if self.mantissa < 0 { NUMERIC_NEG } else { 0 };
+        let weight = i16::try_from(weight).map_err(|_| "NUMERIC value out of range")?;
+
+        out.extend_from_slice(&(digits.len() as i16).to_be_bytes());
+        out.extend_from_slice(&weight.to_be_bytes());
+        out.extend_from_slice(&sign.to_be_bytes());
+        out.extend_from_slice(&(self.scale as u16).to_be_bytes());
+        for digit in digits {
+            out.extend_from_slice(&digit.to_be_bytes());
+        }
+
+        Ok(IsNull::No)
+    }
+
+    accepts!(NUMERIC);
+    to_sql_checked!();
+}
@@ -0,0 +1,42 @@
+use postgres_protocol::types;
+use std::error::Error;
+
+use crate::types::{FromSql, IsNull, ToSql, Type};
+
+/// A wrapper which stores a `TIMESTAMP` or `TIMESTAMP WITH TIME ZONE` as the raw number of microseconds since
+/// 2000-01-01 00:00:00 UTC, Postgres's own epoch.
+///
+/// No conversion is performed in either direction, so every value, including `infinity` and `-infinity`, round-trips
+/// exactly. Note that the epoch differs from the Unix epoch: add `PgTimestamp::UNIX_EPOCH_OFFSET_MICROS` to convert
+/// a value to microseconds since 1970-01-01.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PgTimestamp(pub i64);
+
+impl PgTimestamp {
+    /// The number of microseconds from 1970-01-01 to 2000-01-01, or 946,684,800 seconds.
+    pub const UNIX_EPOCH_OFFSET_MICROS: i64 = 946_684_800_000_000;
+
+    /// The `infinity` timestamp.
+    pub const INFINITY: PgTimestamp = PgTimestamp(i64::max_value());
+
+    /// The `-infinity` timestamp.
+    pub const NEG_INFINITY: PgTimestamp = PgTimestamp(i64::min_value());
+}
+
+impl<'a> FromSql<'a> for PgTimestamp {
+    fn from_sql(_: &Type, raw: &'a [u8]) -> Result<PgTimestamp, Box<dyn Error + Sync + Send>> {
+        types::timestamp_from_sql(raw).map(PgTimestamp)
+    }
+
+    accepts!(TIMESTAMP, TIMESTAMPTZ);
+}
+
+impl ToSql for PgTimestamp {
+    fn to_sql(&self, _: &Type, out: &mut Vec<u8>) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        types::timestamp_to_sql(self.0, out);
+        Ok(IsNull::No)
+    }
+
+    accepts!(TIMESTAMP, TIMESTAMPTZ);
+    to_sql_checked!();
+}
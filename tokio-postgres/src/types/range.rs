@@ -0,0 +1,114 @@
+use postgres_protocol::types;
+use std::error::Error;
+
+use crate::types::{FromSql, IsNull, Kind, ToSql, Type};
+
+/// A Postgres range value, such as a `TSTZRANGE` or `DATERANGE`.
+///
+/// The bounds are decoded and encoded with the implementations for the range's element type, so for example a
+/// `TSTZRANGE` can be retrieved as a `Range<SystemTime>`. Whether each bound is inclusive is preserved exactly, but
+/// note that Postgres normalizes ranges of discrete types such as `DATERANGE` to the `[)` form, and normalizes ranges
+/// containing no values to `empty`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Range<T> {
+    /// The empty range.
+    Empty,
+    /// A range with a lower and an upper bound.
+    Nonempty(RangeBound<T>, RangeBound<T>),
+}
+
+/// One side of a `Range`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum RangeBound<T> {
+    /// A bound which includes its value.
+    Inclusive(T),
+    /// A bound which excludes its value.
+    Exclusive(T),
+    /// A side of the range with no bound.
+    Unbounded,
+}
+
+impl<'a, T: FromSql<'a>> FromSql<'a> for Range<T> {
+    fn from_sql(ty: &Type, raw: &'a [u8]) -> Result<Range<T>, Box<dyn Error + Sync + Send>> {
+        let member_type = match *ty.kind() {
+            Kind::Range(ref member) => member,
+            _ => panic!("expected range type"),
+        };
+
+        match types::range_from_sql(raw)? {
+            types::Range::Empty => Ok(Range::Empty),
+            types::Range::Nonempty(lower, upper) => Ok(Range::Nonempty(
+                bound_from_sql(member_type, lower)?,
+                bound_from_sql(member_type, upper)?,
+            )),
+        }
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        match *ty.kind() {
+            Kind::Range(ref inner) => T::accepts(inner),
+            _ => false,
+        }
+    }
+}
+
+fn bound_from_sql<'a, T: FromSql<'a>>(
+    ty: &Type,
+    bound: types::RangeBound<Option<&'a [u8]>>,
+) -> Result<RangeBound<T>, Box<dyn Error + Sync + Send>> {
+    match bound {
+        types::RangeBound::Inclusive(raw) => {
+            T::from_sql_nullable(ty, raw).map(RangeBound::Inclusive)
+        }
+        types::RangeBound::Exclusive(raw) => {
+            T::from_sql_nullable(ty, raw).map(RangeBound::Exclusive)
+        }
+        types::RangeBound::Unbounded => Ok(RangeBound::Unbounded),
+    }
+}
+
+impl<T: ToSql> ToSql for Range<T> {
+    fn to_sql(&self, ty: &Type, out: &mut Vec<u8>) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        let member_type = match *ty.kind() {
+            Kind::Range(ref member) => member,
+            _ => panic!("expected range type"),
+        };
+
+        match self {
+            Range::Empty => types::empty_range_to_sql(out),
+            Range::Nonempty(lower, upper) => types::range_to_sql(
+                |buf| bound_to_sql(member_type, lower, buf),
+                |buf| bound_to_sql(member_type, upper, buf),
+                out,
+            )?,
+        }
+
+        Ok(IsNull::No)
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        match *ty.kind() {
+            Kind::Range(ref inner) => T::accepts(inner),
+            _ => false,
+        }
+    }
+
+    to_sql_checked!();
+}
+
+fn bound_to_sql<T: ToSql>(
+    ty: &Type,
+    bound: &RangeBound<T>,
+    buf: &mut Vec<u8>,
+) -> Result<types::RangeBound<postgres_protocol::IsNull>, Box<dyn Error + Sync + Send>> {
+    let is_null = |value: &T, buf: &mut Vec<u8>| match value.to_sql(ty, buf)? {
+        IsNull::No => Ok(postgres_protocol::IsNull::No),
+        IsNull::Yes => Ok(postgres_protocol::IsNull::Yes),
+    };
+
+    match bound {
+        RangeBound::Inclusive(value) => is_null(value, buf).map(types::RangeBound::Inclusive),
+        RangeBound::Exclusive(value) => is_null(value, buf).map(types::RangeBound::Exclusive),
+        RangeBound::Unbounded => Ok(types::RangeBound::Unbounded),
+    }
+}
@@ -0,0 +1,51 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::types::{Format, IsNull, ToSql, Type, WrongType};
+
+/// A query parameter containing a value which has already been encoded.
+///
+/// This is an explicit escape hatch for sending values of types which are not otherwise supported, such as those
+/// defined by extensions. The bytes are sent unchanged in the specified format.
+///
+/// Since `ToSql::accepts` only sees the parameter's type and not the value, it accepts every type. The exact
+/// `Type` is checked when the value is encoded instead, and a `WrongType` error is returned for any other type.
+///
+/// The bytes can be owned or borrowed, so a `Raw<&[u8]>` can be used to send a value which is already in memory
+/// without allocating a new buffer for each execution.
+///
+/// Text-format values can only be passed directly as query parameters. In particular, they cannot be used as elements
+/// of arrays.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Raw<B = Vec<u8>> {
+    /// The Postgres type of the value.
+    pub type_: Type,
+    /// The format of the encoded value.
+    pub format: Format,
+    /// The encoded value.
+    pub bytes: B,
+}
+
+impl<B> ToSql for Raw<B>
+where
+    B: AsRef<[u8]> + fmt::Debug,
+{
+    fn to_sql(&self, ty: &Type, out: &mut Vec<u8>) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        if *ty != self.type_ {
+            return Err(Box::new(WrongType::new(ty.clone())));
+        }
+
+        out.extend_from_slice(self.bytes.as_ref());
+        Ok(IsNull::No)
+    }
+
+    fn accepts(_: &Type) -> bool {
+        true
+    }
+
+    fn encode_format(&self) -> Format {
+        self.format
+    }
+
+    to_sql_checked!();
+}
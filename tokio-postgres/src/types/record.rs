@@ -0,0 +1,90 @@
+use fallible_iterator::FallibleIterator;
+use postgres_protocol::types;
+use std::error::Error;
+
+use crate::types::{FromSql, Oid, Type, WrongType};
+
+/// An anonymous composite value, such as one produced by a `ROW(...)` expression or a function returning `record`.
+///
+/// Unlike named composite types, the fields of a record are sent along with their type OIDs, so they can be decoded
+/// without a type lookup. Only fields of built-in types can be decoded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Record<'a> {
+    fields: Vec<(Oid, Option<&'a [u8]>)>,
+}
+
+impl<'a> Record<'a> {
+    /// Returns the number of fields in the record.
+    pub fn len(&self) -> usize {
+        self.fields.len()
+    }
+
+    /// Determines if the record has no fields.
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+
+    /// Returns the type OID of a field.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is out of bounds.
+    pub fn oid(&self, idx: usize) -> Oid {
+        self.fields[idx].0
+    }
+
+    /// Determines if a field is `NULL`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is out of bounds.
+    pub fn is_null(&self, idx: usize) -> bool {
+        self.fields[idx].1.is_none()
+    }
+
+    /// Deserializes a field of the record.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is out of bounds or if the value cannot be converted to the specified type.
+    pub fn get<T>(&self, idx: usize) -> T
+    where
+        T: FromSql<'a>,
+    {
+        match self.try_get(idx) {
+            Ok(ok) => ok,
+            Err(err) => panic!("error retrieving record field {}: {}", idx, err),
+        }
+    }
+
+    /// Like `Record::get`, but returns a `Result` rather than panicking.
+    pub fn try_get<T>(&self, idx: usize) -> Result<T, Box<dyn Error + Sync + Send>>
+    where
+        T: FromSql<'a>,
+    {
+        let (oid, raw) = match self.fields.get(idx) {
+            Some(field) => *field,
+            None => return Err("record field index out of bounds".into()),
+        };
+
+        let ty = match Type::from_oid(oid) {
+            Some(ty) => ty,
+            None => return Err(format!("unknown type OID {} in record field {}", oid, idx).into()),
+        };
+        if !T::accepts(&ty) {
+            return Err(Box::new(WrongType::new(ty)));
+        }
+
+        T::from_sql_nullable(&ty, raw)
+    }
+}
+
+impl<'a> FromSql<'a> for Record<'a> {
+    fn from_sql(_: &Type, raw: &'a [u8]) -> Result<Record<'a>, Box<dyn Error + Sync + Send>> {
+        let record = types::record_from_sql(raw)?;
+        let fields = record.fields().collect()?;
+        Ok(Record { fields })
+    }
+
+    accepts!(RECORD);
+}
@@ -1,4 +1,5 @@
 use serde_1::{Deserialize, Serialize};
+use serde_json_1::value::RawValue;
 use serde_json_1::Value;
 use std::error::Error;
 use std::fmt::Debug;
@@ -7,6 +8,9 @@ use std::io::Read;
 use crate::types::{FromSql, IsNull, ToSql, Type};
 
 /// A wrapper type to allow arbitrary `Serialize`/`Deserialize` types to convert to Postgres JSON values.
+///
+/// For example, `Json<HashMap<String, String>>` converts a JSON object with string values to and from a map. Decoding
+/// fails if the value is not an object or if any of its values are not strings.
 #[derive(Debug)]
 pub struct Json<T>(pub T);
 
@@ -14,16 +18,8 @@ impl<'a, T> FromSql<'a> for Json<T>
 where
     T: Deserialize<'a>,
 {
-    fn from_sql(ty: &Type, mut raw: &'a [u8]) -> Result<Json<T>, Box<dyn Error + Sync + Send>> {
-        if *ty == Type::JSONB {
-            let mut b = [0; 1];
-            raw.read_exact(&mut b)?;
-            // We only support version 1 of the jsonb binary format
-            if b[0] != 1 {
-                return Err("unsupported JSONB encoding version".into());
-            }
-        }
-        serde_json_1::de::from_slice(raw)
+    fn from_sql(ty: &Type, raw: &'a [u8]) -> Result<Json<T>, Box<dyn Error + Sync + Send>> {
+        serde_json_1::de::from_slice(json_body(ty, raw)?)
             .map(Json)
             .map_err(Into::into)
     }
@@ -63,3 +59,26 @@ impl ToSql for Value {
     accepts!(JSON, JSONB);
     to_sql_checked!();
 }
+
+// Borrows the JSON text from the row so documents can be relayed without building a `Value`. The text is checked to
+// be valid JSON, but is not otherwise parsed.
+impl<'a> FromSql<'a> for &'a RawValue {
+    fn from_sql(ty: &Type, raw: &'a [u8]) -> Result<&'a RawValue, Box<dyn Error + Sync + Send>> {
+        serde_json_1::de::from_slice(json_body(ty, raw)?).map_err(Into::into)
+    }
+
+    accepts!(JSON, JSONB);
+}
+
+// Strips the version header from a JSONB value.
+fn json_body<'a>(ty: &Type, mut raw: &'a [u8]) -> Result<&'a [u8], Box<dyn Error + Sync + Send>> {
+    if *ty == Type::JSONB {
+        let mut b = [0; 1];
+        raw.read_exact(&mut b)?;
+        // We only support version 1 of the jsonb binary format
+        if b[0] != 1 {
+            return Err("unsupported JSONB encoding version".into());
+        }
+    }
+    Ok(raw)
+}
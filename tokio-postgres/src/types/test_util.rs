@@ -0,0 +1,128 @@
+//! Helpers for testing `ToSql` and `FromSql` implementations against a server.
+
+use futures::{Future, Stream};
+use std::f32;
+use std::f64;
+use std::fmt;
+use std::time::{Duration, UNIX_EPOCH};
+use tokio::runtime::current_thread::Runtime;
+
+use crate::types::{FromSqlOwned, ToSql, Type};
+use crate::{Client, NoTls};
+
+/// A connection to the test database.
+pub struct TestConnection {
+    runtime: Runtime,
+    client: Client,
+}
+
+impl TestConnection {
+    pub fn new() -> TestConnection {
+        let mut runtime = Runtime::new().unwrap();
+
+        let handshake = crate::connect("host=localhost port=5433 user=postgres", NoTls);
+        let (client, connection) = runtime.block_on(handshake).unwrap();
+        let connection = connection.map_err(|e| panic!("{}", e));
+        runtime.spawn(connection);
+
+        TestConnection { runtime, client }
+    }
+}
+
+/// Sends `value` to the server as a parameter of type `ty`, reads it back, and asserts that it is unchanged.
+pub fn assert_roundtrip<T>(conn: &mut TestConnection, ty: &Type, value: &T)
+where
+    T: PartialEq + fmt::Debug + FromSqlOwned + ToSql,
+{
+    let prepare = conn
+        .client
+        .prepare_typed("SELECT $1", std::slice::from_ref(ty));
+    let stmt = conn.runtime.block_on(prepare).unwrap();
+
+    let query = conn.client.query(&stmt, &[value]).collect();
+    let rows = conn.runtime.block_on(query).unwrap();
+    let result = rows[0].get::<_, T>(0);
+    assert_eq!(value, &result, "{} round trip", ty);
+}
+
+#[test]
+fn scalar_roundtrips() {
+    let mut conn = TestConnection::new();
+
+    for value in &[Some(true), Some(false), None] {
+        assert_roundtrip(&mut conn, &Type::BOOL, value);
+    }
+    for value in &[Some(-128i8), Some(-1), Some(0), Some(1), Some(127)] {
+        assert_roundtrip(&mut conn, &Type::CHAR, value);
+    }
+    for value in &[
+        Some(-32768i16),
+        Some(-1),
+        Some(0),
+        Some(1),
+        Some(0x0102),
+        Some(32767),
+    ] {
+        assert_roundtrip(&mut conn, &Type::INT2, value);
+    }
+    for value in &[
+        Some(-2_147_483_648i32),
+        Some(-1),
+        Some(0),
+        Some(0x0102_0304),
+        Some(2_147_483_647),
+    ] {
+        assert_roundtrip(&mut conn, &Type::INT4, value);
+    }
+    for value in &[
+        Some(-9_223_372_036_854_775_808i64),
+        Some(-1),
+        Some(0),
+        Some(0x0102_0304_0506_0708),
+        Some(9_223_372_036_854_775_807),
+    ] {
+        assert_roundtrip(&mut conn, &Type::INT8, value);
+    }
+    for value in &[Some(0u32), Some(0x0102_0304), Some(4_294_967_295)] {
+        assert_roundtrip(&mut conn, &Type::OID, value);
+    }
+    for value in &[
+        Some(f32::MIN_POSITIVE),
+        Some(-0.5f32),
+        Some(f32::EPSILON),
+        Some(f32::MAX),
+        Some(f32::MIN),
+    ] {
+        assert_roundtrip(&mut conn, &Type::FLOAT4, value);
+    }
+    for value in &[
+        Some(f64::MIN_POSITIVE),
+        Some(-0.5f64),
+        Some(f64::EPSILON),
+        Some(f64::MAX),
+        Some(f64::MIN),
+    ] {
+        assert_roundtrip(&mut conn, &Type::FLOAT8, value);
+    }
+    for value in &[
+        Some(String::new()),
+        Some("\u{1f418} élephant".to_string()),
+        Some("a".repeat(100_000)),
+    ] {
+        assert_roundtrip(&mut conn, &Type::TEXT, value);
+    }
+    for value in &[
+        Some(vec![]),
+        Some((0..=255u8).collect::<Vec<u8>>()),
+        Some(vec![0; 100_000]),
+    ] {
+        assert_roundtrip(&mut conn, &Type::BYTEA, value);
+    }
+    for value in &[
+        Some(UNIX_EPOCH),
+        Some(UNIX_EPOCH - Duration::from_micros(1)),
+        Some(UNIX_EPOCH + Duration::from_micros(0x0102_0304_0506)),
+    ] {
+        assert_roundtrip(&mut conn, &Type::TIMESTAMP, value);
+    }
+}
@@ -0,0 +1,98 @@
+use std::error::Error;
+use std::str;
+
+use crate::types::{FromSql, Kind, Type};
+
+/// A one-dimensional array whose elements are kept in their text representation.
+///
+/// Arrays retrieved in the text format, such as arrays of `ACLITEM`, which has no binary format, can only be decoded
+/// as this type. `NULL` elements are represented by `None`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextArray(pub Vec<Option<String>>);
+
+impl<'a> FromSql<'a> for TextArray {
+    fn from_sql(_: &Type, _: &'a [u8]) -> Result<TextArray, Box<dyn Error + Sync + Send>> {
+        Err("TextArray can only be decoded from the text format".into())
+    }
+
+    fn from_sql_text(_: &Type, raw: &'a [u8]) -> Result<TextArray, Box<dyn Error + Sync + Send>> {
+        let s = str::from_utf8(raw)?;
+        parse(s).map(TextArray)
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        match *ty.kind() {
+            Kind::Array(_) => true,
+            _ => false,
+        }
+    }
+}
+
+fn parse(s: &str) -> Result<Vec<Option<String>>, Box<dyn Error + Sync + Send>> {
+    // skip any explicit dimension decoration, e.g. `[0:1]={a,b}`
+    let s = match s.find('=') {
+        Some(idx) if s.starts_with('[') => &s[idx + 1..],
+        _ => s,
+    };
+
+    let mut chars = s.trim().chars().peekable();
+    if chars.next() != Some('{') {
+        return Err("invalid array literal".into());
+    }
+
+    let mut elements = vec![];
+    if chars.peek() == Some(&'}') {
+        chars.next();
+    } else {
+        loop {
+            let element = match chars.peek() {
+                Some('"') => {
+                    chars.next();
+                    let mut element = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('"') => break,
+                            Some('\\') => match chars.next() {
+                                Some(c) => element.push(c),
+                                None => return Err("invalid array literal".into()),
+                            },
+                            Some(c) => element.push(c),
+                            None => return Err("invalid array literal".into()),
+                        }
+                    }
+                    Some(element)
+                }
+                Some('{') => return Err("multidimensional arrays are not supported".into()),
+                _ => {
+                    let mut element = String::new();
+                    while let Some(&c) = chars.peek() {
+                        if c == ',' || c == '}' {
+                            break;
+                        }
+                        element.push(c);
+                        chars.next();
+                    }
+                    let element = element.trim();
+                    if element.eq_ignore_ascii_case("NULL") {
+                        None
+                    } else {
+                        Some(element.to_string())
+                    }
+                }
+            };
+            elements.push(element);
+
+            match chars.next() {
+                Some(',') => {}
+                Some('}') => break,
+                _ => return Err("invalid array literal".into()),
+            }
+        }
+    }
+
+    if chars.next().is_some() {
+        return Err("invalid array literal".into());
+    }
+
+    Ok(elements)
+}
@@ -0,0 +1,34 @@
+use postgres_protocol::types;
+use std::error::Error;
+use time_01::Duration;
+
+use crate::types::{FromSql, IsNull, ToSql, Type};
+
+impl<'a> FromSql<'a> for Duration {
+    fn from_sql(_: &Type, raw: &[u8]) -> Result<Duration, Box<dyn Error + Sync + Send>> {
+        let (microseconds, days, months) = types::interval_from_sql(raw)?;
+        if months != 0 {
+            return Err(
+                "interval has a nonzero months component, which cannot be converted to a fixed duration"
+                    .into(),
+            );
+        }
+        Ok(Duration::days(i64::from(days)) + Duration::microseconds(microseconds))
+    }
+
+    accepts!(INTERVAL);
+}
+
+impl ToSql for Duration {
+    fn to_sql(&self, _: &Type, w: &mut Vec<u8>) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        let microseconds = match self.num_microseconds() {
+            Some(microseconds) => microseconds,
+            None => return Err("value too large to transmit".into()),
+        };
+        types::interval_to_sql(microseconds, 0, 0, w);
+        Ok(IsNull::No)
+    }
+
+    accepts!(INTERVAL);
+    to_sql_checked!();
+}
@@ -0,0 +1,47 @@
+use postgres_protocol::types;
+use std::error::Error;
+
+use crate::types::{FromSql, IsNull, ToSql, Type};
+
+// Number of milliseconds from 1970-01-01 to 2000-01-01
+const EPOCH_OFFSET_MILLIS: i64 = 946_684_800_000;
+
+/// A wrapper which stores a `TIMESTAMP WITH TIME ZONE` as a number of milliseconds since the Unix epoch.
+///
+/// Times before 1970 are represented by negative values. Precision below a millisecond is truncated towards negative
+/// infinity when decoding. The `infinity` and `-infinity` timestamps cannot be decoded, and values whose number of
+/// microseconds since 2000 does not fit in an `i64` cannot be encoded.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TimestampMillis(pub i64);
+
+impl<'a> FromSql<'a> for TimestampMillis {
+    fn from_sql(_: &Type, raw: &'a [u8]) -> Result<TimestampMillis, Box<dyn Error + Sync + Send>> {
+        let micros = types::timestamp_from_sql(raw)?;
+        if micros == i64::max_value() || micros == i64::min_value() {
+            return Err("infinite timestamp".into());
+        }
+        // round towards negative infinity rather than zero
+        let mut millis = micros / 1000;
+        if micros % 1000 < 0 {
+            millis -= 1;
+        }
+        Ok(TimestampMillis(millis + EPOCH_OFFSET_MILLIS))
+    }
+
+    accepts!(TIMESTAMPTZ);
+}
+
+impl ToSql for TimestampMillis {
+    fn to_sql(&self, _: &Type, out: &mut Vec<u8>) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        let micros = self
+            .0
+            .checked_sub(EPOCH_OFFSET_MILLIS)
+            .and_then(|millis| millis.checked_mul(1000))
+            .ok_or("value too large to transmit")?;
+        types::timestamp_to_sql(micros, out);
+        Ok(IsNull::No)
+    }
+
+    accepts!(TIMESTAMPTZ);
+    to_sql_checked!();
+}
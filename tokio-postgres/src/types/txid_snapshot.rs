@@ -0,0 +1,39 @@
+use fallible_iterator::FallibleIterator;
+use postgres_protocol::types;
+use std::error::Error;
+
+use crate::types::{FromSql, IsNull, ToSql, Type};
+
+/// A snapshot of the transactions visible at a point in time, as stored by the `TXID_SNAPSHOT` type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TxidSnapshot {
+    /// The earliest transaction ID that is still active.
+    pub xmin: u64,
+    /// The first as-yet-unassigned transaction ID.
+    pub xmax: u64,
+    /// The IDs of the transactions in progress at the time of the snapshot.
+    pub xip: Vec<u64>,
+}
+
+impl<'a> FromSql<'a> for TxidSnapshot {
+    fn from_sql(_: &Type, raw: &'a [u8]) -> Result<TxidSnapshot, Box<dyn Error + Sync + Send>> {
+        let snapshot = types::txid_snapshot_from_sql(raw)?;
+        Ok(TxidSnapshot {
+            xmin: snapshot.xmin(),
+            xmax: snapshot.xmax(),
+            xip: snapshot.xip().collect()?,
+        })
+    }
+
+    accepts!(TXID_SNAPSHOT);
+}
+
+impl ToSql for TxidSnapshot {
+    fn to_sql(&self, _: &Type, out: &mut Vec<u8>) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        types::txid_snapshot_to_sql(self.xmin, self.xmax, self.xip.iter().cloned(), out)?;
+        Ok(IsNull::No)
+    }
+
+    accepts!(TXID_SNAPSHOT);
+    to_sql_checked!();
+}
@@ -172,6 +172,8 @@ pub enum Inner {
     DateRangeArray,
     Int8Range,
     Int8RangeArray,
+    Jsonpath,
+    JsonpathArray,
     Regnamespace,
     RegnamespaceArray,
     Regrole,
@@ -341,6 +343,8 @@ impl Inner {
             3913 => Some(Inner::DateRangeArray),
             3926 => Some(Inner::Int8Range),
             3927 => Some(Inner::Int8RangeArray),
+            4072 => Some(Inner::Jsonpath),
+            4073 => Some(Inner::JsonpathArray),
             4089 => Some(Inner::Regnamespace),
             4090 => Some(Inner::RegnamespaceArray),
             4096 => Some(Inner::Regrole),
@@ -510,6 +514,8 @@ impl Inner {
             Inner::DateRangeArray => 3913,
             Inner::Int8Range => 3926,
             Inner::Int8RangeArray => 3927,
+            Inner::Jsonpath => 4072,
+            Inner::JsonpathArray => 4073,
             Inner::Regnamespace => 4089,
             Inner::RegnamespaceArray => 4090,
             Inner::Regrole => 4096,
@@ -679,6 +685,8 @@ impl Inner {
             Inner::DateRangeArray => &Kind::Array(Type(Inner::DateRange)),
             Inner::Int8Range => &Kind::Range(Type(Inner::Int8)),
             Inner::Int8RangeArray => &Kind::Array(Type(Inner::Int8Range)),
+            Inner::Jsonpath => &Kind::Simple,
+            Inner::JsonpathArray => &Kind::Array(Type(Inner::Jsonpath)),
             Inner::Regnamespace => &Kind::Simple,
             Inner::RegnamespaceArray => &Kind::Array(Type(Inner::Regnamespace)),
             Inner::Regrole => &Kind::Simple,
@@ -848,6 +856,8 @@ impl Inner {
             Inner::DateRangeArray => "_daterange",
             Inner::Int8Range => "int8range",
             Inner::Int8RangeArray => "_int8range",
+            Inner::Jsonpath => "jsonpath",
+            Inner::JsonpathArray => "_jsonpath",
             Inner::Regnamespace => "regnamespace",
             Inner::RegnamespaceArray => "_regnamespace",
             Inner::Regrole => "regrole",
@@ -1334,6 +1344,12 @@ impl Type {
     /// INT8RANGE&#91;&#93;
     pub const INT8_RANGE_ARRAY: Type = Type(Inner::Int8RangeArray);
 
+    /// JSONPATH - JSON path
+    pub const JSONPATH: Type = Type(Inner::Jsonpath);
+
+    /// JSONPATH&#91;&#93;
+    pub const JSONPATH_ARRAY: Type = Type(Inner::JsonpathArray);
+
     /// REGNAMESPACE - registered namespace
     pub const REGNAMESPACE: Type = Type(Inner::Regnamespace);
 
@@ -0,0 +1,76 @@
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+/// A Postgres server version.
+///
+/// Versions compare in release order, so they can be used to check for the availability of server features. Servers
+/// before version 10 report three components (e.g. `9.6.2`), while newer servers report two (e.g. `14.1`), which are
+/// parsed as the `major` and `minor` components. Prerelease suffixes such as `beta1`, `rc1`, or `devel` are ignored.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Version {
+    /// The major version.
+    pub major: u32,
+    /// The minor version.
+    pub minor: u32,
+    /// The patch version.
+    pub patch: u32,
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(fmt, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// An error returned when parsing a `Version` from a string.
+#[derive(Debug)]
+pub struct ParseVersionError(());
+
+impl fmt::Display for ParseVersionError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.write_str("invalid server version")
+    }
+}
+
+impl Error for ParseVersionError {}
+
+impl FromStr for Version {
+    type Err = ParseVersionError;
+
+    fn from_str(s: &str) -> Result<Version, ParseVersionError> {
+        // the version may be followed by build information, e.g. `10.4 (Debian 10.4-2.pgdg90+1)`
+        let s = s.split_whitespace().next().unwrap_or("");
+
+        let mut components = [0; 3];
+        let mut it = s.split('.');
+        for (i, component) in components.iter_mut().enumerate() {
+            let part = match it.next() {
+                Some(part) => part,
+                None => break,
+            };
+
+            let end = part
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(part.len());
+            if end == 0 {
+                if i == 0 {
+                    return Err(ParseVersionError(()));
+                }
+                break;
+            }
+            *component = part[..end].parse().map_err(|_| ParseVersionError(()))?;
+
+            // anything after the digits is a prerelease suffix, which ends the version
+            if end != part.len() {
+                break;
+            }
+        }
+
+        Ok(Version {
+            major: components[0],
+            minor: components[1],
+            patch: components[2],
+        })
+    }
+}
@@ -1,23 +1,30 @@
 #![warn(rust_2018_idioms)]
 
-use futures::sync::mpsc;
+use futures::sync::{mpsc, oneshot};
 use futures::{future, stream, try_ready};
 use log::debug;
+use std::collections::HashMap;
 use std::error::Error;
 use std::fmt::Write;
 use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 use tokio::net::TcpStream;
 use tokio::prelude::*;
 use tokio::runtime::current_thread::Runtime;
 use tokio::timer::Delay;
-use tokio_postgres::error::SqlState;
+use tokio_postgres::binary_copy::BinaryCopyWriter;
+use tokio_postgres::error::{SqlState, UnexpectedMessage, UnsupportedServerVersion};
 use tokio_postgres::impls;
-use tokio_postgres::tls::NoTlsStream;
-use tokio_postgres::types::{Kind, Type};
-use tokio_postgres::{AsyncMessage, Client, Connection, NoTls, SimpleQueryMessage};
+use tokio_postgres::tls::{EitherTls, NoTlsStream};
+use tokio_postgres::types::{EncodedText, Format, Kind, Raw, ToSql, Type, WasNull};
+use tokio_postgres::{
+    AsyncMessage, Client, Connection, ConnectionState, MessageDirection, NoTls, SimpleQueryMessage,
+    Version,
+};
 
 mod parse;
+mod replication;
 #[cfg(feature = "runtime")]
 mod runtime;
 mod types;
@@ -189,6 +196,299 @@ fn insert_select() {
     runtime.block_on(tests).unwrap();
 }
 
+#[test]
+fn prepare_and_query() {
+    let _ = env_logger::try_init();
+    let mut runtime = Runtime::new().unwrap();
+
+    let (mut client, connection) = runtime.block_on(connect("user=postgres")).unwrap();
+    let connection = connection.map_err(|e| panic!("{}", e));
+    runtime.handle().spawn(connection).unwrap();
+
+    runtime
+        .block_on(
+            client
+                .simple_query("CREATE TYPE pg_temp.mood AS ENUM ('sad', 'happy')")
+                .for_each(|_| Ok(())),
+        )
+        .unwrap();
+
+    let (statement, rows) = runtime
+        .block_on(client.prepare_and_query(
+            "SELECT $1::INT4 + 1, 'happy'::mood::TEXT, 'sad'::mood",
+            &[Type::INT4],
+            &[&1i32],
+        ))
+        .unwrap();
+    assert_eq!(statement.params(), &[Type::INT4]);
+    assert_eq!(statement.columns()[2].type_().name(), "mood");
+    assert!(statement
+        .columns()
+        .iter()
+        .all(|c| c.format() == Format::Binary));
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].get::<_, i32>(0), 2);
+    assert_eq!(rows[0].get::<_, &str>(1), "happy");
+
+    let rows = runtime
+        .block_on(client.query(&statement, &[&41i32]).collect())
+        .unwrap();
+    assert_eq!(rows[0].get::<_, i32>(0), 42);
+
+    let err = runtime
+        .block_on(client.prepare_and_query("SELECT $1::INT4", &[Type::INT4], &[]))
+        .err()
+        .unwrap();
+    assert_eq!(err.to_string(), "expected 1 parameters but got 0");
+    assert_eq!(err.parameter_count(), Some((1, 0)));
+
+    let prepared_statements = |client: &mut Client, runtime: &mut Runtime| {
+        let count = client
+            .simple_query("SELECT count(*) FROM pg_prepared_statements")
+            .filter_map(|m| match m {
+                SimpleQueryMessage::Row(row) => Some(row.get(0).unwrap().to_string()),
+                _ => None,
+            })
+            .collect();
+        runtime.block_on(count).unwrap()
+    };
+    let before = prepared_statements(&mut client, &mut runtime);
+
+    let err = runtime
+        .block_on(client.prepare_and_query("SELECT 1 / $1::INT4", &[Type::INT4], &[&0i32]))
+        .err()
+        .unwrap();
+    assert_eq!(err.code(), Some(&SqlState::DIVISION_BY_ZERO));
+    assert_eq!(err.parameter_count(), None);
+
+    assert_eq!(prepared_statements(&mut client, &mut runtime), before);
+}
+
+#[test]
+fn prepare_named() {
+    let _ = env_logger::try_init();
+    let mut runtime = Runtime::new().unwrap();
+
+    let (mut client, connection) = runtime.block_on(connect("user=postgres")).unwrap();
+    let connection = connection.map_err(|e| panic!("{}", e));
+    runtime.handle().spawn(connection).unwrap();
+
+    let statement = runtime
+        .block_on(client.prepare_named("SELECT :a::INT4, :b::TEXT, :a + 1"))
+        .unwrap();
+    assert_eq!(statement.names(), ["a", "b"]);
+    assert_eq!(statement.statement().params(), &[Type::INT4, Type::TEXT]);
+
+    let statement = runtime
+        .block_on(client.prepare_named(
+            "SELECT ':a', E'\\':a', 1 AS \":a\", $$ :a $$, $tag$ :a $tag$ -- :a\n/* :a */ , :b_2::TEXT",
+        ))
+        .unwrap();
+    assert_eq!(statement.names(), ["b_2"]);
+
+    let statement = runtime
+        .block_on(client.prepare_named(
+            "SELECT x[1:2], x[lo:hi], x[(:i)], ARRAY[:i, :j] \
+             FROM (SELECT ARRAY[1, 2, 3] AS x, 2 AS lo, 3 AS hi) t",
+        ))
+        .unwrap();
+    assert_eq!(statement.names(), ["i", "j"]);
+
+    let mut params = HashMap::<&str, &dyn ToSql>::new();
+    params.insert("i", &1i32);
+    params.insert("j", &2i32);
+    let rows = runtime
+        .block_on(client.query_named(&statement, &params).collect())
+        .unwrap();
+    assert_eq!(rows[0].get::<_, Vec<i32>>(0), vec![1, 2]);
+    assert_eq!(rows[0].get::<_, Vec<i32>>(1), vec![2, 3]);
+    assert_eq!(rows[0].get::<_, i32>(2), 1);
+    assert_eq!(rows[0].get::<_, Vec<i32>>(3), vec![1, 2]);
+
+    params.remove("j");
+    let err = runtime
+        .block_on(client.query_named(&statement, &params).collect())
+        .err()
+        .unwrap();
+    assert_eq!(err.to_string(), "no value provided for parameter `:j`");
+}
+
+#[test]
+fn column_table_info() {
+    let _ = env_logger::try_init();
+    let mut runtime = Runtime::new().unwrap();
+
+    let (mut client, connection) = runtime.block_on(connect("user=postgres")).unwrap();
+    let connection = connection.map_err(|e| panic!("{}", e));
+    runtime.handle().spawn(connection).unwrap();
+
+    runtime
+        .block_on(
+            client
+                .simple_query("CREATE TEMPORARY TABLE foo (id INT, name TEXT)")
+                .for_each(|_| Ok(())),
+        )
+        .unwrap();
+
+    let stmt = runtime
+        .block_on(client.prepare("SELECT name, 1 AS one, id FROM foo"))
+        .unwrap();
+    let oid_stmt = runtime
+        .block_on(client.prepare("SELECT 'foo'::regclass::oid"))
+        .unwrap();
+    let rows = runtime
+        .block_on(client.query(&oid_stmt, &[]).collect())
+        .unwrap();
+    let oid = rows[0].get::<_, u32>(0);
+
+    let columns = stmt.columns();
+    assert_eq!(columns[0].table_oid(), Some(oid));
+    assert_eq!(columns[0].column_id(), Some(2));
+    assert_eq!(columns[1].table_oid(), None);
+    assert_eq!(columns[1].column_id(), None);
+    assert_eq!(columns[2].table_oid(), Some(oid));
+    assert_eq!(columns[2].column_id(), Some(1));
+}
+
+#[test]
+fn column_type_modifiers() {
+    let _ = env_logger::try_init();
+    let mut runtime = Runtime::new().unwrap();
+
+    let (mut client, connection) = runtime.block_on(connect("user=postgres")).unwrap();
+    let connection = connection.map_err(|e| panic!("{}", e));
+    runtime.handle().spawn(connection).unwrap();
+
+    runtime
+        .block_on(
+            client
+                .simple_query(
+                    "CREATE TEMPORARY TABLE foo (
+                        name VARCHAR(255),
+                        code CHAR(3),
+                        notes TEXT,
+                        price NUMERIC(10, 2),
+                        amount NUMERIC
+                    )",
+                )
+                .for_each(|_| Ok(())),
+        )
+        .unwrap();
+
+    let stmt = runtime
+        .block_on(client.prepare("SELECT name, code, notes, price, amount FROM foo"))
+        .unwrap();
+
+    let columns = stmt.columns();
+    assert_eq!(columns[0].type_modifier(), 259);
+    assert_eq!(columns[0].max_length(), Some(255));
+    assert_eq!(columns[1].max_length(), Some(3));
+    assert_eq!(columns[2].type_modifier(), -1);
+    assert_eq!(columns[2].max_length(), None);
+    assert_eq!(columns[3].numeric_precision_scale(), Some((10, 2)));
+    assert_eq!(columns[3].max_length(), None);
+    assert_eq!(columns[4].numeric_precision_scale(), None);
+}
+
+#[test]
+fn server_version() {
+    let _ = env_logger::try_init();
+    let mut runtime = Runtime::new().unwrap();
+
+    let (_client, connection) = runtime.block_on(connect("user=postgres")).unwrap();
+    let version = connection.server_version().unwrap();
+    assert!(
+        version
+            >= Version {
+                major: 9,
+                minor: 5,
+                patch: 0
+            }
+    );
+}
+
+#[test]
+fn version_parse() {
+    let parse = |s: &str| {
+        s.parse::<Version>()
+            .ok()
+            .map(|v| (v.major, v.minor, v.patch))
+    };
+
+    assert_eq!(parse("9.6.2"), Some((9, 6, 2)));
+    assert_eq!(parse("14.1"), Some((14, 1, 0)));
+    assert_eq!(parse("10.4 (Debian 10.4-2.pgdg90+1)"), Some((10, 4, 0)));
+    assert_eq!(parse("15beta1"), Some((15, 0, 0)));
+    assert_eq!(parse("9.6rc1"), Some((9, 6, 0)));
+    assert_eq!(parse("16devel"), Some((16, 0, 0)));
+    assert_eq!(parse(""), None);
+    assert_eq!(parse("devel"), None);
+    assert_eq!(parse("99999999999.1"), None);
+
+    assert!("9.6.2".parse::<Version>().unwrap() < "10.0".parse::<Version>().unwrap());
+}
+
+#[test]
+fn db_error_fields() {
+    let _ = env_logger::try_init();
+    let mut runtime = Runtime::new().unwrap();
+
+    let (mut client, connection) = runtime.block_on(connect("user=postgres")).unwrap();
+    let connection = connection.map_err(|e| panic!("{}", e));
+    runtime.handle().spawn(connection).unwrap();
+
+    runtime
+        .block_on(
+            client
+                .simple_query(
+                    "CREATE TEMPORARY TABLE foo (id INT CONSTRAINT foo_pkey PRIMARY KEY);
+                     INSERT INTO foo (id) VALUES (1);",
+                )
+                .for_each(|_| Ok(())),
+        )
+        .unwrap();
+
+    let err = runtime
+        .block_on(
+            client
+                .simple_query("INSERT INTO foo (id) VALUES (1)")
+                .for_each(|_| Ok(())),
+        )
+        .unwrap_err();
+
+    let db_error = err.as_db_error().unwrap();
+    assert_eq!(db_error.code(), &SqlState::UNIQUE_VIOLATION);
+    assert_eq!(db_error.severity(), "ERROR");
+    assert_eq!(db_error.table(), Some("foo"));
+    assert_eq!(db_error.constraint(), Some("foo_pkey"));
+    assert_eq!(db_error.detail(), Some("Key (id)=(1) already exists."));
+    assert!(db_error.schema().unwrap().starts_with("pg_temp"));
+    assert!(db_error.routine().is_some());
+}
+
+#[test]
+fn boxed_error_source() {
+    fn run(runtime: &mut Runtime, client: &mut Client) -> Result<(), Box<dyn Error + Sync + Send>> {
+        runtime.block_on(client.prepare("SELECT * FROM nonexistent_table"))?;
+        Ok(())
+    }
+
+    let _ = env_logger::try_init();
+    let mut runtime = Runtime::new().unwrap();
+
+    let (mut client, connection) = runtime.block_on(connect("user=postgres")).unwrap();
+    let connection = connection.map_err(|e| panic!("{}", e));
+    runtime.handle().spawn(connection).unwrap();
+
+    let err = run(&mut runtime, &mut client).unwrap_err();
+    let err = err.downcast::<tokio_postgres::Error>().unwrap();
+    let source = err.source().unwrap();
+    let db_error = source
+        .downcast_ref::<tokio_postgres::error::DbError>()
+        .unwrap();
+    assert_eq!(db_error.code(), &SqlState::UNDEFINED_TABLE);
+}
+
 #[test]
 fn query_portal() {
     let _ = env_logger::try_init();
@@ -443,6 +743,96 @@ fn custom_simple() {
     assert_eq!(&Kind::Simple, ty.kind());
 }
 
+#[test]
+fn deallocate_all() {
+    let _ = env_logger::try_init();
+    let mut runtime = Runtime::new().unwrap();
+
+    let (mut client, connection) = runtime.block_on(connect("user=postgres")).unwrap();
+    let connection = connection.map_err(|e| panic!("{}", e));
+    runtime.handle().spawn(connection).unwrap();
+
+    let select = runtime
+        .block_on(client.prepare("SELECT $1::HSTORE"))
+        .unwrap();
+
+    runtime
+        .block_on(client.simple_query("DEALLOCATE ALL").for_each(|_| Ok(())))
+        .unwrap();
+
+    let err = match runtime.block_on(
+        client
+            .query(&select, &[&None::<HashMap<String, Option<String>>>])
+            .collect(),
+    ) {
+        Ok(_) => panic!("unexpected success"),
+        Err(e) => e,
+    };
+    assert_eq!(err.to_string(), "statement was deallocated");
+
+    // the client's internal type lookup statements are prepared again
+    let select = runtime
+        .block_on(client.prepare("SELECT $1::HSTORE[]"))
+        .unwrap();
+    assert_eq!("_hstore", select.params()[0].name());
+
+    let select = runtime.block_on(client.prepare("SELECT 1")).unwrap();
+    runtime
+        .block_on(client.simple_query("DISCARD ALL").for_each(|_| Ok(())))
+        .unwrap();
+    let err = runtime.block_on(client.execute(&select, &[])).unwrap_err();
+    assert_eq!(err.to_string(), "statement was deallocated");
+}
+
+#[test]
+fn closed_after_send() {
+    let _ = env_logger::try_init();
+    let mut runtime = Runtime::new().unwrap();
+
+    let (mut client, connection) = runtime.block_on(connect("user=postgres")).unwrap();
+    let connection = connection.then(|_| Ok(()));
+    runtime.handle().spawn(connection).unwrap();
+
+    let (mut killer, connection) = runtime.block_on(connect("user=postgres")).unwrap();
+    let connection = connection.map_err(|e| panic!("{}", e));
+    runtime.handle().spawn(connection).unwrap();
+
+    let pid = runtime
+        .block_on(client.prepare("SELECT pg_backend_pid()"))
+        .unwrap();
+    let pid = runtime.block_on(client.query(&pid, &[]).collect()).unwrap()[0].get::<_, i32>(0);
+    let sleep = runtime
+        .block_on(client.prepare("SELECT pg_sleep(5)"))
+        .unwrap();
+    let select = runtime.block_on(client.prepare("SELECT 1")).unwrap();
+
+    // the second query is sent behind the first, which is interrupted by the server shutting the connection down
+    let kill = Delay::new(Instant::now() + Duration::from_millis(250))
+        .map_err(|e| panic!("{}", e))
+        .and_then(|_| {
+            killer
+                .simple_query(&format!("SELECT pg_terminate_backend({})", pid))
+                .for_each(|_| Ok(()))
+        });
+    let sleep = client.query(&sleep, &[]).collect().then(Ok::<_, ()>);
+    let select = client.query(&select, &[]).collect().then(Ok::<_, ()>);
+    let (sleep, select, ()) = runtime
+        .block_on(sleep.join3(select, kill.map_err(|e| panic!("{}", e))))
+        .unwrap();
+
+    let err = sleep.err().unwrap();
+    assert_eq!(err.code(), Some(&SqlState::ADMIN_SHUTDOWN));
+    let err = select.err().unwrap();
+    assert!(err.is_closed());
+    assert!(err.is_closed_after_send());
+
+    let err = runtime
+        .block_on(client.simple_query("SELECT 1").for_each(|_| Ok(())))
+        .unwrap_err();
+    assert!(err.is_closed());
+    assert!(!err.is_closed_after_send());
+}
+
 #[test]
 fn notifications() {
     let _ = env_logger::try_init();
@@ -617,6 +1007,101 @@ fn copy_in() {
     assert_eq!(rows[1].get::<_, &str>(1), "joe");
 }
 
+#[test]
+fn unexpected_message() {
+    let _ = env_logger::try_init();
+    let mut runtime = Runtime::new().unwrap();
+
+    let (mut client, connection) = runtime.block_on(connect("user=postgres")).unwrap();
+    let connection = connection.map_err(|e| panic!("{}", e));
+    runtime.handle().spawn(connection).unwrap();
+
+    let err = runtime
+        .block_on(
+            client
+                .simple_query(
+                    "CREATE TEMPORARY TABLE foo (id INTEGER);
+                     COPY foo FROM STDIN",
+                )
+                .collect(),
+        )
+        .err()
+        .unwrap();
+    let source = err
+        .source()
+        .and_then(|e| e.downcast_ref::<UnexpectedMessage>())
+        .unwrap();
+    assert_eq!(source.expected(), "a simple query response");
+    assert_eq!(source.received(), "CopyInResponse");
+    assert_eq!(
+        err.to_string(),
+        "unexpected message from server: expected a simple query response but received \
+         CopyInResponse"
+    );
+}
+
+#[test]
+fn copy_in_binary() {
+    let _ = env_logger::try_init();
+    let mut runtime = Runtime::new().unwrap();
+
+    let (mut client, connection) = runtime.block_on(connect("user=postgres")).unwrap();
+    let connection = connection.map_err(|e| panic!("{}", e));
+    runtime.handle().spawn(connection).unwrap();
+
+    runtime
+        .block_on(
+            client
+                .simple_query("CREATE TEMPORARY TABLE foo (id INTEGER, name TEXT)")
+                .for_each(|_| Ok(())),
+        )
+        .unwrap();
+
+    let mut writer = BinaryCopyWriter::new(&[Type::INT4, Type::TEXT]);
+    writer.write(&[&1i32, &"jim"]).unwrap();
+    writer.write(&[&2i32, &None::<&str>]).unwrap();
+    let err = writer.write(&[&3i32]).unwrap_err();
+    assert!(err.to_string().contains("row 2"));
+    let err = writer.write(&[&"joe", &"joe"]).unwrap_err();
+    assert!(err.to_string().contains("row 2"));
+    let text = Raw {
+        type_: Type::INT4,
+        format: Format::Text,
+        bytes: &b"3"[..],
+    };
+    let err = writer.write(&[&text, &"joe"]).unwrap_err();
+    assert!(err.to_string().contains("row 2"));
+    assert_eq!(writer.rows(), 2);
+
+    let mut wide = BinaryCopyWriter::new(&vec![Type::INT4; 32768]);
+    let err = wide.write(&vec![&0i32 as &dyn ToSql; 32768]).unwrap_err();
+    assert!(err.to_string().contains("row 0"));
+
+    let stream = stream::iter_ok::<_, String>(vec![writer.finish()]);
+    let rows = runtime
+        .block_on(
+            client
+                .prepare("COPY foo FROM STDIN (FORMAT binary)")
+                .and_then(|s| client.copy_in(&s, &[], stream)),
+        )
+        .unwrap();
+    assert_eq!(rows, 2);
+
+    let rows = runtime
+        .block_on(
+            client
+                .prepare("SELECT id, name FROM foo ORDER BY id")
+                .and_then(|s| client.query(&s, &[]).collect()),
+        )
+        .unwrap();
+
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0].get::<_, i32>(0), 1);
+    assert_eq!(rows[0].get::<_, Option<&str>>(1), Some("jim"));
+    assert_eq!(rows[1].get::<_, i32>(0), 2);
+    assert_eq!(rows[1].get::<_, Option<&str>>(1), None);
+}
+
 #[test]
 fn copy_in_large() {
     let _ = env_logger::try_init();
@@ -812,24 +1297,95 @@ fn simple_query() {
         _ => panic!("unexpected message"),
     }
     match &messages[2] {
+        SimpleQueryMessage::RowDescription(columns) => {
+            assert_eq!(&**columns, &["id".to_string(), "name".to_string()]);
+        }
+        _ => panic!("unexpected message"),
+    }
+    match &messages[3] {
         SimpleQueryMessage::Row(row) => {
             assert_eq!(row.get(0), Some("1"));
             assert_eq!(row.get(1), Some("steven"));
         }
         _ => panic!("unexpected message"),
     }
-    match &messages[3] {
+    match &messages[4] {
         SimpleQueryMessage::Row(row) => {
             assert_eq!(row.get(0), Some("2"));
             assert_eq!(row.get(1), Some("joe"));
         }
         _ => panic!("unexpected message"),
     }
-    match messages[4] {
+    match messages[5] {
         SimpleQueryMessage::CommandComplete(2) => {}
         _ => panic!("unexpected message"),
     }
-    assert_eq!(messages.len(), 5);
+    assert_eq!(messages.len(), 6);
+}
+
+#[test]
+fn simple_query_multiple_result_sets() {
+    let _ = env_logger::try_init();
+    let mut runtime = Runtime::new().unwrap();
+
+    let (mut client, connection) = runtime.block_on(connect("user=postgres")).unwrap();
+    let connection = connection.map_err(|e| panic!("{}", e));
+    runtime.handle().spawn(connection).unwrap();
+
+    let f = client
+        .simple_query(
+            "SELECT 1 AS a, 2 AS b;
+            SELECT 'x' AS c WHERE false;
+            SELECT 'y' AS d",
+        )
+        .collect();
+    let messages = runtime.block_on(f).unwrap();
+
+    match &messages[0] {
+        SimpleQueryMessage::RowDescription(columns) => {
+            assert_eq!(&**columns, &["a".to_string(), "b".to_string()]);
+        }
+        _ => panic!("unexpected message"),
+    }
+    match &messages[1] {
+        SimpleQueryMessage::Row(row) => {
+            assert_eq!(row.columns(), &["a".to_string(), "b".to_string()]);
+            assert_eq!(row.get("b"), Some("2"));
+        }
+        _ => panic!("unexpected message"),
+    }
+    match messages[2] {
+        SimpleQueryMessage::CommandComplete(1) => {}
+        _ => panic!("unexpected message"),
+    }
+    match &messages[3] {
+        SimpleQueryMessage::RowDescription(columns) => {
+            assert_eq!(&**columns, &["c".to_string()]);
+        }
+        _ => panic!("unexpected message"),
+    }
+    match messages[4] {
+        SimpleQueryMessage::CommandComplete(0) => {}
+        _ => panic!("unexpected message"),
+    }
+    match &messages[5] {
+        SimpleQueryMessage::RowDescription(columns) => {
+            assert_eq!(&**columns, &["d".to_string()]);
+        }
+        _ => panic!("unexpected message"),
+    }
+    match &messages[6] {
+        SimpleQueryMessage::Row(row) => {
+            assert_eq!(row.columns(), &["d".to_string()]);
+            assert_eq!(row.get(0), Some("y"));
+        }
+        _ => panic!("unexpected message"),
+    }
+    match messages[7] {
+        SimpleQueryMessage::CommandComplete(1) => {}
+        _ => panic!("unexpected message"),
+    }
+    assert_eq!(messages.len(), 8);
 }
 
 #[test]
@@ -927,3 +1483,527 @@ fn poll_idle_new() {
     };
     runtime.block_on(future).unwrap();
 }
+
+#[test]
+fn buffer_sizes() {
+    let _ = env_logger::try_init();
+    let mut runtime = Runtime::new().unwrap();
+
+    for &size in &[0, 64 * 1024] {
+        let mut config = "user=postgres".parse::<tokio_postgres::Config>().unwrap();
+        config.read_buffer_size(size).write_buffer_size(size);
+
+        let handshake = TcpStream::connect(&"127.0.0.1:5433".parse().unwrap())
+            .map_err(|e| panic!("{}", e))
+            .and_then(move |s| config.connect_raw(s, NoTls));
+        let (mut client, connection) = runtime.block_on(handshake).unwrap();
+        let connection = connection.map_err(|e| panic!("{}", e));
+        runtime.handle().spawn(connection).unwrap();
+
+        let prepare = client.prepare("SELECT repeat('a', 100000)");
+        let statement = runtime.block_on(prepare).unwrap();
+        let rows = runtime
+            .block_on(client.query(&statement, &[]).collect())
+            .unwrap();
+        assert_eq!(rows[0].get::<_, &str>(0).len(), 100_000);
+    }
+}
+
+#[test]
+fn connection_state() {
+    let _ = env_logger::try_init();
+    let mut runtime = Runtime::new().unwrap();
+
+    let (mut client, connection) = runtime.block_on(connect("user=postgres")).unwrap();
+    let connection = connection.map_err(|e| panic!("{}", e));
+    runtime.handle().spawn(connection).unwrap();
+
+    assert_eq!(client.state(), ConnectionState::Idle);
+
+    runtime
+        .block_on(client.simple_query("BEGIN").collect())
+        .unwrap();
+    assert_eq!(client.state(), ConnectionState::InTransaction);
+
+    runtime
+        .block_on(client.simple_query("SELECT 1/0").collect())
+        .err()
+        .unwrap();
+    // the error is returned before the server has finished with the request
+    runtime
+        .block_on(future::poll_fn(|| client.poll_idle()))
+        .unwrap();
+    assert_eq!(client.state(), ConnectionState::InFailedTransaction);
+
+    runtime
+        .block_on(client.simple_query("ROLLBACK").collect())
+        .unwrap();
+    assert_eq!(client.state(), ConnectionState::Idle);
+
+    drop(runtime);
+    assert_eq!(client.state(), ConnectionState::Closed);
+}
+
+#[test]
+fn desynchronized_connection_state() {
+    use std::io::Read;
+    use std::net::TcpListener;
+    use std::thread;
+
+    let _ = env_logger::try_init();
+    let mut runtime = Runtime::new().unwrap();
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let server = thread::spawn(move || {
+        let mut stream = listener.accept().unwrap().0;
+
+        let mut len = [0; 4];
+        stream.read_exact(&mut len).unwrap();
+        let mut startup = vec![0; i32::from_be_bytes(len) as usize - 4];
+        stream.read_exact(&mut startup).unwrap();
+
+        write_backend_message(&mut stream, b'R', &0i32.to_be_bytes());
+        write_backend_message(&mut stream, b'Z', b"I");
+        // a response with no outstanding request
+        write_backend_message(&mut stream, b'Z', b"I");
+        stream
+    });
+
+    let config = "user=postgres".parse::<tokio_postgres::Config>().unwrap();
+    let handshake = TcpStream::connect(&addr)
+        .map_err(|e| panic!("{}", e))
+        .and_then(move |s| config.connect_raw(s, NoTls));
+    let (client, connection) = runtime.block_on(handshake).unwrap();
+
+    let err = runtime.block_on(connection).err().unwrap();
+    assert!(err.source().unwrap().is::<UnexpectedMessage>(), "{}", err);
+    assert_eq!(client.state(), ConnectionState::Desynchronized);
+
+    server.join().unwrap();
+}
+
+#[test]
+fn max_message_size() {
+    let _ = env_logger::try_init();
+    let mut runtime = Runtime::new().unwrap();
+
+    let mut config = "user=postgres".parse::<tokio_postgres::Config>().unwrap();
+    config.max_message_size(10 * 1024);
+
+    let handshake = TcpStream::connect(&"127.0.0.1:5433".parse().unwrap())
+        .map_err(|e| panic!("{}", e))
+        .and_then(move |s| config.connect_raw(s, NoTls));
+    let (mut client, connection) = runtime.block_on(handshake).unwrap();
+    let (tx, rx) = oneshot::channel();
+    runtime.spawn(connection.then(|r| tx.send(r).map_err(|_| ())));
+
+    let prepare = client.prepare("SELECT repeat('a', 100000)");
+    let statement = runtime.block_on(prepare).unwrap();
+    assert!(runtime
+        .block_on(client.query(&statement, &[]).collect())
+        .is_err());
+    assert!(runtime.block_on(rx).unwrap().is_err());
+    assert!(client.is_closed());
+}
+
+#[test]
+fn parameter_count() {
+    let _ = env_logger::try_init();
+    let mut runtime = Runtime::new().unwrap();
+
+    let (mut client, connection) = runtime.block_on(connect("user=postgres")).unwrap();
+    let connection = connection.map_err(|e| panic!("{}", e));
+    runtime.spawn(connection);
+
+    let prepare = client.prepare("SELECT $1::INT4, $2::TEXT");
+    let statement = runtime.block_on(prepare).unwrap();
+    assert_eq!(statement.parameter_count(), 2);
+
+    let err = match runtime.block_on(client.query(&statement, &[&1i32]).collect()) {
+        Ok(_) => panic!("unexpected success"),
+        Err(e) => e,
+    };
+    assert_eq!(err.to_string(), "expected 2 parameters but got 1");
+    assert_eq!(err.parameter_count(), Some((2, 1)));
+    let err = runtime
+        .block_on(client.execute(&statement, &[&1i32, &"a", &2i32]))
+        .unwrap_err();
+    assert_eq!(err.parameter_count(), Some((2, 3)));
+
+    let rows = runtime
+        .block_on(client.query(&statement, &[&1i32, &"a"]).collect())
+        .unwrap();
+    assert_eq!(rows[0].get::<_, &str>(1), "a");
+}
+
+#[test]
+fn query_field() {
+    let _ = env_logger::try_init();
+    let mut runtime = Runtime::new().unwrap();
+
+    let mut config = "user=postgres".parse::<tokio_postgres::Config>().unwrap();
+    config.max_message_size(10 * 1024);
+
+    let handshake = TcpStream::connect(&"127.0.0.1:5433".parse().unwrap())
+        .map_err(|e| panic!("{}", e))
+        .and_then(move |s| config.connect_raw(s, NoTls));
+    let (mut client, connection) = runtime.block_on(handshake).unwrap();
+    let connection = connection.map_err(|e| panic!("{}", e));
+    runtime.spawn(connection);
+
+    // the value is far larger than the maximum message size
+    let prepare = client.prepare("SELECT decode(repeat('0102', 50000), 'hex')");
+    let statement = runtime.block_on(prepare).unwrap();
+    let chunks = runtime
+        .block_on(client.query_field(&statement, &[]).collect())
+        .unwrap();
+    let value = chunks.concat();
+    assert_eq!(value.len(), 100_000);
+    assert!(value.chunks(2).all(|c| c == [1, 2]));
+
+    let prepare =
+        client.prepare("SELECT x FROM (VALUES ('\\x01'::BYTEA), (NULL), ('\\x0203')) AS t(x)");
+    let statement = runtime.block_on(prepare).unwrap();
+    let chunks = client
+        .query_field(&statement, &[])
+        .then(Ok::<_, ()>)
+        .collect();
+    let chunks = runtime.block_on(chunks).unwrap();
+    assert_eq!(chunks.len(), 2);
+    assert_eq!(&chunks[0].as_ref().unwrap()[..], [1]);
+    let err = chunks[1].as_ref().unwrap_err();
+    assert!(err.source().unwrap().is::<WasNull>());
+
+    let prepare = client.prepare("SELECT x FROM (VALUES (''::BYTEA), ('\\x0203')) AS t(x)");
+    let statement = runtime.block_on(prepare).unwrap();
+    let chunks = runtime
+        .block_on(client.query_field(&statement, &[]).collect())
+        .unwrap();
+    assert_eq!(chunks.concat(), [2, 3]);
+
+    let prepare = client.prepare("SELECT 1, 2");
+    let statement = runtime.block_on(prepare).unwrap();
+    assert!(runtime
+        .block_on(client.query_field(&statement, &[]).collect())
+        .is_err());
+
+    // rows are buffered as usual afterwards
+    let prepare = client.prepare("SELECT 1::INT4");
+    let statement = runtime.block_on(prepare).unwrap();
+    let rows = runtime
+        .block_on(client.query(&statement, &[]).collect())
+        .unwrap();
+    assert_eq!(rows[0].get::<_, i32>(0), 1);
+}
+
+#[test]
+fn client_encoding() {
+    let _ = env_logger::try_init();
+    let mut runtime = Runtime::new().unwrap();
+
+    let (mut client, connection) = runtime
+        .block_on(connect("user=postgres client_encoding=LATIN1"))
+        .unwrap();
+    let connection = connection.map_err(|e| panic!("{}", e));
+    runtime.spawn(connection);
+
+    let prepare = client.prepare("SELECT 'caf' || chr(233)");
+    let statement = runtime.block_on(prepare).unwrap();
+    let rows = runtime
+        .block_on(client.query(&statement, &[]).collect())
+        .unwrap();
+    assert_eq!(
+        rows[0].get::<_, EncodedText>(0),
+        EncodedText(b"caf\xe9".to_vec())
+    );
+    assert!(rows[0].try_get::<_, String>(0).is_err());
+    assert!(rows[0].try_get::<_, Vec<u8>>(0).is_err());
+
+    let (mut client, connection) = runtime.block_on(connect("user=postgres")).unwrap();
+    let (tx, rx) = oneshot::channel();
+    runtime.spawn(connection.then(|r| tx.send(r).map_err(|_| ())));
+
+    let _ = runtime.block_on(
+        client
+            .simple_query("SET client_encoding = 'LATIN1'")
+            .collect(),
+    );
+    let err = runtime.block_on(rx).unwrap().err().unwrap();
+    assert!(err.to_string().contains("client_encoding is LATIN1"));
+    assert!(client.is_closed());
+}
+
+#[test]
+fn message_trace() {
+    let _ = env_logger::try_init();
+    let mut runtime = Runtime::new().unwrap();
+
+    let messages = Arc::new(Mutex::new(vec![]));
+
+    let (mut client, mut connection) = runtime.block_on(connect("user=postgres")).unwrap();
+    let trace = messages.clone();
+    connection.set_message_trace(move |direction, tag, len| {
+        trace.lock().unwrap().push((direction, tag, len));
+    });
+    let connection = connection.map_err(|e| panic!("{}", e));
+    runtime.handle().spawn(connection).unwrap();
+
+    let prepare = client.prepare("SELECT 1::INT");
+    let statement = runtime.block_on(prepare).unwrap();
+    let rows = runtime
+        .block_on(client.query(&statement, &[]).collect())
+        .unwrap();
+    assert_eq!(rows.len(), 1);
+
+    let messages = messages.lock().unwrap();
+    let frontend = messages
+        .iter()
+        .filter(|m| m.0 == MessageDirection::Frontend)
+        .map(|m| m.1)
+        .collect::<Vec<_>>();
+    let backend = messages
+        .iter()
+        .filter(|m| m.0 == MessageDirection::Backend)
+        .map(|m| m.1)
+        .collect::<Vec<_>>();
+
+    assert_eq!(&frontend[..3], b"PDS");
+    assert_eq!(&frontend[frontend.len() - 3..], b"BES");
+    assert_eq!(&backend[..4], b"1tTZ");
+    assert_eq!(&backend[backend.len() - 4..], b"2DCZ");
+    assert!(messages.contains(&(MessageDirection::Backend, b'D', 4 + 1 + 2 + 4 + 4)));
+}
+
+#[test]
+fn either_tls() {
+    let _ = env_logger::try_init();
+    let mut runtime = Runtime::new().unwrap();
+
+    for &(tls, sslmode) in &[
+        (EitherTls::Left(NoTls), "prefer"),
+        (EitherTls::Right(NoTls), "disable"),
+    ] {
+        let config = format!("user=postgres sslmode={}", sslmode)
+            .parse::<tokio_postgres::Config>()
+            .unwrap();
+        let handshake = TcpStream::connect(&"127.0.0.1:5433".parse().unwrap())
+            .map_err(|e| panic!("{}", e))
+            .and_then(move |s| config.connect_raw(s, tls));
+        let (mut client, connection) = runtime.block_on(handshake).unwrap();
+        let connection = connection.map_err(|e| panic!("{}", e));
+        runtime.handle().spawn(connection).unwrap();
+
+        let prepare = client.prepare("SELECT 1::INT");
+        let statement = runtime.block_on(prepare).unwrap();
+        let rows = runtime
+            .block_on(client.query(&statement, &[]).collect())
+            .unwrap();
+        assert_eq!(rows[0].get::<_, i32>(0), 1);
+    }
+
+    let config = "user=postgres sslmode=require"
+        .parse::<tokio_postgres::Config>()
+        .unwrap();
+    let handshake = TcpStream::connect(&"127.0.0.1:5433".parse().unwrap())
+        .map_err(|e| panic!("{}", e))
+        .and_then(move |s| config.connect_raw(s, EitherTls::Right::<NoTls, _>(NoTls)));
+    assert!(runtime.block_on(handshake).is_err());
+}
+
+fn read_frontend_message(stream: &mut std::net::TcpStream) -> (u8, Vec<u8>) {
+    use std::io::Read;
+
+    let mut header = [0; 5];
+    stream.read_exact(&mut header).unwrap();
+    let len = i32::from_be_bytes([header[1], header[2], header[3], header[4]]) as usize;
+    let mut body = vec![0; len - 4];
+    stream.read_exact(&mut body).unwrap();
+    (header[0], body)
+}
+
+fn write_backend_message(stream: &mut std::net::TcpStream, tag: u8, body: &[u8]) {
+    use std::io::Write;
+
+    let mut buf = vec![tag];
+    buf.extend_from_slice(&(body.len() as i32 + 4).to_be_bytes());
+    buf.extend_from_slice(body);
+    stream.write_all(&buf).unwrap();
+}
+
+#[test]
+fn gss_authenticator() {
+    use std::io::Read;
+    use std::net::TcpListener;
+    use std::thread;
+    use tokio_postgres::auth::{AuthenticationExchange, AuthenticationMethod, Authenticator};
+
+    struct TestAuthenticator;
+
+    impl Authenticator for TestAuthenticator {
+        fn start(
+            &self,
+            method: AuthenticationMethod,
+        ) -> Result<Box<dyn AuthenticationExchange>, Box<dyn Error + Sync + Send>> {
+            assert_eq!(method, AuthenticationMethod::Gss);
+            Ok(Box::new(TestExchange))
+        }
+    }
+
+    struct TestExchange;
+
+    impl AuthenticationExchange for TestExchange {
+        fn step(
+            &mut self,
+            data: Option<&[u8]>,
+        ) -> Result<Option<Vec<u8>>, Box<dyn Error + Sync + Send>> {
+            match data {
+                None => Ok(Some(b"hello".to_vec())),
+                Some(b"challenge") => Ok(Some(b"response".to_vec())),
+                Some(b"done") => Ok(None),
+                Some(_) => Err("unexpected token".into()),
+            }
+        }
+    }
+
+    let _ = env_logger::try_init();
+    let mut runtime = Runtime::new().unwrap();
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let server = thread::spawn(move || {
+        let mut stream = listener.accept().unwrap().0;
+
+        let mut len = [0; 4];
+        stream.read_exact(&mut len).unwrap();
+        let mut startup = vec![0; i32::from_be_bytes(len) as usize - 4];
+        stream.read_exact(&mut startup).unwrap();
+
+        write_backend_message(&mut stream, b'R', &7i32.to_be_bytes());
+        assert_eq!(
+            read_frontend_message(&mut stream),
+            (b'p', b"hello".to_vec())
+        );
+
+        let mut body = 8i32.to_be_bytes().to_vec();
+        body.extend_from_slice(b"challenge");
+        write_backend_message(&mut stream, b'R', &body);
+        assert_eq!(
+            read_frontend_message(&mut stream),
+            (b'p', b"response".to_vec())
+        );
+
+        let mut body = 8i32.to_be_bytes().to_vec();
+        body.extend_from_slice(b"done");
+        write_backend_message(&mut stream, b'R', &body);
+        write_backend_message(&mut stream, b'R', &0i32.to_be_bytes());
+        write_backend_message(&mut stream, b'Z', b"I");
+    });
+
+    let mut config = "user=postgres".parse::<tokio_postgres::Config>().unwrap();
+    config.authenticator(TestAuthenticator);
+    let handshake = TcpStream::connect(&addr)
+        .map_err(|e| panic!("{}", e))
+        .and_then(move |s| config.connect_raw(s, NoTls));
+    let (_client, _connection) = runtime.block_on(handshake).unwrap();
+
+    server.join().unwrap();
+}
+
+#[test]
+fn gss_without_authenticator() {
+    use std::io::Read;
+    use std::net::TcpListener;
+    use std::thread;
+
+    let _ = env_logger::try_init();
+    let mut runtime = Runtime::new().unwrap();
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let server = thread::spawn(move || {
+        let mut stream = listener.accept().unwrap().0;
+
+        let mut len = [0; 4];
+        stream.read_exact(&mut len).unwrap();
+        let mut startup = vec![0; i32::from_be_bytes(len) as usize - 4];
+        stream.read_exact(&mut startup).unwrap();
+
+        write_backend_message(&mut stream, b'R', &7i32.to_be_bytes());
+    });
+
+    let config = "user=postgres".parse::<tokio_postgres::Config>().unwrap();
+    let handshake = TcpStream::connect(&addr)
+        .map_err(|e| panic!("{}", e))
+        .and_then(move |s| config.connect_raw(s, NoTls));
+    let err = runtime.block_on(handshake).err().unwrap();
+    assert!(err.to_string().contains("GSSAPI"), "{}", err);
+
+    server.join().unwrap();
+}
+
+#[test]
+#[cfg(feature = "raw-protocol")]
+fn raw_request() {
+    let _ = env_logger::try_init();
+    let mut runtime = Runtime::new().unwrap();
+
+    let (mut client, connection) = runtime.block_on(connect("user=postgres")).unwrap();
+    let connection = connection.map_err(|e| panic!("{}", e));
+    runtime.handle().spawn(connection).unwrap();
+
+    let messages = runtime
+        .block_on(client.raw_request(b'Q', b"SELECT 1\0").collect())
+        .unwrap();
+    let tags = messages.iter().map(|m| m.0).collect::<Vec<_>>();
+    assert_eq!(tags, b"TDCZ");
+    // one column, four bytes long, containing "1"
+    assert_eq!(messages[1].1, b"\0\x01\0\0\0\x011");
+    assert_eq!(messages[2].1, b"SELECT 1\0");
+    assert_eq!(messages[3].1, b"I");
+
+    // the connection is still usable afterwards
+    let stmt = runtime.block_on(client.prepare("SELECT 2")).unwrap();
+    let rows = runtime
+        .block_on(client.query(&stmt, &[]).collect())
+        .unwrap();
+    assert_eq!(rows[0].get::<_, i32>(0), 2);
+}
+
+#[test]
+fn require_server_version() {
+    let _ = env_logger::try_init();
+    let mut runtime = Runtime::new().unwrap();
+
+    let (_client, connection) = runtime.block_on(connect("user=postgres")).unwrap();
+    let version = connection.server_version().unwrap();
+
+    let mut config = "user=postgres".parse::<tokio_postgres::Config>().unwrap();
+    config.require_server_version(Some(version));
+    let connect = TcpStream::connect(&"127.0.0.1:5433".parse().unwrap())
+        .map_err(|e| panic!("{}", e))
+        .and_then(move |s| config.connect_raw(s, NoTls));
+    let _ = runtime.block_on(connect).unwrap();
+
+    let required = Version {
+        major: version.major + 1,
+        minor: 0,
+        patch: 0,
+    };
+    let mut config = "user=postgres".parse::<tokio_postgres::Config>().unwrap();
+    config.require_server_version(Some(required));
+    let connect = TcpStream::connect(&"127.0.0.1:5433".parse().unwrap())
+        .map_err(|e| panic!("{}", e))
+        .and_then(move |s| config.connect_raw(s, NoTls));
+    let err = runtime.block_on(connect).err().unwrap();
+    let cause = err
+        .source()
+        .unwrap()
+        .downcast_ref::<UnsupportedServerVersion>()
+        .unwrap();
+    assert_eq!(cause.found(), Some(version));
+    assert_eq!(cause.required(), required);
+}
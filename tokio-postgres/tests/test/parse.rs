@@ -1,5 +1,5 @@
 use std::time::Duration;
-use tokio_postgres::config::{Config, TargetSessionAttrs};
+use tokio_postgres::config::{Config, ReplicationMode, SslMode, TargetSessionAttrs};
 
 fn check(s: &str, config: &Config) {
     assert_eq!(s.parse::<Config>().expect(s), *config, "`{}`", s);
@@ -36,6 +36,85 @@ fn settings() {
     );
 }
 
+#[test]
+fn options() {
+    check(
+        "options='-c search_path=myschema -c statement_timeout=5000'",
+        Config::new().options("-c search_path=myschema -c statement_timeout=5000"),
+    );
+    check(
+        r"options=-c\ application_name=foo\\\ bar",
+        Config::new().options(r"-c application_name=foo\ bar"),
+    );
+    check(
+        "postgresql:///?options=-c%20search_path%3Dmyschema",
+        Config::new().options("-c search_path=myschema"),
+    );
+}
+
+#[test]
+fn replication() {
+    check(
+        "replication=true",
+        Config::new().replication_mode(Some(ReplicationMode::Physical)),
+    );
+    check(
+        "replication=database",
+        Config::new().replication_mode(Some(ReplicationMode::Logical)),
+    );
+    check("replication=off", Config::new().replication_mode(None));
+    assert!("replication=foo".parse::<Config>().is_err());
+}
+
+#[test]
+fn proxy() {
+    check(
+        "proxy=proxy.example.com:1081 proxy_user=foo proxy_password=bar",
+        Config::new()
+            .proxy(Some(("proxy.example.com", 1081)))
+            .proxy_user("foo")
+            .proxy_password("bar"),
+    );
+    check(
+        "proxy=localhost",
+        Config::new().proxy(Some(("localhost", 1080))),
+    );
+    check("proxy=[::1]:1081", Config::new().proxy(Some(("::1", 1081))));
+    check("proxy=[::1]", Config::new().proxy(Some(("::1", 1080))));
+    assert!("proxy=localhost:foo".parse::<Config>().is_err());
+    assert!("proxy=:1080".parse::<Config>().is_err());
+    for value in &["::1", "::1:1080", "[::1", "[::1]1080", "[::1]:", "[]:1080"] {
+        assert!(format!("proxy={}", value).parse::<Config>().is_err());
+    }
+}
+
+#[test]
+fn ssl_mode() {
+    for &(value, mode) in &[
+        ("disable", SslMode::Disable),
+        ("allow", SslMode::Allow),
+        ("prefer", SslMode::Prefer),
+        ("require", SslMode::Require),
+        ("verify-ca", SslMode::VerifyCa),
+        ("verify-full", SslMode::VerifyFull),
+    ] {
+        check(&format!("sslmode={}", value), Config::new().ssl_mode(mode));
+        check(
+            &format!("postgresql://localhost?sslmode={}", value),
+            Config::new().host("localhost").port(5432).ssl_mode(mode),
+        );
+    }
+    assert!("sslmode=foo".parse::<Config>().is_err());
+}
+
+#[test]
+fn client_encoding() {
+    check(
+        "client_encoding=LATIN1",
+        Config::new().client_encoding("LATIN1"),
+    );
+}
+
 #[test]
 fn url() {
     check("postgresql://", &Config::new());
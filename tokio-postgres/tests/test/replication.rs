@@ -0,0 +1,199 @@
+use futures::{future, Future, Sink, Stream};
+use std::time::SystemTime;
+use tokio::runtime::current_thread::Runtime;
+use tokio_postgres::replication::{standby_status_update, ReplicationMessage};
+use tokio_postgres::types::Lsn;
+use tokio_postgres::SimpleQueryMessage;
+
+use crate::connect;
+
+fn simple_query_row(
+    runtime: &mut Runtime,
+    client: &mut tokio_postgres::Client,
+    query: &str,
+) -> Vec<String> {
+    let messages = runtime
+        .block_on(client.simple_query(query).collect())
+        .unwrap();
+    for message in &messages {
+        if let SimpleQueryMessage::Row(row) = message {
+            return (0..row.len())
+                .map(|i| row.get(i).unwrap_or("").to_string())
+                .collect();
+        }
+    }
+    panic!("no rows returned");
+}
+
+#[test]
+fn physical_replication() {
+    let mut runtime = Runtime::new().unwrap();
+
+    let (mut client, connection) = runtime
+        .block_on(connect("user=postgres replication=true"))
+        .unwrap();
+    let connection = connection.map_err(|e| panic!("{}", e));
+    runtime.handle().spawn(connection).unwrap();
+
+    let (mut other, connection) = runtime.block_on(connect("user=postgres")).unwrap();
+    let connection = connection.map_err(|e| panic!("{}", e));
+    runtime.handle().spawn(connection).unwrap();
+
+    let system = simple_query_row(&mut runtime, &mut client, "IDENTIFY_SYSTEM");
+    let start = system[2].parse::<Lsn>().unwrap();
+
+    let duplex = client.copy_both_simple(&format!("START_REPLICATION PHYSICAL {}", start));
+
+    runtime
+        .block_on(
+            other
+                .simple_query("CREATE TEMPORARY TABLE foo (id INT); INSERT INTO foo VALUES (1)")
+                .collect(),
+        )
+        .unwrap();
+
+    let mut stream = duplex;
+    let xlog_data = loop {
+        let (message, s) = runtime
+            .block_on(stream.into_future())
+            .map_err(|e| e.0)
+            .unwrap();
+        stream = s;
+        match ReplicationMessage::parse(&message.unwrap()).unwrap() {
+            ReplicationMessage::XLogData(xlog_data) => break xlog_data,
+            ReplicationMessage::PrimaryKeepAlive(_) => {}
+        }
+    };
+    assert!(xlog_data.wal_start() >= start);
+    assert!(xlog_data.wal_end() >= xlog_data.wal_start());
+    assert!(!xlog_data.data().is_empty());
+
+    let mut duplex = stream;
+    runtime
+        .block_on(future::poll_fn(|| duplex.close()))
+        .unwrap();
+    runtime.block_on(duplex.collect()).unwrap();
+
+    simple_query_row(&mut runtime, &mut client, "IDENTIFY_SYSTEM");
+}
+
+#[test]
+fn logical_replication() {
+    let mut runtime = Runtime::new().unwrap();
+
+    let (mut client, connection) = runtime
+        .block_on(connect("user=postgres replication=database"))
+        .unwrap();
+    let connection = connection.map_err(|e| panic!("{}", e));
+    runtime.handle().spawn(connection).unwrap();
+
+    let (mut other, connection) = runtime.block_on(connect("user=postgres")).unwrap();
+    let connection = connection.map_err(|e| panic!("{}", e));
+    runtime.handle().spawn(connection).unwrap();
+
+    let slot = simple_query_row(
+        &mut runtime,
+        &mut client,
+        "CREATE_REPLICATION_SLOT logical_replication_test TEMPORARY LOGICAL test_decoding",
+    );
+    let start = slot[1].parse::<Lsn>().unwrap();
+
+    runtime
+        .block_on(
+            other
+                .simple_query(
+                    "DROP TABLE IF EXISTS logical_replication_test;
+                     CREATE TABLE logical_replication_test (id INT PRIMARY KEY);
+                     INSERT INTO logical_replication_test VALUES (42)",
+                )
+                .collect(),
+        )
+        .unwrap();
+
+    let duplex = client.copy_both_simple(&format!(
+        "START_REPLICATION SLOT logical_replication_test LOGICAL {}",
+        start
+    ));
+
+    let mut stream = duplex;
+    let mut last;
+    let mut changes = vec![];
+    loop {
+        let (message, s) = runtime
+            .block_on(stream.into_future())
+            .map_err(|e| e.0)
+            .unwrap();
+        stream = s;
+        match ReplicationMessage::parse(&message.unwrap()).unwrap() {
+            ReplicationMessage::XLogData(xlog_data) => {
+                last = xlog_data.wal_start();
+                let data = String::from_utf8(xlog_data.into_data().to_vec()).unwrap();
+                let commit = data.starts_with("COMMIT");
+                changes.push(data);
+                if commit && changes.iter().any(|c| c.contains("INSERT")) {
+                    break;
+                }
+            }
+            ReplicationMessage::PrimaryKeepAlive(_) => {}
+        }
+    }
+    assert!(changes
+        .iter()
+        .any(|c| c == "table public.logical_replication_test: INSERT: id[integer]:42"));
+
+    let update = standby_status_update(last, last, last, SystemTime::now(), true);
+    let mut stream = runtime.block_on(stream.send(update)).unwrap();
+    loop {
+        let (message, s) = runtime
+            .block_on(stream.into_future())
+            .map_err(|e| e.0)
+            .unwrap();
+        stream = s;
+        if let ReplicationMessage::PrimaryKeepAlive(keepalive) =
+            ReplicationMessage::parse(&message.unwrap()).unwrap()
+        {
+            assert!(keepalive.wal_end() >= last);
+            break;
+        }
+    }
+
+    let mut duplex = stream;
+    runtime
+        .block_on(future::poll_fn(|| duplex.close()))
+        .unwrap();
+    runtime.block_on(duplex.collect()).unwrap();
+
+    let slot = simple_query_row(
+        &mut runtime,
+        &mut other,
+        "SELECT confirmed_flush_lsn FROM pg_replication_slots \
+         WHERE slot_name = 'logical_replication_test'",
+    );
+    assert_eq!(slot[0].parse::<Lsn>().unwrap(), last);
+
+    runtime
+        .block_on(
+            other
+                .simple_query("DROP TABLE logical_replication_test")
+                .collect(),
+        )
+        .unwrap();
+}
+
+#[test]
+fn replication_message_parse() {
+    let mut buf = vec![b'k'];
+    buf.extend_from_slice(&0x0102_0304_0506_0708u64.to_be_bytes());
+    buf.extend_from_slice(&0i64.to_be_bytes());
+    buf.push(1);
+    match ReplicationMessage::parse(&buf.into()).unwrap() {
+        ReplicationMessage::PrimaryKeepAlive(keepalive) => {
+            assert_eq!(keepalive.wal_end(), Lsn::from(0x0102_0304_0506_0708));
+            assert!(keepalive.reply_requested());
+        }
+        _ => panic!("unexpected message"),
+    }
+
+    assert!(ReplicationMessage::parse(&b"k\0"[..].into()).is_err());
+    assert!(ReplicationMessage::parse(&b"?"[..].into()).is_err());
+}
@@ -1,8 +1,13 @@
 use futures::{Future, Stream};
+use std::error::Error;
+use std::io::{self, Read, Write};
+use std::net::{Shutdown, TcpListener, TcpStream};
+use std::sync::mpsc;
+use std::thread;
 use std::time::{Duration, Instant};
 use tokio::runtime::current_thread::Runtime;
 use tokio::timer::Delay;
-use tokio_postgres::error::SqlState;
+use tokio_postgres::error::{ConnectAttempts, SqlState};
 use tokio_postgres::NoTls;
 
 fn smoke_test(s: &str) {
@@ -27,6 +32,50 @@ fn tcp() {
     smoke_test("host=localhost port=5433 user=postgres")
 }
 
+#[test]
+fn ssl_mode_allow() {
+    smoke_test("host=localhost port=5433 user=postgres sslmode=allow");
+
+    // the server rejects the unencrypted session, so a TLS session is attempted as well
+    let mut runtime = Runtime::new().unwrap();
+    let connect = tokio_postgres::connect(
+        "host=localhost port=5433 user=postgres dbname=nonexistent sslmode=allow",
+        NoTls,
+    );
+    let err = runtime.block_on(connect).err().unwrap();
+    let attempts = err
+        .source()
+        .unwrap()
+        .downcast_ref::<ConnectAttempts>()
+        .unwrap()
+        .attempts();
+    assert_eq!(attempts.len(), 2);
+    assert!(attempts[0].1.code().is_some());
+    assert!(attempts[1].1.code().is_none());
+}
+
+#[test]
+fn ssl_mode_verify_full_requires_tls() {
+    let mut runtime = Runtime::new().unwrap();
+    let connect = tokio_postgres::connect(
+        "host=localhost port=5433 user=postgres sslmode=verify-full",
+        NoTls,
+    );
+    assert!(runtime.block_on(connect).is_err());
+}
+
+#[test]
+fn ssl_mode_verify_ca_rejects_unverifying_connector() {
+    let mut runtime = Runtime::new().unwrap();
+    let connect = tokio_postgres::connect(
+        "host=localhost port=5433 user=postgres sslmode=verify-ca",
+        NoTls,
+    );
+    let err = runtime.block_on(connect).err().unwrap();
+    let cause = err.source().unwrap();
+    assert!(cause.to_string().contains("sslmode=verify-ca"), "{}", err);
+}
+
 #[test]
 fn multiple_hosts_one_port() {
     smoke_test("host=foobar.invalid,localhost port=5433 user=postgres");
@@ -71,6 +120,51 @@ fn target_session_attrs_err() {
     runtime.block_on(f).err().unwrap();
 }
 
+#[test]
+fn target_session_attrs_all_hosts_err() {
+    let mut runtime = Runtime::new().unwrap();
+    let f = tokio_postgres::connect(
+        "host=foobar.invalid,localhost port=5433 user=postgres target_session_attrs=read-write
+         options='-c default_transaction_read_only=on'",
+        NoTls,
+    );
+    let err = runtime.block_on(f).err().unwrap();
+    let attempts = err
+        .source()
+        .unwrap()
+        .downcast_ref::<ConnectAttempts>()
+        .unwrap()
+        .attempts();
+    assert_eq!(attempts.len(), 2);
+    assert_eq!(attempts[0].0, "foobar.invalid:5433");
+    assert_eq!(attempts[1].0, "localhost:5433");
+    assert!(attempts[1].1.to_string().contains("does not allow writes"));
+}
+
+#[test]
+fn startup_options() {
+    let mut runtime = Runtime::new().unwrap();
+    let connect = tokio_postgres::connect(
+        r"host=localhost port=5433 user=postgres
+          options='-c application_name=foo\\ bar -c statement_timeout=5000'",
+        NoTls,
+    );
+    let (mut client, connection) = runtime.block_on(connect).unwrap();
+    let connection = connection.map_err(|e| panic!("{}", e));
+    runtime.spawn(connection);
+
+    let stmt = runtime
+        .block_on(client.prepare(
+            "SELECT current_setting('application_name'), current_setting('statement_timeout')",
+        ))
+        .unwrap();
+    let rows = runtime
+        .block_on(client.query(&stmt, &[]).collect())
+        .unwrap();
+    assert_eq!(rows[0].get::<_, &str>(0), "foo bar");
+    assert_eq!(rows[0].get::<_, &str>(1), "5s");
+}
+
 #[test]
 fn cancel_query() {
     let mut runtime = Runtime::new().unwrap();
@@ -100,3 +194,143 @@ fn cancel_query() {
 
     let ((), ()) = runtime.block_on(sleep.join(cancel)).unwrap();
 }
+
+// A minimal SOCKS5 proxy which accepts a single connection, reporting the requested destination.
+fn socks5_proxy(
+    credentials: Option<(&'static str, &'static str)>,
+) -> (u16, mpsc::Receiver<String>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let (tx, rx) = mpsc::channel();
+
+    thread::spawn(move || {
+        let (mut client, _) = listener.accept().unwrap();
+
+        let mut buf = [0; 2];
+        client.read_exact(&mut buf).unwrap();
+        let mut methods = vec![0; buf[1] as usize];
+        client.read_exact(&mut methods).unwrap();
+
+        match credentials {
+            Some((user, password)) => {
+                assert!(methods.contains(&2));
+                client.write_all(&[5, 2]).unwrap();
+                let mut buf = [0; 2];
+                client.read_exact(&mut buf).unwrap();
+                let mut got_user = vec![0; buf[1] as usize];
+                client.read_exact(&mut got_user).unwrap();
+                let mut buf = [0; 1];
+                client.read_exact(&mut buf).unwrap();
+                let mut got_password = vec![0; buf[0] as usize];
+                client.read_exact(&mut got_password).unwrap();
+                if got_user != user.as_bytes() || got_password != password.as_bytes() {
+                    client.write_all(&[1, 1]).unwrap();
+                    return;
+                }
+                client.write_all(&[1, 0]).unwrap();
+            }
+            None => client.write_all(&[5, 0]).unwrap(),
+        }
+
+        let mut buf = [0; 5];
+        client.read_exact(&mut buf).unwrap();
+        assert_eq!(&buf[..4], [5, 1, 0, 3]);
+        let mut host = vec![0; buf[4] as usize];
+        client.read_exact(&mut host).unwrap();
+        let mut port = [0; 2];
+        client.read_exact(&mut port).unwrap();
+        let host = String::from_utf8(host).unwrap();
+        let port = u16::from_be_bytes(port);
+        tx.send(format!("{}:{}", host, port)).unwrap();
+
+        let server = TcpStream::connect((&*host, port)).unwrap();
+        client.write_all(&[5, 0, 0, 1, 127, 0, 0, 1, 0, 0]).unwrap();
+
+        let mut client_read = client.try_clone().unwrap();
+        let mut server_write = server.try_clone().unwrap();
+        thread::spawn(move || {
+            let _ = io::copy(&mut client_read, &mut server_write);
+            let _ = server_write.shutdown(Shutdown::Write);
+        });
+        let (mut server_read, mut client_write) = (server, client);
+        let _ = io::copy(&mut server_read, &mut client_write);
+        let _ = client_write.shutdown(Shutdown::Write);
+    });
+
+    (port, rx)
+}
+
+#[test]
+fn socks5_proxy_connect() {
+    let (port, rx) = socks5_proxy(None);
+    smoke_test(&format!(
+        "host=localhost port=5433 user=postgres proxy=127.0.0.1:{}",
+        port
+    ));
+    assert_eq!(rx.recv().unwrap(), "localhost:5433");
+}
+
+#[test]
+fn socks5_proxy_authentication() {
+    let (port, rx) = socks5_proxy(Some(("proxyuser", "secret")));
+    smoke_test(&format!(
+        "host=localhost port=5433 user=postgres proxy=127.0.0.1:{} proxy_user=proxyuser \
+         proxy_password=secret",
+        port
+    ));
+    assert_eq!(rx.recv().unwrap(), "localhost:5433");
+
+    let (port, _rx) = socks5_proxy(Some(("proxyuser", "secret")));
+    let mut runtime = Runtime::new().unwrap();
+    let connect = tokio_postgres::connect(
+        &format!(
+            "host=localhost port=5433 user=postgres proxy=127.0.0.1:{} proxy_user=proxyuser \
+             proxy_password=wrong",
+            port
+        ),
+        NoTls,
+    );
+    let err = runtime.block_on(connect).err().unwrap();
+    assert!(format!("{:?}", err).contains("SOCKS5 proxy authentication failed"));
+}
+
+#[test]
+fn socks5_proxy_password_without_user() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+
+    let mut runtime = Runtime::new().unwrap();
+    let connect = tokio_postgres::connect(
+        &format!(
+            "host=localhost port=5433 user=postgres proxy=127.0.0.1:{} proxy_password=secret",
+            port
+        ),
+        NoTls,
+    );
+    let err = runtime.block_on(connect).err().unwrap();
+    assert!(err.to_string().contains("proxy user missing"), "{}", err);
+}
+
+#[test]
+fn socks5_proxy_timeout() {
+    // the proxy accepts the connection but never responds to the handshake
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    thread::spawn(move || {
+        let (_client, _) = listener.accept().unwrap();
+        thread::sleep(Duration::from_secs(30));
+    });
+
+    let start = Instant::now();
+    let mut runtime = Runtime::new().unwrap();
+    let connect = tokio_postgres::connect(
+        &format!(
+            "host=localhost port=5433 user=postgres proxy=127.0.0.1:{} connect_timeout=1",
+            port
+        ),
+        NoTls,
+    );
+    let err = runtime.block_on(connect).err().unwrap();
+    assert!(start.elapsed() < Duration::from_secs(10));
+    assert!(format!("{:?}", err).contains("TimedOut"), "{:?}", err);
+}
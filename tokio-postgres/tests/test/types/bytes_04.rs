@@ -0,0 +1,52 @@
+use bytes::Bytes;
+use futures::{Future, Stream};
+use tokio::runtime::current_thread::Runtime;
+use tokio_postgres::types::Format;
+
+use crate::connect;
+use crate::types::test_type;
+
+#[test]
+fn test_bytes_params() {
+    test_type(
+        "BYTEA",
+        &[
+            (Some(Bytes::from(&b"\x00\x01foo"[..])), "'\\x0001666f6f'"),
+            (Some(Bytes::new()), "''"),
+            (None, "NULL"),
+        ],
+    )
+}
+
+#[test]
+fn get_bytes() {
+    let mut runtime = Runtime::new().unwrap();
+
+    let (mut client, connection) = runtime.block_on(connect("user=postgres")).unwrap();
+    let connection = connection.map_err(|e| panic!("{}", e));
+    runtime.handle().spawn(connection).unwrap();
+
+    let stmt = runtime
+        .block_on(client.prepare("SELECT '\\x0001666f6f'::BYTEA, NULL::BYTEA, 1::INT"))
+        .unwrap();
+    let rows = runtime
+        .block_on(client.query(&stmt, &[]).collect())
+        .unwrap();
+
+    assert_eq!(
+        rows[0].get_bytes(0).unwrap(),
+        Some(Bytes::from(&b"\x00\x01foo"[..]))
+    );
+    assert_eq!(rows[0].get_bytes(1).unwrap(), None);
+    assert!(rows[0].get_bytes(2).is_err());
+    assert!(rows[0].get_bytes(3).is_err());
+
+    let stmt = runtime
+        .block_on(client.prepare_with_result_formats("SELECT '\\x0001'::BYTEA", &[Format::Text]))
+        .unwrap();
+    let rows = runtime
+        .block_on(client.query(&stmt, &[]).collect())
+        .unwrap();
+    assert!(rows[0].get_bytes(0).is_err());
+    assert_eq!(rows[0].get::<_, Vec<u8>>(0), [0, 1]);
+}
@@ -1,6 +1,9 @@
 use chrono_04::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
-use tokio_postgres::types::{Date, Timestamp};
+use futures::{Future, Stream};
+use tokio::runtime::current_thread::Runtime;
+use tokio_postgres::types::{Date, Range, RangeBound, Timestamp};
 
+use crate::connect;
 use crate::types::test_type;
 
 #[test]
@@ -103,11 +106,32 @@ fn test_date_params() {
             make_check("'1970-01-01'"),
             make_check("'1965-09-25'"),
             make_check("'2010-02-09'"),
+            (Some(NaiveDate::from_ymd(-43, 3, 15)), "'0044-03-15 BC'"),
             (None, "NULL"),
         ],
     );
 }
 
+#[test]
+fn test_infinite_date() {
+    let mut runtime = Runtime::new().unwrap();
+
+    let (mut client, connection) = runtime.block_on(connect("user=postgres")).unwrap();
+    let connection = connection.map_err(|e| panic!("{}", e));
+    runtime.spawn(connection);
+
+    let stmt = runtime
+        .block_on(client.prepare("SELECT 'infinity'::DATE, '-infinity'::DATE"))
+        .unwrap();
+    let rows = runtime
+        .block_on(client.query(&stmt, &[]).collect())
+        .unwrap();
+    for idx in 0..2 {
+        let err = rows[0].try_get::<_, NaiveDate>(idx).err().unwrap();
+        assert!(err.to_string().contains("infinite"), "{}", err);
+    }
+}
+
 #[test]
 fn test_with_special_date_params() {
     fn make_check(date: &str) -> (Date<NaiveDate>, &str) {
@@ -146,3 +170,29 @@ fn test_time_params() {
         ],
     );
 }
+
+#[test]
+fn test_daterange_params() {
+    let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+    test_type(
+        "DATERANGE",
+        &[
+            (
+                Some(Range::Nonempty(
+                    RangeBound::Inclusive(date(2019, 1, 1)),
+                    RangeBound::Exclusive(date(2019, 2, 1)),
+                )),
+                "'[2019-01-01,2019-02-01)'",
+            ),
+            (
+                Some(Range::Nonempty(
+                    RangeBound::Inclusive(date(2019, 1, 1)),
+                    RangeBound::Unbounded,
+                )),
+                "'[2019-01-01,)'",
+            ),
+            (Some(Range::Empty), "'empty'"),
+            (None, "NULL"),
+        ],
+    );
+}
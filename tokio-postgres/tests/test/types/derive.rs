@@ -0,0 +1,134 @@
+use futures::{Future, Stream};
+use tokio::runtime::current_thread::Runtime;
+use tokio_postgres::row::FromRow;
+use tokio_postgres::types::{Format, FromSql, ToSql, Type, WrongType};
+
+use crate::connect;
+use crate::types::test_type;
+
+#[derive(Debug, PartialEq, ToSql, FromSql)]
+#[postgres(transparent)]
+struct UserId(i32);
+
+#[derive(Debug, PartialEq, ToSql, FromSql)]
+#[postgres(transparent)]
+struct UserName(String);
+
+#[derive(Debug, PartialEq, FromSql)]
+#[postgres(transparent)]
+struct Nickname(Option<String>);
+
+#[test]
+fn transparent() {
+    test_type(
+        "INT4",
+        &[
+            (Some(UserId(1)), "1"),
+            (Some(UserId(-42)), "-42"),
+            (None, "NULL"),
+        ],
+    );
+    test_type(
+        "TEXT",
+        &[
+            (Some(UserName("alice".to_string())), "'alice'"),
+            (None, "NULL"),
+        ],
+    );
+}
+
+#[test]
+fn transparent_accepts() {
+    assert!(<UserId as ToSql>::accepts(&Type::INT4));
+    assert!(!<UserId as ToSql>::accepts(&Type::INT8));
+    assert!(<UserId as FromSql>::accepts(&Type::INT4));
+    assert!(!<UserId as FromSql>::accepts(&Type::TEXT));
+
+    let mut runtime = Runtime::new().unwrap();
+
+    let handshake = connect("user=postgres");
+    let (mut client, connection) = runtime.block_on(handshake).unwrap();
+    let connection = connection.map_err(|e| panic!("{}", e));
+    runtime.spawn(connection);
+
+    let stmt = runtime.block_on(client.prepare("SELECT $1::INT8")).unwrap();
+    let err = runtime
+        .block_on(client.query(&stmt, &[&UserId(1)]).collect())
+        .err()
+        .unwrap();
+    assert!(err
+        .into_source()
+        .unwrap()
+        .downcast_ref::<WrongType>()
+        .is_some());
+}
+
+#[test]
+fn transparent_text_and_null() {
+    let mut runtime = Runtime::new().unwrap();
+
+    let handshake = connect("user=postgres");
+    let (mut client, connection) = runtime.block_on(handshake).unwrap();
+    let connection = connection.map_err(|e| panic!("{}", e));
+    runtime.spawn(connection);
+
+    let stmt = runtime
+        .block_on(client.prepare_with_result_formats(
+            "SELECT 'alice'::TEXT, NULL::TEXT",
+            &[Format::Text, Format::Text],
+        ))
+        .unwrap();
+    let row = runtime.block_on(client.query_one(&stmt, &[])).unwrap();
+    assert_eq!(row.get::<_, UserName>(0), UserName("alice".to_string()));
+    assert_eq!(
+        row.get::<_, Nickname>(0),
+        Nickname(Some("alice".to_string()))
+    );
+    assert_eq!(row.get::<_, Nickname>(1), Nickname(None));
+    assert!(row.try_get::<_, UserName>(1).is_err());
+
+    let stmt = runtime
+        .block_on(client.prepare("SELECT NULL::TEXT"))
+        .unwrap();
+    let row = runtime.block_on(client.query_one(&stmt, &[])).unwrap();
+    assert_eq!(row.get::<_, Nickname>(0), Nickname(None));
+}
+
+#[derive(Debug, PartialEq, FromRow)]
+struct User {
+    id: i32,
+    #[postgres(column = "user_name")]
+    name: String,
+    email: Option<String>,
+}
+
+#[test]
+fn from_row() {
+    let mut runtime = Runtime::new().unwrap();
+
+    let handshake = connect("user=postgres");
+    let (mut client, connection) = runtime.block_on(handshake).unwrap();
+    let connection = connection.map_err(|e| panic!("{}", e));
+    runtime.spawn(connection);
+
+    let stmt = runtime
+        .block_on(client.prepare("SELECT 1::INT4 AS id, 'alice' AS user_name, NULL::TEXT AS email"))
+        .unwrap();
+    let row = runtime.block_on(client.query_one(&stmt, &[])).unwrap();
+    assert_eq!(
+        User::from_row(&row).unwrap(),
+        User {
+            id: 1,
+            name: "alice".to_string(),
+            email: None,
+        }
+    );
+
+    let stmt = runtime
+        .block_on(client.prepare("SELECT 1::INT4 AS id, 'alice' AS name, NULL::TEXT AS email"))
+        .unwrap();
+    let row = runtime.block_on(client.query_one(&stmt, &[])).unwrap();
+    let err = User::from_row(&row).unwrap_err();
+    assert!(err.to_string().contains("user_name"));
+    assert!(err.to_string().contains("`name`"));
+}
@@ -0,0 +1,96 @@
+use futures::{Future, Stream};
+use std::collections::HashMap;
+use tokio::runtime::current_thread::Runtime;
+use tokio_postgres::types::JsonObject;
+
+use crate::connect;
+use crate::types::test_type;
+
+fn object<T>(entries: &[(&str, T)]) -> JsonObject<T>
+where
+    T: Clone,
+{
+    JsonObject(
+        entries
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.clone()))
+            .collect(),
+    )
+}
+
+#[test]
+fn test_json_object_params() {
+    test_type(
+        "JSON",
+        &[
+            (
+                Some(object(&[
+                    ("a", "foo".to_string()),
+                    ("b", "\"\\\n\u{1}é".to_string()),
+                ])),
+                "'{\"a\": \"foo\", \"b\": \"\\\"\\\\\\n\\u0001\\u00e9\"}'",
+            ),
+            (Some(object::<String>(&[])), "'{}'"),
+            (None, "NULL"),
+        ],
+    )
+}
+
+#[test]
+fn test_jsonb_object_params() {
+    test_type(
+        "JSONB",
+        &[
+            (
+                Some(object(&[("a", 1i64), ("b", -20)])),
+                "'{\"a\": 1, \"b\": -20}'",
+            ),
+            (Some(object::<i64>(&[])), "'{}'"),
+            (None, "NULL"),
+        ],
+    );
+    test_type(
+        "JSONB",
+        &[(
+            Some(object(&[("a", Some(true)), ("b", None)])),
+            "'{\"a\": true, \"b\": null}'",
+        )],
+    );
+    test_type(
+        "JSONB",
+        &[(Some(object(&[("a", 1.5f64)])), "'{\"a\": 1.5}'")],
+    );
+}
+
+#[test]
+fn test_json_object_rejects() {
+    let mut runtime = Runtime::new().unwrap();
+
+    let handshake = connect("user=postgres");
+    let (mut client, connection) = runtime.block_on(handshake).unwrap();
+    let connection = connection.map_err(|e| panic!("{}", e));
+    runtime.spawn(connection);
+
+    let prepare = client.prepare(
+        "SELECT '{\"a\": 1, \"b\": \"x\"}'::JSONB, '[\"a\"]'::JSON, '{\"a\": {\"b\": [1]}}'::JSONB, \
+         '{\"a\": \"\\ud83d\\ude00\"}'::JSON",
+    );
+    let stmt = runtime.block_on(prepare).unwrap();
+    let query = client.query(&stmt, &[]).collect();
+    let rows = runtime.block_on(query).unwrap();
+
+    assert!(rows[0].try_get::<_, JsonObject<i32>>(0).is_err());
+    assert!(rows[0].try_get::<_, JsonObject<i32>>(1).is_err());
+    assert!(rows[0].try_get::<_, JsonObject<String>>(2).is_err());
+    assert_eq!(
+        rows[0].get::<_, JsonObject<String>>(3),
+        object(&[("a", "\u{1f600}".to_string())])
+    );
+
+    let mut map = HashMap::new();
+    map.insert("a".to_string(), 1.0 / 0.0);
+    let prepare = client.prepare("SELECT $1::JSONB");
+    let stmt = runtime.block_on(prepare).unwrap();
+    let query = client.query(&stmt, &[&JsonObject(map)]).collect();
+    assert!(runtime.block_on(query).is_err());
+}
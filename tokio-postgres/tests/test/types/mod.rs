@@ -1,5 +1,5 @@
 use futures::{Future, Stream};
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::error::Error;
 use std::f32;
 use std::f64;
@@ -9,20 +9,34 @@ use std::result;
 use std::time::{Duration, UNIX_EPOCH};
 use tokio::runtime::current_thread::Runtime;
 use tokio_postgres::to_sql_checked;
-use tokio_postgres::types::{FromSql, FromSqlOwned, IsNull, Kind, ToSql, Type, WrongType};
+use tokio_postgres::types::{
+    Array, ArrayIter, Bits, Dimension, EncodedText, Format, FromSql, FromSqlOwned, Interval,
+    IsNull, Kind, Lsn, Micros, NumericRaw, PgTimestamp, Range, RangeBound, Raw, Record, TextArray,
+    TimestampMillis, ToSql, TxidSnapshot, Type, WasNull, WrongType,
+};
 
 use crate::connect;
 
 #[cfg(feature = "with-bit-vec-0_7")]
 mod bit_vec_07;
+#[cfg(feature = "with-bytes-0_4")]
+mod bytes_04;
 #[cfg(feature = "with-chrono-0_4")]
 mod chrono_04;
+#[cfg(feature = "derive")]
+mod derive;
 #[cfg(feature = "with-eui48-0_4")]
 mod eui48_04;
 #[cfg(feature = "with-geo-0_10")]
 mod geo_010;
+#[cfg(feature = "json-object")]
+mod json_object;
+#[cfg(feature = "with-ndarray-0_12")]
+mod ndarray_012;
 #[cfg(feature = "with-serde_json-1")]
 mod serde_json_1;
+#[cfg(feature = "with-time-0_1")]
+mod time_01;
 #[cfg(feature = "with-uuid-0_7")]
 mod uuid_07;
 
@@ -63,6 +77,32 @@ fn test_bool_params() {
     );
 }
 
+#[test]
+fn test_bool_text_format() {
+    assert!(bool::from_sql(&Type::BOOL, &[1]).unwrap());
+    assert!(!bool::from_sql(&Type::BOOL, &[0]).unwrap());
+    assert!(bool::from_sql(&Type::BOOL, b"t").is_err());
+    assert!(bool::from_sql(&Type::BOOL, &[2]).is_err());
+    assert!(bool::from_sql_text(&Type::BOOL, b"true").is_err());
+
+    let mut runtime = Runtime::new().unwrap();
+
+    let handshake = connect("user=postgres");
+    let (mut client, connection) = runtime.block_on(handshake).unwrap();
+    let connection = connection.map_err(|e| panic!("{}", e));
+    runtime.spawn(connection);
+
+    let prepare = client.prepare_with_result_formats(
+        "SELECT true, false, NULL::BOOL",
+        &[Format::Text, Format::Text, Format::Text],
+    );
+    let stmt = runtime.block_on(prepare).unwrap();
+    let row = runtime.block_on(client.query_one(&stmt, &[])).unwrap();
+    assert!(row.get::<_, bool>(0));
+    assert!(!row.get::<_, bool>(1));
+    assert_eq!(row.get::<_, Option<bool>>(2), None);
+}
+
 #[test]
 fn test_i8_params() {
     test_type("\"char\"", &[(Some('a' as i8), "'a'"), (None, "NULL")]);
@@ -119,6 +159,78 @@ fn test_oid_params() {
     );
 }
 
+#[test]
+fn test_reg_params() {
+    let mut runtime = Runtime::new().unwrap();
+
+    let handshake = connect("user=postgres");
+    let (mut client, connection) = runtime.block_on(handshake).unwrap();
+    let connection = connection.map_err(|e| panic!("{}", e));
+    runtime.spawn(connection);
+
+    let prepare = client.prepare(
+        "SELECT 'pg_class'::REGCLASS, 'pg_class'::REGCLASS::OID, 'int4'::REGTYPE, $1::REGCLASS::TEXT",
+    );
+    let stmt = runtime.block_on(prepare).unwrap();
+    let query = client.query(&stmt, &[&1259u32]).collect();
+    let rows = runtime.block_on(query).unwrap();
+
+    let regclass: u32 = rows[0].get(0);
+    assert_eq!(regclass, rows[0].get::<_, u32>(1));
+    assert_eq!(rows[0].get::<_, u32>(2), Type::INT4.oid());
+    assert_eq!(rows[0].get::<_, &str>(3), "pg_class");
+}
+
+#[test]
+fn test_lsn_params() {
+    test_type(
+        "PG_LSN",
+        &[
+            (Some(Lsn::from(0x0000_0001_6B37_4D48)), "'1/6B374D48'"),
+            (
+                Some(Lsn::from(0xFFFF_FFFF_FFFF_FFFF)),
+                "'FFFFFFFF/FFFFFFFF'",
+            ),
+            (None, "NULL"),
+        ],
+    );
+}
+
+#[test]
+fn lsn_display() {
+    let lsn = Lsn::from(0x0000_0001_6B37_4D48);
+    assert_eq!(lsn.to_string(), "1/6B374D48");
+    assert_eq!("1/6B374D48".parse::<Lsn>().unwrap(), lsn);
+    assert!("1/6B374D4G".parse::<Lsn>().is_err());
+    assert!(Lsn::from(0x0000_0000_FFFF_FFFF) < lsn);
+}
+
+#[test]
+fn test_txid_snapshot_params() {
+    test_type(
+        "TXID_SNAPSHOT",
+        &[
+            (
+                Some(TxidSnapshot {
+                    xmin: 10,
+                    xmax: 20,
+                    xip: vec![10, 14, 15],
+                }),
+                "'10:20:10,14,15'",
+            ),
+            (
+                Some(TxidSnapshot {
+                    xmin: 5,
+                    xmax: 5,
+                    xip: vec![],
+                }),
+                "'5:5:'",
+            ),
+            (None, "NULL"),
+        ],
+    );
+}
+
 #[test]
 fn test_i64_params() {
     test_type(
@@ -131,6 +243,334 @@ fn test_i64_params() {
     );
 }
 
+#[test]
+fn test_bits_params() {
+    test_type(
+        "VARBIT",
+        &[
+            (Some(Bits(vec![])), "B''"),
+            (Some(Bits(vec![true, false, true])), "B'101'"),
+            (
+                Some(Bits(vec![
+                    false, true, true, false, true, false, false, true, true, true,
+                ])),
+                "B'0110100111'",
+            ),
+            (None, "NULL"),
+        ],
+    );
+    test_type(
+        "BIT(9)",
+        &[
+            (
+                Some(Bits(vec![
+                    true, true, false, false, false, false, false, false, true,
+                ])),
+                "B'110000001'",
+            ),
+            (None, "NULL"),
+        ],
+    );
+}
+
+#[test]
+fn test_timestamp_millis_params() {
+    test_type(
+        "TIMESTAMP WITH TIME ZONE",
+        &[
+            (Some(TimestampMillis(0)), "'1970-01-01 00:00:00Z'"),
+            (
+                Some(TimestampMillis(1_234_567_890_123)),
+                "'2009-02-13 23:31:30.123Z'",
+            ),
+            (Some(TimestampMillis(-1)), "'1969-12-31 23:59:59.999Z'"),
+            (
+                Some(TimestampMillis(946_684_800_000)),
+                "'2000-01-01 00:00:00Z'",
+            ),
+            (None, "NULL"),
+        ],
+    );
+}
+
+#[test]
+fn test_pg_timestamp_params() {
+    for ty in &["TIMESTAMP", "TIMESTAMPTZ"] {
+        test_type(
+            ty,
+            &[
+                (Some(PgTimestamp(0)), "'2000-01-01 00:00:00Z'"),
+                (Some(PgTimestamp(-1)), "'1999-12-31 23:59:59.999999Z'"),
+                (
+                    Some(PgTimestamp(-PgTimestamp::UNIX_EPOCH_OFFSET_MICROS)),
+                    "'1970-01-01 00:00:00Z'",
+                ),
+                (Some(PgTimestamp::INFINITY), "'infinity'"),
+                (Some(PgTimestamp::NEG_INFINITY), "'-infinity'"),
+                (None, "NULL"),
+            ],
+        );
+    }
+}
+
+#[test]
+fn timestamp_millis_errors() {
+    let mut runtime = Runtime::new().unwrap();
+
+    let (mut client, connection) = runtime.block_on(connect("user=postgres")).unwrap();
+    let connection = connection.map_err(|e| panic!("{}", e));
+    runtime.handle().spawn(connection).unwrap();
+
+    let stmt = runtime
+        .block_on(client.prepare(
+            "SELECT '1969-12-31 23:59:59.9995Z'::TIMESTAMPTZ, 'infinity'::TIMESTAMPTZ, $1::TIMESTAMPTZ",
+        ))
+        .unwrap();
+
+    let rows = runtime
+        .block_on(client.query(&stmt, &[&None::<TimestampMillis>]).collect())
+        .unwrap();
+    assert_eq!(rows[0].get::<_, TimestampMillis>(0), TimestampMillis(-1));
+    assert!(rows[0].try_get::<_, TimestampMillis>(1).is_err());
+
+    let result = runtime.block_on(
+        client
+            .query(&stmt, &[&TimestampMillis(i64::max_value())])
+            .collect(),
+    );
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_tstzrange_params() {
+    let time = |secs| UNIX_EPOCH + Duration::from_secs(secs);
+    test_type(
+        "TSTZRANGE",
+        &[
+            (
+                Some(Range::Nonempty(
+                    RangeBound::Inclusive(time(1_546_300_800)),
+                    RangeBound::Exclusive(time(1_546_387_200)),
+                )),
+                "'[2019-01-01 00:00:00Z,2019-01-02 00:00:00Z)'",
+            ),
+            (
+                Some(Range::Nonempty(
+                    RangeBound::Exclusive(time(1_546_300_800)),
+                    RangeBound::Inclusive(time(1_546_387_200)),
+                )),
+                "'(2019-01-01 00:00:00Z,2019-01-02 00:00:00Z]'",
+            ),
+            (
+                Some(Range::Nonempty(
+                    RangeBound::Inclusive(time(1_546_300_800)),
+                    RangeBound::Unbounded,
+                )),
+                "'[2019-01-01 00:00:00Z,)'",
+            ),
+            (
+                Some(Range::Nonempty(
+                    RangeBound::Unbounded,
+                    RangeBound::Unbounded,
+                )),
+                "'(,)'",
+            ),
+            (Some(Range::Empty), "'empty'"),
+            (None, "NULL"),
+        ],
+    );
+}
+
+#[test]
+fn test_int4range_params() {
+    test_type(
+        "INT4RANGE",
+        &[
+            (
+                Some(Range::Nonempty(
+                    RangeBound::Inclusive(1i32),
+                    RangeBound::Exclusive(10),
+                )),
+                "'[1,10)'",
+            ),
+            (Some(Range::Empty), "'[1,1)'"),
+            (None, "NULL"),
+        ],
+    );
+}
+
+#[test]
+fn test_numeric_raw_params() {
+    let numeric = |mantissa, scale| Some(NumericRaw { mantissa, scale });
+    test_type(
+        "NUMERIC",
+        &[
+            (numeric(0, 0), "0"),
+            (numeric(0, 3), "0.000"),
+            (numeric(1250, 2), "12.50"),
+            (numeric(-1250, 2), "-12.50"),
+            (numeric(1, 5), "0.00001"),
+            (numeric(123_456_789, 4), "12345.6789"),
+            (numeric(100_000_000, 0), "100000000"),
+            (numeric(-7, 0), "-7"),
+            (
+                numeric(i128::max_value(), 0),
+                "170141183460469231731687303715884105727",
+            ),
+            (
+                numeric(i128::min_value(), 10),
+                "-17014118346046923173168730371.5884105728",
+            ),
+            (None, "NULL"),
+        ],
+    );
+}
+
+#[test]
+fn numeric_raw_errors() {
+    let mut runtime = Runtime::new().unwrap();
+
+    let handshake = connect("user=postgres");
+    let (mut client, connection) = runtime.block_on(handshake).unwrap();
+    let connection = connection.map_err(|e| panic!("{}", e));
+    runtime.spawn(connection);
+
+    let prepare = client.prepare("SELECT 'NaN'::NUMERIC, 1e40::NUMERIC, 1e-40::NUMERIC");
+    let stmt = runtime.block_on(prepare).unwrap();
+    let query = client.query(&stmt, &[]).collect();
+    let rows = runtime.block_on(query).unwrap();
+
+    assert!(rows[0].try_get::<_, NumericRaw>(0).is_err());
+    assert!(rows[0].try_get::<_, NumericRaw>(1).is_err());
+    assert!(rows[0].try_get::<_, NumericRaw>(2).is_ok());
+}
+
+#[test]
+fn numeric_raw_text() {
+    let mut runtime = Runtime::new().unwrap();
+
+    let handshake = connect("user=postgres");
+    let (mut client, connection) = runtime.block_on(handshake).unwrap();
+    let connection = connection.map_err(|e| panic!("{}", e));
+    runtime.spawn(connection);
+
+    let prepare = client.prepare_with_result_formats(
+        "SELECT 12.50::NUMERIC, -0.001::NUMERIC, 7::NUMERIC, 'NaN'::NUMERIC, \
+         170141183460469231731687303715884105728::NUMERIC, '12.50'::TEXT",
+        &[Format::Text; 6],
+    );
+    let stmt = runtime.block_on(prepare).unwrap();
+    let query = client.query(&stmt, &[]).collect();
+    let rows = runtime.block_on(query).unwrap();
+
+    let numeric = |mantissa, scale| NumericRaw { mantissa, scale };
+    assert_eq!(rows[0].get::<_, NumericRaw>(0), numeric(1250, 2));
+    assert_eq!(rows[0].get::<_, NumericRaw>(1), numeric(-1, 3));
+    assert_eq!(rows[0].get::<_, NumericRaw>(2), numeric(7, 0));
+    for idx in 3..6 {
+        assert!(rows[0].try_get::<_, NumericRaw>(idx).is_err());
+    }
+}
+
+#[test]
+fn test_interval_params() {
+    let interval = |months, days, microseconds| {
+        Some(Interval {
+            months,
+            days,
+            microseconds,
+        })
+    };
+    test_type(
+        "INTERVAL",
+        &[
+            (interval(0, 0, 0), "'0'"),
+            (
+                interval(14, 3, 14_706_500_000),
+                "'1 year 2 mons 3 days 04:05:06.5'",
+            ),
+            (interval(0, -1, 7_200_000_000), "'-1 days +02:00:00'"),
+            (interval(-1, 0, -1), "'-1 mon -00:00:00.000001'"),
+            (None, "NULL"),
+        ],
+    );
+}
+
+#[test]
+fn interval_display() {
+    let mut runtime = Runtime::new().unwrap();
+
+    let handshake = connect("user=postgres");
+    let (mut client, connection) = runtime.block_on(handshake).unwrap();
+    let connection = connection.map_err(|e| panic!("{}", e));
+    runtime.spawn(connection);
+
+    let intervals = [
+        "0",
+        "1 day",
+        "2 days 03:04:05",
+        "1 year 2 mons 3 days 04:05:06.5",
+        "-1 year -2 mons",
+        "-1 days +02:00:00",
+        "1 day -02:00:00.25",
+        "-3 mons 4 days",
+        "100 hours 0.000001 seconds",
+        "-00:00:00.1",
+        "11 mons",
+    ];
+    let stmt = runtime
+        .block_on(client.prepare("SELECT $1::TEXT::INTERVAL, $1::TEXT::INTERVAL::TEXT"))
+        .unwrap();
+    for interval in &intervals {
+        let rows = runtime
+            .block_on(client.query(&stmt, &[interval]).collect())
+            .unwrap();
+        assert_eq!(
+            rows[0].get::<_, Interval>(0).to_string(),
+            rows[0].get::<_, &str>(1),
+        );
+    }
+}
+
+#[test]
+fn test_micros_params() {
+    test_type(
+        "BIGINT",
+        &[
+            (Some(Micros(Duration::from_micros(0))), "0"),
+            (Some(Micros(Duration::from_micros(1_500_000))), "1500000"),
+            (
+                Some(Micros(Duration::from_micros(9_223_372_036_854_775_807))),
+                "9223372036854775807",
+            ),
+            (None, "NULL"),
+        ],
+    );
+
+    let mut runtime = Runtime::new().unwrap();
+
+    let handshake = connect("user=postgres");
+    let (mut client, connection) = runtime.block_on(handshake).unwrap();
+    let connection = connection.map_err(|e| panic!("{}", e));
+    runtime.spawn(connection);
+
+    let stmt = runtime
+        .block_on(client.prepare("SELECT $1::BIGINT"))
+        .unwrap();
+    let overflow = Micros(Duration::from_secs(u64::max_value()));
+    assert!(runtime
+        .block_on(client.query(&stmt, &[&overflow]).collect())
+        .is_err());
+
+    let stmt = runtime
+        .block_on(client.prepare("SELECT -1::BIGINT"))
+        .unwrap();
+    let rows = runtime
+        .block_on(client.query(&stmt, &[]).collect())
+        .unwrap();
+    assert!(rows[0].try_get::<_, Micros>(0).is_err());
+}
+
 #[test]
 fn test_f32_params() {
     test_type(
@@ -160,12 +600,399 @@ fn test_f64_params() {
 #[test]
 fn test_varchar_params() {
     test_type(
-        "VARCHAR",
+        "VARCHAR",
+        &[
+            (Some("hello world".to_owned()), "'hello world'"),
+            (
+                Some("イロハニホヘト チリヌルヲ".to_owned()),
+                "'イロハニホヘト チリヌルヲ'",
+            ),
+            (None, "NULL"),
+        ],
+    );
+}
+
+#[test]
+fn test_text_params() {
+    test_type(
+        "TEXT",
+        &[
+            (Some("hello world".to_owned()), "'hello world'"),
+            (
+                Some("イロハニホヘト チリヌルヲ".to_owned()),
+                "'イロハニホヘト チリヌルヲ'",
+            ),
+            (None, "NULL"),
+        ],
+    );
+}
+
+#[test]
+fn test_jsonpath_params() {
+    test_type(
+        "JSONPATH",
+        &[
+            (Some("$.\"foo\"[*]".to_owned()), "'$.\"foo\"[*]'"),
+            (
+                Some("strict $.\"a\"?(@ > 1)".to_owned()),
+                "'strict $.\"a\"?(@ > 1)'",
+            ),
+            (None, "NULL"),
+        ],
+    );
+}
+
+#[test]
+fn test_borrowed_text() {
+    let mut runtime = Runtime::new().unwrap();
+
+    let handshake = connect("user=postgres");
+    let (mut client, connection) = runtime.block_on(handshake).unwrap();
+    let connection = connection.map_err(|e| panic!("{}", e));
+    runtime.spawn(connection);
+
+    let prepare = client.prepare("SELECT 'foo', 'bar'::VARCHAR, 'baz'::CHAR(4)");
+    let stmt = runtime.block_on(prepare).unwrap();
+    let query = client.query(&stmt, &[]).collect();
+    let rows = runtime.block_on(query).unwrap();
+    let s: &str = rows[0].get(0);
+    assert_eq!(s, "foo");
+    let s: &str = rows[0].get(1);
+    assert_eq!(s, "bar");
+    let s: &str = rows[0].get(2);
+    assert_eq!(s, "baz ");
+}
+
+#[test]
+fn test_bpchar_params() {
+    let mut runtime = Runtime::new().unwrap();
+
+    let handshake = connect("user=postgres");
+    let (mut client, connection) = runtime.block_on(handshake).unwrap();
+    let connection = connection.map_err(|e| panic!("{}", e));
+    runtime.spawn(connection);
+
+    let batch = client
+        .simple_query(
+            "CREATE TEMPORARY TABLE foo (
+                id SERIAL PRIMARY KEY,
+                b CHAR(5)
+            )",
+        )
+        .for_each(|_| Ok(()));
+    runtime.block_on(batch).unwrap();
+
+    let prepare = client.prepare("INSERT INTO foo (b) VALUES ($1), ($2), ($3)");
+    let stmt = runtime.block_on(prepare).unwrap();
+    let execute = client.execute(&stmt, &[&"12345", &"123", &None::<&'static str>]);
+    runtime.block_on(execute).unwrap();
+
+    let prepare = client.prepare("SELECT b FROM foo ORDER BY id");
+    let stmt = runtime.block_on(prepare).unwrap();
+    let query = client.query(&stmt, &[]).collect();
+    let res = runtime.block_on(query).unwrap();
+
+    assert_eq!(
+        vec![Some("12345".to_owned()), Some("123  ".to_owned()), None],
+        res.iter().map(|row| row.get(0)).collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn trailing_spaces() {
+    let mut runtime = Runtime::new().unwrap();
+
+    let handshake = connect("user=postgres");
+    let (mut client, connection) = runtime.block_on(handshake).unwrap();
+    let connection = connection.map_err(|e| panic!("{}", e));
+    runtime.spawn(connection);
+
+    let prepare = client.prepare(
+        "SELECT 'a '::CHAR(4), 'a '::VARCHAR(4), 'a '::TEXT, $1::CHAR(4), $2::VARCHAR(4), $3::TEXT",
+    );
+    let stmt = runtime.block_on(prepare).unwrap();
+    let query = client.query(&stmt, &[&"b ", &"b ", &"b "]).collect();
+    let rows = runtime.block_on(query).unwrap();
+
+    assert_eq!(rows[0].get::<_, &str>(0), "a   ");
+    assert_eq!(rows[0].get::<_, &str>(1), "a ");
+    assert_eq!(rows[0].get::<_, &str>(2), "a ");
+    assert_eq!(rows[0].get::<_, String>(3), "b   ");
+    assert_eq!(rows[0].get::<_, String>(4), "b ");
+    assert_eq!(rows[0].get::<_, String>(5), "b ");
+}
+
+#[test]
+fn test_citext_params() {
+    let mut runtime = Runtime::new().unwrap();
+
+    let handshake = connect("user=postgres");
+    let (mut client, connection) = runtime.block_on(handshake).unwrap();
+    let connection = connection.map_err(|e| panic!("{}", e));
+    runtime.spawn(connection);
+
+    let batch = client
+        .simple_query(
+            "CREATE TEMPORARY TABLE foo (
+                id SERIAL PRIMARY KEY,
+                b CITEXT
+            )",
+        )
+        .for_each(|_| Ok(()));
+    runtime.block_on(batch).unwrap();
+
+    let prepare = client.prepare("INSERT INTO foo (b) VALUES ($1), ($2), ($3)");
+    let stmt = runtime.block_on(prepare).unwrap();
+    let execute = client.execute(&stmt, &[&"foobar", &"FooBar", &None::<&'static str>]);
+    runtime.block_on(execute).unwrap();
+
+    let prepare = client.prepare("SELECT b FROM foo WHERE b = 'FOOBAR' ORDER BY id");
+    let stmt = runtime.block_on(prepare).unwrap();
+    let query = client.query(&stmt, &[]).collect();
+    let res = runtime.block_on(query).unwrap();
+
+    assert_eq!(
+        vec!["foobar".to_string(), "FooBar".to_string()],
+        res.iter()
+            .map(|row| row.get::<_, String>(0))
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn test_bytea_params() {
+    test_type(
+        "BYTEA",
+        &[
+            (Some(vec![0u8, 1, 2, 3, 254, 255]), "'\\x00010203feff'"),
+            (None, "NULL"),
+        ],
+    );
+}
+
+#[test]
+fn bytea_text() {
+    let mut runtime = Runtime::new().unwrap();
+
+    let (mut client, connection) = runtime.block_on(connect("user=postgres")).unwrap();
+    let connection = connection.map_err(|e| panic!("{}", e));
+    runtime.handle().spawn(connection).unwrap();
+
+    for output in &["hex", "escape"] {
+        runtime
+            .block_on(
+                client
+                    .simple_query(&format!("SET bytea_output = '{}'", output))
+                    .for_each(|_| Ok(())),
+            )
+            .unwrap();
+
+        let stmt = runtime
+            .block_on(client.prepare_with_result_formats(
+                "SELECT '\\x00015c27feff'::BYTEA, NULL::BYTEA",
+                &[Format::Text, Format::Text],
+            ))
+            .unwrap();
+        let rows = runtime
+            .block_on(client.query(&stmt, &[]).collect())
+            .unwrap();
+        assert_eq!(rows[0].get::<_, Vec<u8>>(0), [0, 1, b'\\', b'\'', 254, 255]);
+        assert_eq!(rows[0].get::<_, Option<Vec<u8>>>(1), None);
+        assert!(rows[0].try_get::<_, &[u8]>(0).is_err());
+    }
+}
+
+#[test]
+fn test_fixed_bytea_params() {
+    test_type(
+        "BYTEA",
+        &[(Some([0u8, 1, 254, 255]), "'\\x0001feff'"), (None, "NULL")],
+    );
+}
+
+#[test]
+fn fixed_bytea_64() {
+    let mut runtime = Runtime::new().unwrap();
+
+    let handshake = connect("user=postgres");
+    let (mut client, connection) = runtime.block_on(handshake).unwrap();
+    let connection = connection.map_err(|e| panic!("{}", e));
+    runtime.spawn(connection);
+
+    let prepare = client.prepare("SELECT $1::BYTEA");
+    let stmt = runtime.block_on(prepare).unwrap();
+    let query = client.query(&stmt, &[&[7u8; 64]]).collect();
+    let rows = runtime.block_on(query).unwrap();
+    // arrays longer than 32 elements don't implement PartialEq on older compilers
+    assert_eq!(&rows[0].get::<_, [u8; 64]>(0)[..], &[7u8; 64][..]);
+}
+
+#[test]
+fn fixed_bytea_wrong_length() {
+    let mut runtime = Runtime::new().unwrap();
+
+    let handshake = connect("user=postgres");
+    let (mut client, connection) = runtime.block_on(handshake).unwrap();
+    let connection = connection.map_err(|e| panic!("{}", e));
+    runtime.spawn(connection);
+
+    let prepare = client.prepare("SELECT '\\x0001'::BYTEA");
+    let stmt = runtime.block_on(prepare).unwrap();
+    let query = client.query(&stmt, &[]).collect();
+    let rows = runtime.block_on(query).unwrap();
+    assert_eq!(rows[0].get::<_, [u8; 2]>(0), [0, 1]);
+    let err = rows[0].try_get::<_, [u8; 4]>(0).err().unwrap();
+    assert!(
+        err.to_string().contains("expected 4 bytes but got 2"),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn test_borrowed_bytea() {
+    let mut runtime = Runtime::new().unwrap();
+
+    let handshake = connect("user=postgres");
+    let (mut client, connection) = runtime.block_on(handshake).unwrap();
+    let connection = connection.map_err(|e| panic!("{}", e));
+    runtime.spawn(connection);
+
+    let prepare = client.prepare("SELECT 'foo'::BYTEA");
+    let stmt = runtime.block_on(prepare).unwrap();
+    let query = client.query(&stmt, &[]).collect();
+    let rows = runtime.block_on(query).unwrap();
+    let s: &[u8] = rows[0].get(0);
+    assert_eq!(s, b"foo");
+}
+
+#[test]
+fn test_hstore_params() {
+    macro_rules! make_map {
+        ($($k:expr => $v:expr),+) => ({
+            let mut map = HashMap::new();
+            $(map.insert($k, $v);)+
+            map
+        })
+    }
+    test_type(
+        "hstore",
+        &[
+            (
+                Some(make_map!("a".to_owned() => Some("1".to_owned()))),
+                "'a=>1'",
+            ),
+            (
+                Some(make_map!("hello".to_owned() => Some("world!".to_owned()),
+                               "hola".to_owned() => Some("mundo!".to_owned()),
+                               "what".to_owned() => None)),
+                "'hello=>world!,hola=>mundo!,what=>NULL'",
+            ),
+            (None, "NULL"),
+        ],
+    );
+}
+
+#[test]
+fn test_array_params() {
+    test_type(
+        "integer[]",
+        &[
+            (Some(vec![1i32, 2i32]), "ARRAY[1,2]"),
+            (Some(vec![1i32]), "ARRAY[1]"),
+            (Some(vec![]), "ARRAY[]"),
+            (None, "NULL"),
+        ],
+    );
+}
+
+#[test]
+fn test_int2vector_params() {
+    test_type(
+        "INT2VECTOR",
+        &[
+            (Some(vec![1i16, 2, 3]), "'1 2 3'"),
+            (Some(vec![-1i16]), "'-1'"),
+            (None, "NULL"),
+        ],
+    );
+}
+
+#[test]
+fn test_oidvector_params() {
+    test_type(
+        "OIDVECTOR",
+        &[
+            (Some(vec![23u32, 4_000_000_000]), "'23 4000000000'"),
+            (Some(vec![0u32]), "'0'"),
+            (None, "NULL"),
+        ],
+    );
+}
+
+#[test]
+fn index_catalog_vectors() {
+    let mut runtime = Runtime::new().unwrap();
+
+    let (mut client, connection) = runtime.block_on(connect("user=postgres")).unwrap();
+    let connection = connection.map_err(|e| panic!("{}", e));
+    runtime.handle().spawn(connection).unwrap();
+
+    runtime
+        .block_on(
+            client
+                .simple_query(
+                    "CREATE TEMPORARY TABLE foo (a INT, b TEXT, c INT);
+                     CREATE INDEX foo_idx ON foo (c, a)",
+                )
+                .for_each(|_| Ok(())),
+        )
+        .unwrap();
+
+    let stmt = runtime
+        .block_on(client.prepare(
+            "SELECT indkey, indclass FROM pg_index WHERE indexrelid = 'foo_idx'::regclass",
+        ))
+        .unwrap();
+    let rows = runtime
+        .block_on(client.query(&stmt, &[]).collect())
+        .unwrap();
+    assert_eq!(rows[0].get::<_, Vec<i16>>(0), vec![3, 1]);
+    assert_eq!(rows[0].get::<_, Vec<u32>>(1).len(), 2);
+}
+
+#[test]
+fn test_nullable_array_params() {
+    test_type(
+        "integer[]",
+        &[
+            (Some(vec![Some(1i32), None, Some(3i32)]), "ARRAY[1,NULL,3]"),
+            (Some(vec![None]), "ARRAY[NULL]::integer[]"),
+            (Some(vec![Some(1i32)]), "ARRAY[1]"),
+            (None, "NULL"),
+        ],
+    );
+}
+
+#[test]
+fn test_bytea_array_params() {
+    test_type(
+        "BYTEA[]",
+        &[
+            (
+                Some(vec![vec![0u8, 1, 2], vec![], vec![254, 255]]),
+                "ARRAY['\\x000102', '\\x', '\\xfeff']::BYTEA[]",
+            ),
+            (Some(vec![]), "ARRAY[]::BYTEA[]"),
+            (None, "NULL"),
+        ],
+    );
+    test_type(
+        "BYTEA[]",
         &[
-            (Some("hello world".to_owned()), "'hello world'"),
             (
-                Some("イロハニホヘト チリヌルヲ".to_owned()),
-                "'イロハニホヘト チリヌルヲ'",
+                Some(vec![Some(vec![0u8, 1]), None, Some(vec![])]),
+                "ARRAY['\\x0001', NULL, '\\x']::BYTEA[]",
             ),
             (None, "NULL"),
         ],
@@ -173,22 +1000,26 @@ fn test_varchar_params() {
 }
 
 #[test]
-fn test_text_params() {
+fn test_char_array_params() {
     test_type(
-        "TEXT",
+        "\"char\"[]",
         &[
-            (Some("hello world".to_owned()), "'hello world'"),
             (
-                Some("イロハニホヘト チリヌルヲ".to_owned()),
-                "'イロハニホヘト チリヌルヲ'",
+                Some(vec!['i' as i8, 'o' as i8, 'b' as i8]),
+                "ARRAY['i', 'o', 'b']::\"char\"[]",
             ),
+            (Some(vec![]), "ARRAY[]::\"char\"[]"),
             (None, "NULL"),
         ],
     );
+
+    assert!(<Vec<i8> as FromSql>::accepts(&Type::CHAR_ARRAY));
+    assert!(!<Vec<i8> as FromSql>::accepts(&Type::BPCHAR_ARRAY));
+    assert!(!<Vec<i8> as FromSql>::accepts(&Type::TEXT_ARRAY));
 }
 
 #[test]
-fn test_borrowed_text() {
+fn void() {
     let mut runtime = Runtime::new().unwrap();
 
     let handshake = connect("user=postgres");
@@ -196,16 +1027,19 @@ fn test_borrowed_text() {
     let connection = connection.map_err(|e| panic!("{}", e));
     runtime.spawn(connection);
 
-    let prepare = client.prepare("SELECT 'foo'");
+    let prepare = client.prepare("SELECT pg_sleep(0), NULL::VOID");
     let stmt = runtime.block_on(prepare).unwrap();
     let query = client.query(&stmt, &[]).collect();
     let rows = runtime.block_on(query).unwrap();
-    let s: &str = rows[0].get(0);
-    assert_eq!(s, "foo");
+
+    assert_eq!(stmt.columns()[0].type_(), &Type::VOID);
+    rows[0].get::<_, ()>(0);
+    assert_eq!(rows[0].get::<_, Option<()>>(1), None);
+    assert!(rows[0].try_get::<_, ()>(1).is_err());
 }
 
 #[test]
-fn test_bpchar_params() {
+fn was_null_column() {
     let mut runtime = Runtime::new().unwrap();
 
     let handshake = connect("user=postgres");
@@ -213,34 +1047,18 @@ fn test_bpchar_params() {
     let connection = connection.map_err(|e| panic!("{}", e));
     runtime.spawn(connection);
 
-    let batch = client
-        .simple_query(
-            "CREATE TEMPORARY TABLE foo (
-                id SERIAL PRIMARY KEY,
-                b CHAR(5)
-            )",
-        )
-        .for_each(|_| Ok(()));
-    runtime.block_on(batch).unwrap();
-
-    let prepare = client.prepare("INSERT INTO foo (b) VALUES ($1), ($2), ($3)");
-    let stmt = runtime.block_on(prepare).unwrap();
-    let execute = client.execute(&stmt, &[&"12345", &"123", &None::<&'static str>]);
-    runtime.block_on(execute).unwrap();
-
-    let prepare = client.prepare("SELECT b FROM foo ORDER BY id");
+    let prepare = client.prepare("SELECT 1::INT4 AS id, NULL::INT4 AS age");
     let stmt = runtime.block_on(prepare).unwrap();
     let query = client.query(&stmt, &[]).collect();
-    let res = runtime.block_on(query).unwrap();
+    let rows = runtime.block_on(query).unwrap();
 
-    assert_eq!(
-        vec![Some("12345".to_owned()), Some("123  ".to_owned()), None],
-        res.iter().map(|row| row.get(0)).collect::<Vec<_>>()
-    );
+    let err = rows[0].try_get::<_, i32>("age").err().unwrap();
+    assert!(err.source().unwrap().is::<WasNull>(), "{}", err);
+    assert!(err.to_string().contains("column 1 (`age`)"), "{}", err);
 }
 
 #[test]
-fn test_citext_params() {
+fn sets() {
     let mut runtime = Runtime::new().unwrap();
 
     let handshake = connect("user=postgres");
@@ -248,47 +1066,74 @@ fn test_citext_params() {
     let connection = connection.map_err(|e| panic!("{}", e));
     runtime.spawn(connection);
 
-    let batch = client
-        .simple_query(
-            "CREATE TEMPORARY TABLE foo (
-                id SERIAL PRIMARY KEY,
-                b CITEXT
-            )",
-        )
-        .for_each(|_| Ok(()));
-    runtime.block_on(batch).unwrap();
-
-    let prepare = client.prepare("INSERT INTO foo (b) VALUES ($1), ($2), ($3)");
+    let prepare = client.prepare(
+        "SELECT ARRAY['b', 'a', 'b'], ARRAY[]::TEXT[], ARRAY['a', NULL], ARRAY[['a'], ['b']]",
+    );
     let stmt = runtime.block_on(prepare).unwrap();
-    let execute = client.execute(&stmt, &[&"foobar", &"FooBar", &None::<&'static str>]);
-    runtime.block_on(execute).unwrap();
+    let query = client.query(&stmt, &[]).collect();
+    let rows = runtime.block_on(query).unwrap();
 
-    let prepare = client.prepare("SELECT b FROM foo WHERE b = 'FOOBAR' ORDER BY id");
+    let set = rows[0].get::<_, BTreeSet<String>>(0);
+    assert_eq!(set.into_iter().collect::<Vec<_>>(), ["a", "b"]);
+    let set = rows[0].get::<_, HashSet<&str>>(0);
+    assert_eq!(set.len(), 2);
+    assert!(set.contains("a") && set.contains("b"));
+    assert!(rows[0].get::<_, HashSet<String>>(1).is_empty());
+
+    let err = rows[0].try_get::<_, HashSet<String>>(2).err().unwrap();
+    assert!(err.source().unwrap().is::<WasNull>(), "{}", err);
+    let set = rows[0].get::<_, BTreeSet<Option<String>>>(2);
+    assert_eq!(set.len(), 2);
+
+    assert!(rows[0].try_get::<_, BTreeSet<String>>(3).is_err());
+}
+
+#[test]
+fn record() {
+    let mut runtime = Runtime::new().unwrap();
+
+    let handshake = connect("user=postgres");
+    let (mut client, connection) = runtime.block_on(handshake).unwrap();
+    let connection = connection.map_err(|e| panic!("{}", e));
+    runtime.spawn(connection);
+
+    let prepare = client.prepare("SELECT ROW(1, 'a'::TEXT, true, NULL::INT4), ROW()");
     let stmt = runtime.block_on(prepare).unwrap();
     let query = client.query(&stmt, &[]).collect();
-    let res = runtime.block_on(query).unwrap();
+    let rows = runtime.block_on(query).unwrap();
 
-    assert_eq!(
-        vec!["foobar".to_string(), "FooBar".to_string()],
-        res.iter()
-            .map(|row| row.get::<_, String>(0))
-            .collect::<Vec<_>>()
-    );
+    let record = rows[0].get::<_, Record<'_>>(0);
+    assert_eq!(record.len(), 4);
+    assert_eq!(record.oid(0), Type::INT4.oid());
+    assert_eq!(record.get::<i32>(0), 1);
+    assert_eq!(record.get::<&str>(1), "a");
+    assert!(record.get::<bool>(2));
+    assert!(record.is_null(3));
+    assert_eq!(record.get::<Option<i32>>(3), None);
+    assert!(record.try_get::<i32>(3).is_err());
+    assert!(record.try_get::<String>(0).is_err());
+    assert!(record.try_get::<i32>(4).is_err());
+
+    assert!(rows[0].get::<_, Record<'_>>(1).is_empty());
 }
 
 #[test]
-fn test_bytea_params() {
+fn test_bool_array_params() {
     test_type(
-        "BYTEA",
+        "BOOL[]",
         &[
-            (Some(vec![0u8, 1, 2, 3, 254, 255]), "'\\x00010203feff'"),
+            (
+                Some(vec![Some(true), None, Some(false), Some(true)]),
+                "ARRAY[true, NULL, false, true]",
+            ),
+            (Some(vec![]), "ARRAY[]::BOOL[]"),
             (None, "NULL"),
         ],
     );
 }
 
 #[test]
-fn test_borrowed_bytea() {
+fn fixed_width_arrays() {
     let mut runtime = Runtime::new().unwrap();
 
     let handshake = connect("user=postgres");
@@ -296,52 +1141,147 @@ fn test_borrowed_bytea() {
     let connection = connection.map_err(|e| panic!("{}", e));
     runtime.spawn(connection);
 
-    let prepare = client.prepare("SELECT 'foo'::BYTEA");
+    let prepare = client.prepare(
+        "SELECT ARRAY[1, -2]::\"char\"[], ARRAY[1, -2]::INT2[], ARRAY[1, -2]::INT4[], ARRAY[1, 4294967295]::OID[], \
+         ARRAY[1, -2]::INT8[], ARRAY[1.5, -2]::FLOAT4[], ARRAY[1.5, -2]::FLOAT8[], ARRAY[true, false], \
+         ARRAY[1, NULL]::INT4[], ARRAY[1.5, NULL]::FLOAT8[], ARRAY[NULL, true]",
+    );
     let stmt = runtime.block_on(prepare).unwrap();
     let query = client.query(&stmt, &[]).collect();
     let rows = runtime.block_on(query).unwrap();
-    let s: &[u8] = rows[0].get(0);
-    assert_eq!(s, b"foo");
+
+    assert_eq!(rows[0].get::<_, Vec<i8>>(0), [1, -2]);
+    assert_eq!(rows[0].get::<_, Vec<i16>>(1), [1, -2]);
+    assert_eq!(rows[0].get::<_, Vec<i32>>(2), [1, -2]);
+    assert_eq!(rows[0].get::<_, Vec<u32>>(3), [1, 4_294_967_295]);
+    assert_eq!(rows[0].get::<_, Vec<i64>>(4), [1, -2]);
+    assert_eq!(rows[0].get::<_, Vec<f32>>(5), [1.5, -2.]);
+    assert_eq!(rows[0].get::<_, Vec<f64>>(6), [1.5, -2.]);
+    assert_eq!(rows[0].get::<_, Vec<bool>>(7), [true, false]);
+
+    assert_eq!(rows[0].get::<_, Vec<Option<i32>>>(8), [Some(1), None]);
+    assert_eq!(rows[0].get::<_, Vec<Option<f64>>>(9), [Some(1.5), None]);
+    assert_eq!(rows[0].get::<_, Vec<Option<bool>>>(10), [None, Some(true)]);
+
+    for &idx in &[8, 9, 10] {
+        let err = match idx {
+            8 => rows[0].try_get::<_, Vec<i32>>(idx).err().unwrap(),
+            9 => rows[0].try_get::<_, Vec<f64>>(idx).err().unwrap(),
+            _ => rows[0].try_get::<_, Vec<bool>>(idx).err().unwrap(),
+        };
+        assert!(err.source().unwrap().is::<WasNull>(), "{}", err);
+    }
 }
 
 #[test]
-fn test_hstore_params() {
-    macro_rules! make_map {
-        ($($k:expr => $v:expr),+) => ({
-            let mut map = HashMap::new();
-            $(map.insert($k, $v);)+
-            map
-        })
-    }
+fn test_multidimensional_array_params() {
     test_type(
-        "hstore",
+        "INT[]",
         &[
             (
-                Some(make_map!("a".to_owned() => Some("1".to_owned()))),
-                "'a=>1'",
+                Some(Array::from_parts(
+                    vec![1i32, 2, 3, 4, 5, 6],
+                    vec![
+                        Dimension {
+                            len: 2,
+                            lower_bound: 1,
+                        },
+                        Dimension {
+                            len: 3,
+                            lower_bound: 1,
+                        },
+                    ],
+                )),
+                "ARRAY[[1,2,3],[4,5,6]]",
             ),
             (
-                Some(make_map!("hello".to_owned() => Some("world!".to_owned()),
-                               "hola".to_owned() => Some("mundo!".to_owned()),
-                               "what".to_owned() => None)),
-                "'hello=>world!,hola=>mundo!,what=>NULL'",
+                Some(Array::from_vec(vec![1i32, 2, 3], 2)),
+                "'[2:4]={1,2,3}'",
             ),
+            (Some(Array::from_vec(vec![], 1)), "ARRAY[]::INT[]"),
             (None, "NULL"),
         ],
     );
 }
 
 #[test]
-fn test_array_params() {
-    test_type(
-        "integer[]",
-        &[
-            (Some(vec![1i32, 2i32]), "ARRAY[1,2]"),
-            (Some(vec![1i32]), "ARRAY[1]"),
-            (Some(vec![]), "ARRAY[]"),
-            (None, "NULL"),
+fn array_flatten() {
+    let mut runtime = Runtime::new().unwrap();
+
+    let (mut client, connection) = runtime.block_on(connect("user=postgres")).unwrap();
+    let connection = connection.map_err(|e| panic!("{}", e));
+    runtime.handle().spawn(connection).unwrap();
+
+    let stmt = runtime
+        .block_on(client.prepare("SELECT ARRAY[[1, 2], [3, NULL]], ARRAY[[1, 2], [3, 4]]"))
+        .unwrap();
+    let rows = runtime
+        .block_on(client.query(&stmt, &[]).collect())
+        .unwrap();
+
+    let array = rows[0].get::<_, Array<Option<i32>>>(0);
+    assert_eq!(array.into_inner(), vec![Some(1), Some(2), Some(3), None]);
+    assert!(rows[0].try_get::<_, Array<i32>>(0).is_err());
+
+    let array = rows[0].get::<_, Array<i32>>(1);
+    assert_eq!(array.into_inner().iter().sum::<i32>(), 10);
+}
+
+#[test]
+fn array_iter() {
+    let mut runtime = Runtime::new().unwrap();
+
+    let (mut client, connection) = runtime.block_on(connect("user=postgres")).unwrap();
+    let connection = connection.map_err(|e| panic!("{}", e));
+    runtime.handle().spawn(connection).unwrap();
+
+    let stmt = runtime
+        .block_on(client.prepare("SELECT $1::INT[], 4 = ANY($1), 5 = ANY($1)"))
+        .unwrap();
+    let elements = ArrayIter((1..4).map(|i: i32| i * 2));
+    let rows = runtime
+        .block_on(client.query(&stmt, &[&elements]).collect())
+        .unwrap();
+
+    assert_eq!(rows[0].get::<_, Vec<i32>>(0), vec![2, 4, 6]);
+    assert!(rows[0].get::<_, bool>(1));
+    assert!(!rows[0].get::<_, bool>(2));
+}
+
+#[test]
+fn array_indexing() {
+    let array = Array::from_vec(vec![1i32, 2, 3], 2);
+    assert_eq!(
+        array.dimensions(),
+        &[Dimension {
+            len: 3,
+            lower_bound: 2,
+        }]
+    );
+    assert_eq!(array.get(&[1]), None);
+    assert_eq!(array.get(&[2]), Some(&1));
+    assert_eq!(array.get(&[4]), Some(&3));
+    assert_eq!(array.get(&[5]), None);
+    assert_eq!(array.get(&[2, 1]), None);
+    assert_eq!(array.as_slice(), &[1, 2, 3]);
+
+    let array = Array::from_parts(
+        vec![1i32, 2, 3, 4, 5, 6],
+        vec![
+            Dimension {
+                len: 2,
+                lower_bound: 0,
+            },
+            Dimension {
+                len: 3,
+                lower_bound: -1,
+            },
         ],
     );
+    assert_eq!(array.get(&[0, -1]), Some(&1));
+    assert_eq!(array.get(&[1, 0]), Some(&5));
+    assert_eq!(array.get(&[1, 2]), None);
+    assert_eq!(array.get(&[-2_147_483_648, 0]), None);
 }
 
 #[allow(clippy::eq_op)]
@@ -469,6 +1409,42 @@ fn test_slice_range() {
     };
 }
 
+#[test]
+fn raw() {
+    let mut runtime = Runtime::new().unwrap();
+
+    let handshake = connect("user=postgres");
+    let (mut client, connection) = runtime.block_on(handshake).unwrap();
+    let connection = connection.map_err(|e| panic!("{}", e));
+    runtime.spawn(connection);
+
+    let prepare = client.prepare("SELECT $1::POINT::TEXT, $2::INT4");
+    let stmt = runtime.block_on(prepare).unwrap();
+
+    let point = Raw {
+        type_: Type::POINT,
+        format: Format::Text,
+        bytes: b"(1.5,2)".to_vec(),
+    };
+    let int_bytes = 7i32.to_be_bytes();
+    let int = Raw {
+        type_: Type::INT4,
+        format: Format::Binary,
+        bytes: &int_bytes[..],
+    };
+    let query = client.query(&stmt, &[&point, &int]).collect();
+    let rows = runtime.block_on(query).unwrap();
+    assert_eq!(rows[0].get::<_, &str>(0), "(1.5,2)");
+    assert_eq!(rows[0].get::<_, i32>(1), 7);
+
+    let query = client.query(&stmt, &[&point, &point]).collect();
+    let err = runtime.block_on(query).err().unwrap();
+    match err.source() {
+        Some(e) if e.is::<WrongType>() => {}
+        _ => panic!("Unexpected error {:?}", err),
+    };
+}
+
 #[test]
 fn domain() {
     #[derive(Debug, PartialEq)]
@@ -575,6 +1551,98 @@ fn composite() {
     }
 }
 
+#[test]
+fn composite_array() {
+    #[derive(Debug, PartialEq)]
+    struct Address {
+        street: String,
+        number: Option<i32>,
+    }
+
+    impl<'a> FromSql<'a> for Address {
+        fn from_sql(ty: &Type, raw: &[u8]) -> result::Result<Self, Box<dyn Error + Sync + Send>> {
+            let fields = match *ty.kind() {
+                Kind::Composite(ref fields) => fields,
+                _ => unreachable!(),
+            };
+
+            fn read_i32(buf: &mut &[u8]) -> i32 {
+                let (head, tail) = buf.split_at(4);
+                *buf = tail;
+                i32::from_be_bytes([head[0], head[1], head[2], head[3]])
+            }
+
+            let mut buf = raw;
+            assert_eq!(read_i32(&mut buf) as usize, fields.len());
+            let mut values = vec![];
+            for _ in fields {
+                let _oid = read_i32(&mut buf);
+                let len = read_i32(&mut buf);
+                if len < 0 {
+                    values.push(None);
+                } else {
+                    let (value, tail) = buf.split_at(len as usize);
+                    values.push(Some(value));
+                    buf = tail;
+                }
+            }
+
+            Ok(Address {
+                street: String::from_sql_nullable(fields[0].type_(), values[0])?,
+                number: Option::<i32>::from_sql_nullable(fields[1].type_(), values[1])?,
+            })
+        }
+
+        fn accepts(ty: &Type) -> bool {
+            ty.name() == "address"
+        }
+    }
+
+    let mut runtime = Runtime::new().unwrap();
+
+    let handshake = connect("user=postgres");
+    let (mut client, connection) = runtime.block_on(handshake).unwrap();
+    let connection = connection.map_err(|e| panic!("{}", e));
+    runtime.spawn(connection);
+
+    let batch = client
+        .simple_query(
+            "CREATE TYPE pg_temp.address AS (
+                street TEXT,
+                number INTEGER
+            )",
+        )
+        .for_each(|_| Ok(()));
+    runtime.block_on(batch).unwrap();
+
+    let prepare = client.prepare(
+        "SELECT ARRAY[ROW('Main St', 1), NULL, ROW('Elm St', NULL)]::address[],
+                ARRAY[]::address[]",
+    );
+    let stmt = runtime.block_on(prepare).unwrap();
+    let query = client.query(&stmt, &[]).collect();
+    let rows = runtime.block_on(query).unwrap();
+
+    let addresses: Vec<Option<Address>> = rows[0].get(0);
+    assert_eq!(
+        addresses,
+        vec![
+            Some(Address {
+                street: "Main St".to_owned(),
+                number: Some(1),
+            }),
+            None,
+            Some(Address {
+                street: "Elm St".to_owned(),
+                number: None,
+            }),
+        ]
+    );
+
+    let empty: Vec<Address> = rows[0].get(1);
+    assert_eq!(empty, vec![]);
+}
+
 #[test]
 fn enum_() {
     let mut runtime = Runtime::new().unwrap();
@@ -604,6 +1672,39 @@ fn enum_() {
     }
 }
 
+#[test]
+fn enum_array() {
+    let mut runtime = Runtime::new().unwrap();
+
+    let handshake = connect("user=postgres");
+    let (mut client, connection) = runtime.block_on(handshake).unwrap();
+    let connection = connection.map_err(|e| panic!("{}", e));
+    runtime.spawn(connection);
+
+    let batch = client
+        .simple_query("CREATE TYPE pg_temp.mood AS ENUM ('sad', 'ok', 'happy');")
+        .for_each(|_| Ok(()));
+    runtime.block_on(batch).unwrap();
+
+    let prepare = client
+        .prepare("SELECT 'ok'::mood, ARRAY['happy', 'sad']::mood[], ARRAY['ok', NULL]::mood[]");
+    let stmt = runtime.block_on(prepare).unwrap();
+    match *stmt.columns()[1].type_().kind() {
+        Kind::Array(ref member) => assert_eq!(member.name(), "mood"),
+        _ => panic!("bad type"),
+    }
+    let query = client.query(&stmt, &[]).collect();
+    let rows = runtime.block_on(query).unwrap();
+
+    assert_eq!(rows[0].get::<_, &str>(0), "ok");
+    assert_eq!(rows[0].get::<_, Vec<String>>(1), ["happy", "sad"]);
+    assert_eq!(
+        rows[0].get::<_, Vec<Option<String>>>(2),
+        [Some("ok".to_string()), None]
+    );
+    assert!(rows[0].try_get::<_, Vec<String>>(2).is_err());
+}
+
 #[test]
 fn system_time() {
     test_type(
@@ -655,3 +1756,177 @@ fn inet() {
         ],
     );
 }
+
+#[test]
+fn inet_array() {
+    test_type(
+        "INET[]",
+        &[
+            (
+                Some(vec![
+                    "127.0.0.1".parse::<IpAddr>().unwrap(),
+                    "::1".parse::<IpAddr>().unwrap(),
+                ]),
+                "ARRAY['127.0.0.1', '::1']::INET[]",
+            ),
+            (
+                Some(vec!["127.0.0.1".parse::<IpAddr>().unwrap()]),
+                "ARRAY['127.0.0.1']::INET[]",
+            ),
+            (Some(vec![]), "ARRAY[]::INET[]"),
+            (None, "NULL"),
+        ],
+    );
+}
+
+#[test]
+fn aclitem() {
+    let mut runtime = Runtime::new().unwrap();
+
+    let (mut client, connection) = runtime.block_on(connect("user=postgres")).unwrap();
+    let connection = connection.map_err(|e| panic!("{}", e));
+    runtime.handle().spawn(connection).unwrap();
+
+    runtime
+        .block_on(
+            client
+                .simple_query(
+                    r#"BEGIN;
+                       CREATE ROLE "Foo Bar";
+                       CREATE TEMPORARY TABLE foo (id INT);
+                       GRANT SELECT ON foo TO "Foo Bar";"#,
+                )
+                .for_each(|_| Ok(())),
+        )
+        .unwrap();
+
+    let stmt =
+        runtime
+            .block_on(client.prepare(
+                "SELECT relacl, relacl[1], 1::INT FROM pg_class WHERE oid = 'foo'::regclass",
+            ))
+            .unwrap();
+    let rows = runtime
+        .block_on(client.query(&stmt, &[]).collect())
+        .unwrap();
+
+    assert_eq!(
+        rows[0].get::<_, TextArray>(0),
+        TextArray(vec![
+            Some("postgres=arwdDxt/postgres".to_string()),
+            Some(r#""Foo Bar"=r/postgres"#.to_string()),
+        ])
+    );
+    assert_eq!(rows[0].get::<_, &str>(1), "postgres=arwdDxt/postgres");
+    assert_eq!(rows[0].get::<_, i32>(2), 1);
+    assert!(rows[0].try_get::<_, Vec<String>>(0).is_err());
+
+    let stmt = runtime
+        .block_on(client.prepare_with_result_formats(
+            "SELECT ARRAY[1, NULL]::INT4[], ARRAY[1, NULL]::INT4[]",
+            &[Format::Text],
+        ))
+        .unwrap();
+    let rows = runtime
+        .block_on(client.query(&stmt, &[]).collect())
+        .unwrap();
+    assert_eq!(
+        rows[0].get::<_, TextArray>(0),
+        TextArray(vec![Some("1".to_string()), None])
+    );
+    assert!(rows[0].try_get::<_, Vec<Option<i32>>>(0).is_err());
+    assert!(rows[0].try_get::<_, TextArray>(1).is_err());
+    assert_eq!(rows[0].get::<_, Vec<Option<i32>>>(1), [Some(1), None]);
+
+    runtime
+        .block_on(client.simple_query("ROLLBACK").for_each(|_| Ok(())))
+        .unwrap();
+}
+
+#[test]
+fn text_format_columns() {
+    let mut runtime = Runtime::new().unwrap();
+
+    let (mut client, connection) = runtime.block_on(connect("user=postgres")).unwrap();
+    let connection = connection.map_err(|e| panic!("{}", e));
+    runtime.handle().spawn(connection).unwrap();
+
+    let stmt = runtime
+        .block_on(client.prepare("SELECT '(1,2)'::POINT, 'foo'::TEXT"))
+        .unwrap();
+    assert_eq!(stmt.columns()[0].format(), Format::Binary);
+    assert_eq!(stmt.columns()[1].format(), Format::Binary);
+
+    let stmt = runtime
+        .block_on(client.prepare_with_result_formats(
+            "SELECT '(1,2)'::POINT, 1::INT, 'foo'::TEXT, NULL::TEXT, 2::INT",
+            &[Format::Text, Format::Text, Format::Text, Format::Text],
+        ))
+        .unwrap();
+    let formats = stmt
+        .columns()
+        .iter()
+        .map(|c| c.format())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        formats,
+        [
+            Format::Text,
+            Format::Text,
+            Format::Text,
+            Format::Text,
+            Format::Binary
+        ]
+    );
+
+    let rows = runtime
+        .block_on(client.query(&stmt, &[]).collect())
+        .unwrap();
+    assert_eq!(rows[0].get::<_, &str>(0), "(1,2)");
+    assert_eq!(rows[0].get::<_, String>(1), "1");
+    assert!(rows[0].try_get::<_, i32>(1).is_err());
+    assert_eq!(rows[0].get::<_, &str>(2), "foo");
+    assert_eq!(
+        rows[0].get::<_, EncodedText>(2),
+        EncodedText(b"foo".to_vec())
+    );
+    assert_eq!(rows[0].get::<_, Option<String>>(3), None);
+    assert_eq!(rows[0].get::<_, i32>(4), 2);
+}
+
+#[test]
+fn boxed_params() {
+    let mut runtime = Runtime::new().unwrap();
+
+    let (mut client, connection) = runtime.block_on(connect("user=postgres")).unwrap();
+    let connection = connection.map_err(|e| panic!("{}", e));
+    runtime.handle().spawn(connection).unwrap();
+
+    let params: Vec<Box<dyn ToSql>> = vec![
+        Box::new(1i32),
+        Box::new("foo".to_string()),
+        Box::new(None::<i64>),
+        Box::new(std::rc::Rc::new(2i16)),
+        Box::new(std::sync::Arc::new(vec![3i32])),
+    ];
+    let params = params.iter().map(|p| p as &dyn ToSql).collect::<Vec<_>>();
+
+    let stmt = runtime
+        .block_on(client.prepare("SELECT $1::INT, $2::TEXT, $3::BIGINT, $4::SMALLINT, $5::INT[]"))
+        .unwrap();
+    let rows = runtime
+        .block_on(client.query(&stmt, &params).collect())
+        .unwrap();
+
+    assert_eq!(rows[0].get::<_, i32>(0), 1);
+    assert_eq!(rows[0].get::<_, &str>(1), "foo");
+    assert_eq!(rows[0].get::<_, Option<i64>>(2), None);
+    assert_eq!(rows[0].get::<_, i16>(3), 2);
+    assert_eq!(rows[0].get::<_, Vec<i32>>(4), vec![3]);
+
+    let boxed: Box<dyn ToSql> = Box::new("foo");
+    let stmt = runtime.block_on(client.prepare("SELECT $1::INT")).unwrap();
+    assert!(runtime
+        .block_on(client.query(&stmt, &[&boxed]).collect())
+        .is_err());
+}
@@ -0,0 +1,40 @@
+use futures::{Future, Stream};
+use ndarray_012::{arr1, arr2, Array1, Array2};
+use tokio::runtime::current_thread::Runtime;
+
+use crate::connect;
+
+#[test]
+fn ndarray() {
+    let mut runtime = Runtime::new().unwrap();
+
+    let (mut client, connection) = runtime.block_on(connect("user=postgres")).unwrap();
+    let connection = connection.map_err(|e| panic!("{}", e));
+    runtime.handle().spawn(connection).unwrap();
+
+    let stmt = runtime
+        .block_on(client.prepare(
+            "SELECT '{1.5,2,3}'::FLOAT8[], '{{1,2,3},{4,5,6}}'::FLOAT8[], '{}'::FLOAT8[], '{1,NULL}'::INT4[]",
+        ))
+        .unwrap();
+    let rows = runtime
+        .block_on(client.query(&stmt, &[]).collect())
+        .unwrap();
+
+    assert_eq!(rows[0].get::<_, Array1<f64>>(0), arr1(&[1.5, 2., 3.]));
+    assert_eq!(
+        rows[0].get::<_, Array2<f64>>(1),
+        arr2(&[[1., 2., 3.], [4., 5., 6.]])
+    );
+    assert_eq!(rows[0].get::<_, Array1<f64>>(2), Array1::<f64>::zeros(0));
+    assert_eq!(rows[0].get::<_, Array2<f64>>(2).shape(), &[0, 0]);
+    assert_eq!(
+        rows[0].get::<_, Array1<Option<i32>>>(3),
+        arr1(&[Some(1), None])
+    );
+
+    assert!(rows[0].try_get::<_, Array2<f64>>(0).is_err());
+    assert!(rows[0].try_get::<_, Array1<f64>>(1).is_err());
+    assert!(rows[0].try_get::<_, Array1<i32>>(3).is_err());
+    assert!(rows[0].try_get::<_, Array1<i32>>(0).is_err());
+}
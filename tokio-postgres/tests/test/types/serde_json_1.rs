@@ -1,5 +1,11 @@
+use futures::{Future, Stream};
+use serde_json_1::value::RawValue;
 use serde_json_1::Value;
+use std::collections::HashMap;
+use tokio::runtime::current_thread::Runtime;
+use tokio_postgres::types::Json;
 
+use crate::connect;
 use crate::types::test_type;
 
 #[test]
@@ -15,6 +21,7 @@ fn test_json_params() {
                 Some(serde_json_1::from_str::<Value>("{\"f\": \"asd\"}").unwrap()),
                 "'{\"f\": \"asd\"}'",
             ),
+            (Some(Value::Null), "'null'"),
             (None, "NULL"),
         ],
     )
@@ -33,7 +40,99 @@ fn test_jsonb_params() {
                 Some(serde_json_1::from_str::<Value>("{\"f\": \"asd\"}").unwrap()),
                 "'{\"f\": \"asd\"}'",
             ),
+            (Some(Value::Null), "'null'"),
             (None, "NULL"),
         ],
     )
 }
+
+#[test]
+fn get_json() {
+    let mut runtime = Runtime::new().unwrap();
+
+    let handshake = connect("user=postgres");
+    let (mut client, connection) = runtime.block_on(handshake).unwrap();
+    let connection = connection.map_err(|e| panic!("{}", e));
+    runtime.spawn(connection);
+
+    let prepare = client
+        .prepare("SELECT '{\"x\": 1, \"y\": 2}'::JSON AS point, '[3, 4]'::JSONB, '\"foo\"'::JSONB");
+    let stmt = runtime.block_on(prepare).unwrap();
+    let rows = runtime
+        .block_on(client.query(&stmt, &[]).collect())
+        .unwrap();
+
+    let point = rows[0].get_json::<_, HashMap<&str, i32>>("point").unwrap();
+    assert_eq!(point["x"], 1);
+    assert_eq!(point["y"], 2);
+    assert_eq!(rows[0].get_json::<_, Vec<i32>>(1).unwrap(), [3, 4]);
+    assert_eq!(rows[0].get_json::<_, &str>(2).unwrap(), "foo");
+    assert!(rows[0].get_json::<_, Vec<i32>>(2).is_err());
+}
+
+#[test]
+fn test_json_compact() {
+    let mut runtime = Runtime::new().unwrap();
+
+    let handshake = connect("user=postgres");
+    let (mut client, connection) = runtime.block_on(handshake).unwrap();
+    let connection = connection.map_err(|e| panic!("{}", e));
+    runtime.spawn(connection);
+
+    let value = serde_json_1::from_str::<Value>("{ \"a\" : [1, 2, null] }").unwrap();
+
+    let prepare = client.prepare("SELECT $1::JSON::TEXT, $2::JSON IS NULL");
+    let stmt = runtime.block_on(prepare).unwrap();
+    let query = client.query(&stmt, &[&value, &Value::Null]).collect();
+    let rows = runtime.block_on(query).unwrap();
+    assert_eq!(rows[0].get::<_, &str>(0), "{\"a\":[1,2,null]}");
+    assert!(!rows[0].get::<_, bool>(1));
+}
+
+#[test]
+fn test_json_map() {
+    let mut runtime = Runtime::new().unwrap();
+
+    let handshake = connect("user=postgres");
+    let (mut client, connection) = runtime.block_on(handshake).unwrap();
+    let connection = connection.map_err(|e| panic!("{}", e));
+    runtime.spawn(connection);
+
+    let mut map = HashMap::new();
+    map.insert("a".to_string(), "foo".to_string());
+    map.insert("b".to_string(), "bar".to_string());
+
+    let prepare =
+        client.prepare("SELECT $1::JSONB, $1::JSONB->>'b', '{\"a\": 1}'::JSONB, '[\"a\"]'::JSONB");
+    let stmt = runtime.block_on(prepare).unwrap();
+    let query = client.query(&stmt, &[&Json(&map)]).collect();
+    let rows = runtime.block_on(query).unwrap();
+
+    assert_eq!(rows[0].get::<_, Json<HashMap<String, String>>>(0).0, map);
+    assert_eq!(rows[0].get::<_, &str>(1), "bar");
+    assert!(rows[0]
+        .try_get::<_, Json<HashMap<String, String>>>(2)
+        .is_err());
+    assert!(rows[0]
+        .try_get::<_, Json<HashMap<String, String>>>(3)
+        .is_err());
+}
+
+#[test]
+fn raw_value() {
+    let mut runtime = Runtime::new().unwrap();
+
+    let handshake = connect("user=postgres");
+    let (mut client, connection) = runtime.block_on(handshake).unwrap();
+    let connection = connection.map_err(|e| panic!("{}", e));
+    runtime.spawn(connection);
+
+    let prepare = client.prepare("SELECT '{\"a\":  [1, 2]}'::JSON, '{\"a\": [1, 2]}'::JSONB");
+    let stmt = runtime.block_on(prepare).unwrap();
+    let rows = runtime
+        .block_on(client.query(&stmt, &[]).collect())
+        .unwrap();
+
+    assert_eq!(rows[0].get::<_, &RawValue>(0).get(), "{\"a\":  [1, 2]}");
+    assert_eq!(rows[0].get::<_, &RawValue>(1).get(), "{\"a\": [1, 2]}");
+}
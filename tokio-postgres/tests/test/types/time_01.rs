@@ -0,0 +1,57 @@
+use futures::{Future, Stream};
+use time_01::Duration;
+use tokio::runtime::current_thread::Runtime;
+
+use crate::connect;
+use crate::types::test_type;
+
+#[test]
+fn test_duration_params() {
+    test_type(
+        "INTERVAL",
+        &[
+            (Some(Duration::microseconds(1_500_000)), "'1.5 seconds'"),
+            (Some(Duration::hours(-36)), "'-36 hours'"),
+            (Some(Duration::days(3)), "'3 days'"),
+            (Some(Duration::zero()), "'0'"),
+            (None, "NULL"),
+        ],
+    );
+}
+
+#[test]
+fn test_duration_array_params() {
+    test_type(
+        "INTERVAL[]",
+        &[
+            (
+                Some(vec![
+                    Some(Duration::microseconds(1_500_000)),
+                    None,
+                    Some(Duration::days(-2)),
+                ]),
+                "ARRAY['1.5 seconds', NULL, '-2 days']::INTERVAL[]",
+            ),
+            (Some(vec![]), "ARRAY[]::INTERVAL[]"),
+            (None, "NULL"),
+        ],
+    );
+}
+
+#[test]
+fn duration_months() {
+    let mut runtime = Runtime::new().unwrap();
+
+    let (mut client, connection) = runtime.block_on(connect("user=postgres")).unwrap();
+    let connection = connection.map_err(|e| panic!("{}", e));
+    runtime.spawn(connection);
+
+    let stmt = runtime
+        .block_on(client.prepare("SELECT '1 month'::INTERVAL"))
+        .unwrap();
+    let rows = runtime
+        .block_on(client.query(&stmt, &[]).collect())
+        .unwrap();
+    let err = rows[0].try_get::<_, Duration>(0).err().unwrap();
+    assert!(err.to_string().contains("months"), "{}", err);
+}
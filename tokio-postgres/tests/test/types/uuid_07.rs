@@ -15,3 +15,25 @@ fn test_uuid_params() {
         ],
     )
 }
+
+#[test]
+fn test_uuid_array_params() {
+    test_type(
+        "UUID[]",
+        &[
+            (
+                Some(vec![
+                    Uuid::parse_str("a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a11").unwrap(),
+                    Uuid::nil(),
+                ]),
+                "ARRAY['a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a11', '00000000-0000-0000-0000-000000000000']::UUID[]",
+            ),
+            (
+                Some(vec![Uuid::parse_str("a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a11").unwrap()]),
+                "ARRAY['a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a11']::UUID[]",
+            ),
+            (Some(vec![]), "ARRAY[]::UUID[]"),
+            (None, "NULL"),
+        ],
+    )
+}